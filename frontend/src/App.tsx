@@ -7,6 +7,8 @@ import './App.css';
 
 function App() {
   const [settings, setSettings] = useState<Settings | null>(null);
+  const [needsWizard, setNeedsWizard] = useState(true);
+  const [safeMode, setSafeMode] = useState(false);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
@@ -18,8 +20,11 @@ function App() {
     setIsLoading(true);
     setError(null);
     try {
+      setSafeMode(await invoke<boolean>('is_safe_mode'));
       const loadedSettings = await invoke<Settings>('load_settings');
       setSettings(loadedSettings);
+      // Also re-prompts setups completed by a wizard that predates a required step
+      setNeedsWizard(await invoke<boolean>('needs_wizard'));
     } catch (err) {
       console.error('Failed to load settings:', err);
       setError(err as string);
@@ -47,8 +52,21 @@ function App() {
     );
   }
 
+  // Safe mode only offers repair: re-running setup and diagnostics
+  if (safeMode) {
+    return (
+      <>
+        <div className="safe-mode-banner">
+          Safe mode: automatic changes are disabled and only setup and diagnostics are available.
+          Restart normally once the configuration is fixed.
+        </div>
+        <Wizard />
+      </>
+    );
+  }
+
   // Show wizard if not completed
-  if (!settings?.wizard.completed) {
+  if (!settings || needsWizard) {
     return <Wizard />;
   }
 
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import type { DriveInfo } from './Wizard';
 import './ProfileManager.css';
 
 export interface ProfileInfo {
@@ -19,8 +20,16 @@ interface VirtualNode {
   children?: VirtualNode[];
   size?: number;
   modified?: string;
-  source: 'Base' | 'Workspace' | 'Override';
+  source: 'Base' | 'Workspace' | 'Override' | 'Shared';
   writable: boolean;
+  stats?: {
+    file_count: number;
+    total_bytes: number;
+    base_files: number;
+    workspace_files: number;
+    override_files: number;
+    shared_files?: number;
+  } | null;
 }
 
 interface ProfileManagerProps {
@@ -36,6 +45,7 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [notification, setNotification] = useState<string | null>(null);
+  const [lowSpaceWarning, setLowSpaceWarning] = useState<string | null>(null);
   const [isBuilding, setIsBuilding] = useState(false);
   const [buildProgress, setBuildProgress] = useState<any>(null);
 
@@ -44,9 +54,29 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
     setupEventListeners();
   }, []);
 
+  // Poll the data root's free space, warning when it can't hold what a runtime build copies
+  useEffect(() => {
+    const checkFreeSpace = () => {
+      invoke<DriveInfo>('get_data_root_drive_info')
+        .then(info => setLowSpaceWarning(info.low_space_warning ?? null))
+        .catch(err => console.error('Failed to check free space:', err));
+    };
+    checkFreeSpace();
+    const interval = setInterval(checkFreeSpace, 60000);
+    return () => clearInterval(interval);
+  }, []);
+
   useEffect(() => {
     if (selectedProfile) {
+      invoke('open_profile', { name: selectedProfile })
+        .catch(err => console.error('Failed to open profile:', err));
       loadVirtualTree(selectedProfile);
+
+      // Stop watching the previous profile when switching away from it
+      return () => {
+        invoke('stop_profile_watch', { profileName: selectedProfile })
+          .catch(err => console.error('Failed to stop workspace watch:', err));
+      };
     }
   }, [selectedProfile]);
 
@@ -61,9 +91,16 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
         loadVirtualTree(selectedProfile);
       }
     });
+
+    // The OS watcher couldn't be created, so the workspace is being polled instead
+    const unlistenFallback = await listen<{ profile_name: string; error: string; poll_interval_ms: number }>('watcher-fallback', (event) => {
+      setNotification(`Watching ${event.payload.profile_name} by polling every ${event.payload.poll_interval_ms / 1000}s; changes may take longer to appear`);
+      setTimeout(() => setNotification(null), 5000);
+    });
     
     return () => {
       unlisten();
+      unlistenFallback();
     };
   };
 
@@ -166,15 +203,17 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
         loadVirtualTree(selectedProfile);
       } else if (action === 'delete' && node.source === 'Workspace') {
         if (confirm(`Delete ${node.name}? This cannot be undone.`)) {
-          await invoke('delete_workspace_file', {
+          const outcome = await invoke<'BaseRevealed' | 'Removed'>('delete_workspace_file', {
             profileName: selectedProfile,
             virtualPath: node.path
           });
-          setNotification(`Deleted ${node.name} from workspace`);
+          setNotification(outcome === 'BaseRevealed'
+            ? `Deleted ${node.name} from workspace, original file restored`
+            : `Deleted ${node.name} from workspace`);
           setTimeout(() => setNotification(null), 3000);
           loadVirtualTree(selectedProfile);
         }
-      } else if (action === 'copy_to_workspace' && node.source === 'Base') {
+      } else if (action === 'copy_to_workspace' && (node.source === 'Base' || node.source === 'Shared')) {
         await invoke('copy_to_workspace', {
           profileName: selectedProfile,
           virtualPath: node.path
@@ -251,7 +290,8 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
         profileName: profileName
       });
       console.log('Runtime plan:', plan);
-      setNotification(`Runtime plan computed: ${(plan as any).total_files} files`);
+      const excluded = (plan as any).excluded_files ?? 0;
+      setNotification(`Runtime plan computed: ${(plan as any).total_files} files${excluded > 0 ? ` (${excluded} base files excluded)` : ''}`);
       setTimeout(() => setNotification(null), 3000);
     } catch (err) {
       console.error('Plan computation failed:', err);
@@ -327,7 +367,7 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
                   🔍 Debug
                 </button>
               )}
-              {node.source === 'Base' && (
+              {(node.source === 'Base' || node.source === 'Shared') && (
                 <button 
                   className="action-btn copy"
                   onClick={(e) => {
@@ -381,6 +421,15 @@ function ProfileManager({ onOpenWorkspace }: ProfileManagerProps) {
           <button onClick={() => setError(null)}>×</button>
         </div>
       )}
+
+      {/* Low Space Warning */}
+      {lowSpaceWarning && (
+        <div className="error-banner low-space-banner">
+          <span className="error-icon">💾</span>
+          <span>{lowSpaceWarning}</span>
+          <button onClick={() => setLowSpaceWarning(null)}>×</button>
+        </div>
+      )}
       
       {/* Sidebar */}
       <div className="sidebar">
@@ -31,6 +31,7 @@ export interface DriveInfo {
   free_space_formatted: string;
   is_valid: boolean;
   error_message?: string;
+  low_space_warning?: string;
 }
 
 export interface PathValidation {
@@ -1,11 +1,106 @@
 use std::path::{Path, PathBuf};
 use blake3::{Hash, Hasher};
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
+use walkdir::WalkDir;
+use rayon::prelude::*;
 use log::{warn, debug};
+use crate::path_utils::rel_path_key;
+
+/// Name of the cache metadata file, stored next to index.json
+pub const CACHE_META_FILE: &str = "cache_meta.json";
+
+/// Hash algorithm blobs are addressed by; each algorithm's blobs live under `blobs/<algorithm>/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Directory name used for this algorithm's blobs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Blake3 => "blake3",
+        }
+    }
+}
+
+/// How a single file's content is hashed; the hash is the same either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashMode {
+    /// Hash on the calling thread; best when many files are hashed in parallel already
+    #[default]
+    Single,
+    /// Memory-map the file and hash it across the rayon pool; best for a few huge files
+    Parallel,
+    /// Parallel for files of at least `PARALLEL_HASH_MIN_SIZE`, single-threaded otherwise
+    Auto,
+}
+
+impl HashMode {
+    /// Mode for a `UserPreferences::hash_mode` name (unknown names hash single-threaded)
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "parallel" => Self::Parallel,
+            "auto" => Self::Auto,
+            _ => Self::Single,
+        }
+    }
+}
+
+/// Cache-wide settings stored in cache_meta.json
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CacheMeta {
+    /// Algorithm new blobs are stored and indexed under
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+    /// Random per-cache key for integrity tags (hex), created the first time a blob is tagged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity_key: Option<String>,
+}
+
+/// A failed hardlink, described with both paths while keeping the OS error (and its raw code) as the source
+#[derive(Debug)]
+struct HardlinkError {
+    message: String,
+    source: io::Error,
+}
+
+impl std::fmt::Display for HardlinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for HardlinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Wrap a hardlink failure so callers can still classify it by the original OS error
+pub(crate) fn hardlink_error(src: &Path, dst: &Path, error: io::Error) -> io::Error {
+    let message = format!(
+        "Failed to create hardlink from '{}' to '{}': {}. Ensure cache and workspace are on the same NTFS volume.",
+        src.display(), dst.display(), error
+    );
+    io::Error::new(error.kind(), HardlinkError { message, source: error })
+}
+
+/// Serializes creating the integrity key so concurrent writers can't each generate their own
+static META_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes index updates, so a load-modify-save can't overwrite another and garbage collection
+/// can confirm a blob is still unreferenced right before deleting it
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
 
 /// Represents a blob path in the cache
 #[derive(Debug, Clone)]
@@ -19,31 +114,532 @@ pub struct BlobPath {
 pub struct BlobReference {
     pub profile: String,
     pub rel_path: String,
+    /// Size of the workspace file when the reference was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Modification time of the workspace file when the reference was recorded (ms since the Unix epoch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<i64>,
+    /// Quick fingerprint of the workspace file when the reference was recorded (see `BlobCache::quick_fingerprint`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quick_fingerprint: Option<String>,
+}
+
+impl BlobReference {
+    /// Whether a workspace file's size or mtime differs from what was recorded, without re-hashing
+    /// References from older indexes have no stats and are compared against the blob's current stats instead
+    pub fn is_modified(&self, file_path: &Path, blob_path: &Path) -> io::Result<bool> {
+        let (size, mtime) = file_stat(file_path)?;
+        let (expected_size, expected_mtime) = match (self.size, self.mtime) {
+            (Some(size), Some(mtime)) => (size, mtime),
+            _ => file_stat(blob_path)?,
+        };
+
+        Ok(size != expected_size || mtime != expected_mtime)
+    }
+}
+
+/// Reference paths always use forward slashes, whichever separator the caller used
+fn canonical_rel_path(rel_path: &str) -> String {
+    rel_path.replace('\\', "/")
+}
+
+/// Parse a hex integrity key from cache_meta.json
+fn parse_integrity_key(key: &str) -> io::Result<[u8; 32]> {
+    Hash::from_hex(key)
+        .map(|key| *key.as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid integrity key: {}", e)))
+}
+
+/// Size and modification time (ms since the Unix epoch) of a file
+fn file_stat(path: &Path) -> io::Result<(u64, i64)> {
+    let metadata = fs::metadata(path)?;
+    let mtime = metadata.modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0);
+    Ok((metadata.len(), mtime))
 }
 
 /// Index structure for blob reference tracking
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BlobIndex {
+    /// Algorithm the hash keys were computed with (indexes without one predate the field and are BLAKE3)
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
     pub refs: HashMap<String, Vec<BlobReference>>, // hash -> list of references
+    /// Hashes of blobs kept even when nothing references them
+    #[serde(default)]
+    pub pinned: HashSet<String>,
+    /// Keyed BLAKE3 integrity tag of each tagged blob (hash -> tag)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub integrity_tags: HashMap<String, String>,
+}
+
+impl BlobIndex {
+    /// Number of references to a blob (0 if the blob isn't in the index)
+    pub fn refcount(&self, hash_str: &str) -> usize {
+        self.refs.get(hash_str).map_or(0, |refs| refs.len())
+    }
+
+    /// Whether a blob is pinned against garbage collection
+    pub fn is_pinned(&self, hash_str: &str) -> bool {
+        self.pinned.contains(hash_str)
+    }
+
+    /// Whether garbage collection would delete a blob: it has no references and isn't pinned
+    pub fn is_collectable(&self, hash_str: &str) -> bool {
+        self.refcount(hash_str) == 0 && !self.is_pinned(hash_str)
+    }
+
+    /// Total number of references across all blobs
+    pub fn total_refs(&self) -> usize {
+        self.refs.values().map(|refs| refs.len()).sum()
+    }
+}
+
+/// Read buffer size used when hashing files (1 MB keeps NVMe drives and large archives busy)
+/// Copies go through `fs::copy`, which uses the OS copy routine and its own buffering
+pub const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Files from this size are hashed in parallel in `HashMode::Auto`
+pub const PARALLEL_HASH_MIN_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Read buffer size for parallel hashing, so each read gives the rayon pool enough to split up
+pub const PARALLEL_HASH_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+/// Bytes read from each end of a file for its quick fingerprint
+pub const QUICK_FINGERPRINT_SPAN: u64 = 1024 * 1024;
+
+/// Maximum number of example entries kept per audit category
+const AUDIT_EXAMPLE_LIMIT: usize = 20;
+
+/// A single category of discrepancies found by an audit
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AuditCategory {
+    /// Total number of discrepancies in this category
+    pub count: usize,
+    /// Capped list of example entries
+    pub examples: Vec<String>,
+}
+
+impl AuditCategory {
+    fn record(&mut self, example: String) {
+        self.count += 1;
+        if self.examples.len() < AUDIT_EXAMPLE_LIMIT {
+            self.examples.push(example);
+        }
+    }
+}
+
+/// Result of cross-checking the index against workspaces and blob storage
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AuditReport {
+    /// Total references found in the index
+    pub total_refs: usize,
+    /// Total files found across all profile workspaces
+    pub total_workspace_files: usize,
+    /// Index references with no matching workspace file
+    pub dangling_refs: AuditCategory,
+    /// Workspace files with no matching index reference
+    pub unreferenced_files: AuditCategory,
+    /// Referenced hashes with no blob on disk
+    pub missing_blobs: AuditCategory,
+    /// Referenced workspace files whose size or mtime changed since the reference was recorded
+    #[serde(default)]
+    pub modified_files: AuditCategory,
+}
+
+impl AuditReport {
+    /// Check if the audit found no discrepancies
+    pub fn is_clean(&self) -> bool {
+        self.dangling_refs.count == 0
+            && self.unreferenced_files.count == 0
+            && self.missing_blobs.count == 0
+            && self.modified_files.count == 0
+    }
+}
+
+/// Result of checking stored blobs against their address hash and integrity tag
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IntegrityReport {
+    /// Blobs found in storage
+    pub blobs_checked: usize,
+    /// Blobs stored before integrity tags were enabled, which can only be checked against their address
+    pub untagged: usize,
+    /// Blobs whose content no longer matches their address hash or integrity tag
+    pub tampered: AuditCategory,
+}
+
+impl IntegrityReport {
+    /// Check if every blob matched
+    pub fn is_clean(&self) -> bool {
+        self.tampered.count == 0
+    }
+}
+
+/// Reference count and size of one profile's indexed files
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProfileTotals {
+    pub refs: usize,
+    /// Sum of the recorded file sizes (shared blobs count once per reference)
+    pub bytes: u64,
+}
+
+/// A blob-backed file referenced by a profile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProfileBlobEntry {
+    pub rel_path: String,
+    pub hash: String,
+    /// Size recorded with the reference (None for references that predate recorded sizes)
+    pub size: Option<u64>,
+}
+
+/// Whether a blob is present in the cache, from a batch existence check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlobPresence {
+    pub hash: String,
+    pub exists: bool,
+    /// The hash isn't a valid hash of the cache's algorithm (reported as not present)
+    #[serde(default)]
+    pub malformed: bool,
+}
+
+/// The same override (same blob at the same path) carried by several profiles
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub rel_path: String,
+    /// Profiles referencing the blob at this path, sorted
+    pub profiles: Vec<String>,
+    /// Size recorded with the references (None for references that predate recorded sizes)
+    pub size: Option<u64>,
+}
+
+/// Cache usage computed from the index alone, without walking blob storage
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IndexTotals {
+    /// Blobs with at least one reference
+    pub unique_blobs: usize,
+    /// Size of each referenced blob counted once
+    pub unique_bytes: u64,
+    /// Referenced blobs whose references predate recorded sizes, left out of `unique_bytes`
+    pub unsized_blobs: usize,
+    /// Total references across all blobs
+    pub total_refs: usize,
+    /// Per-profile breakdown for this cache's namespace
+    pub profiles: HashMap<String, ProfileTotals>,
+}
+
+/// Blobs and bytes removed by a garbage collection
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GcSummary {
+    pub blobs_collected: usize,
+    pub bytes_reclaimed: u64,
+}
+
+/// What a garbage collection would reclaim, computed without deleting anything
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GcPreview {
+    pub orphaned_blobs: usize,
+    pub bytes_reclaimable: u64,
+}
+
+/// Options for `maintain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceOptions {
+    /// Remove empty shard directories after collecting garbage
+    #[serde(default = "default_true")]
+    pub compact: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for MaintenanceOptions {
+    fn default() -> Self {
+        Self { compact: true }
+    }
+}
+
+/// Phases of cache maintenance, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaintenancePhase {
+    CollectGarbage,
+    Compact,
+    Complete,
+}
+
+/// Progress of cache maintenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceProgress {
+    pub phase: MaintenancePhase,
+    /// Blobs examined so far by garbage collection
+    pub blobs_scanned: usize,
+    pub bytes_reclaimed: u64,
+}
+
+/// Combined result of garbage collection and compaction
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MaintenanceReport {
+    pub blobs_collected: usize,
+    /// Bytes freed by garbage collection
+    pub gc_bytes_reclaimed: u64,
+    pub gc_time_ms: u64,
+    /// Empty directories removed by compaction
+    pub directories_removed: usize,
+    pub compact_time_ms: u64,
+    pub total_time_ms: u64,
+    /// Whether maintenance was cancelled before finishing; work already done is kept
+    pub cancelled: bool,
+}
+
+/// How many blobs garbage collection examines between progress reports
+const MAINTENANCE_PROGRESS_INTERVAL: usize = 1000;
+
+/// Result of merging another blob cache into this one
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MergeReport {
+    /// Blobs copied because they only existed in the other cache
+    pub blobs_added: usize,
+    /// Total size of the copied blobs
+    pub bytes_copied: u64,
+    /// References added to this cache's index
+    pub refs_merged: usize,
 }
 
 /// Content-addressed blob cache manager
+#[derive(Debug, Clone)]
 pub struct BlobCache {
     pub cache_dir: PathBuf,
+    /// Prefix for the profile names this cache records, so data roots sharing one cache can't collide
+    pub namespace: Option<String>,
+    /// Algorithm blobs are addressed by, read from cache_meta.json
+    pub algorithm: HashAlgorithm,
+    /// Record a keyed integrity tag for each blob stored, so tampering and bit-rot are detected
+    pub integrity_tags: bool,
+    /// Where staging copies of new blobs are written before being moved into place (next to the blob when unset)
+    pub temp_dir: Option<PathBuf>,
+    /// Restore original modification times when normalizing and copying blobs (see `UserPreferences::preserve_mtimes`)
+    pub preserve_mtimes: bool,
+    /// Files at least this large get a quick fingerprint recorded with their reference, so an unchanged file
+    /// isn't fully re-hashed (None disables quick fingerprints)
+    pub quick_fingerprint_min_size: Option<u64>,
+    /// Whether files are hashed single-threaded or across the rayon pool
+    pub hash_mode: HashMode,
 }
 
 impl BlobCache {
     pub fn new<P: AsRef<Path>>(cache_dir: P) -> Self {
+        let cache_dir = cache_dir.as_ref().to_path_buf();
+        let algorithm = match Self::read_meta(&cache_dir) {
+            Ok(meta) => meta.algorithm,
+            Err(e) => {
+                warn!("Failed to read cache metadata, assuming {}: {}", HashAlgorithm::default().as_str(), e);
+                HashAlgorithm::default()
+            }
+        };
+
         Self {
-            cache_dir: cache_dir.as_ref().to_path_buf(),
+            cache_dir,
+            namespace: None,
+            algorithm,
+            integrity_tags: false,
+            temp_dir: None,
+            preserve_mtimes: false,
+            quick_fingerprint_min_size: None,
+            hash_mode: HashMode::default(),
+        }
+    }
+
+    /// Record integrity tags for blobs stored from now on
+    pub fn with_integrity_tags(mut self, enabled: bool) -> Self {
+        self.integrity_tags = enabled;
+        self
+    }
+
+    /// Stage copies of new blobs in `temp_dir` instead of next to the blob
+    pub fn with_temp_dir(mut self, temp_dir: Option<PathBuf>) -> Self {
+        self.temp_dir = temp_dir;
+        self
+    }
+
+    /// Keep original modification times through normalization and blob copies
+    pub fn with_preserve_mtimes(mut self, enabled: bool) -> Self {
+        self.preserve_mtimes = enabled;
+        self
+    }
+
+    /// Record quick fingerprints for files of at least `min_size` bytes (None disables them)
+    pub fn with_quick_fingerprints(mut self, min_size: Option<u64>) -> Self {
+        self.quick_fingerprint_min_size = min_size;
+        self
+    }
+
+    /// Hash files stored from now on in `mode`
+    pub fn with_hash_mode(mut self, mode: HashMode) -> Self {
+        self.hash_mode = mode;
+        self
+    }
+
+    /// Cheap change-detection fingerprint of a file: a hash of its size and its first and last `QUICK_FINGERPRINT_SPAN` bytes
+    /// Only a heuristic: an edit confined to the middle of a file that keeps its size goes unnoticed.
+    /// Blobs are always addressed by the full content hash
+    pub fn quick_fingerprint<P: AsRef<Path>>(file_path: P) -> io::Result<String> {
+        let mut file = fs::File::open(file_path)?;
+        let size = file.metadata()?.len();
+        let mut hasher = Hasher::new();
+        hasher.update(&size.to_le_bytes());
+
+        let span = QUICK_FINGERPRINT_SPAN.min(size);
+        let mut buffer = vec![0u8; span as usize];
+        file.read_exact(&mut buffer)?;
+        hasher.update(&buffer);
+        file.seek(SeekFrom::Start(size - span))?;
+        file.read_exact(&mut buffer)?;
+        hasher.update(&buffer);
+
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    /// Quick fingerprint of a file if it's large enough to get one
+    fn quick_fingerprint_for(&self, file_path: &Path, size: Option<u64>) -> Option<String> {
+        let min_size = self.quick_fingerprint_min_size?;
+        if size? < min_size {
+            return None;
+        }
+        Self::quick_fingerprint(file_path)
+            .map_err(|e| warn!("Failed to fingerprint {}: {}", file_path.display(), e))
+            .ok()
+    }
+
+    /// The blob a workspace file still matches by quick fingerprint, so it can be treated as unchanged without a full hash
+    /// None if quick fingerprints are disabled, the file has no recorded fingerprint, its blob is gone, or the fingerprint differs
+    pub fn unchanged_by_fingerprint(&self, profile: &str, rel_path: &str, file_path: &Path) -> io::Result<Option<BlobPath>> {
+        let Some(min_size) = self.quick_fingerprint_min_size else {
+            return Ok(None);
+        };
+        // Files under the minimum size never get a fingerprint, so there's no need to load the index for them
+        if fs::metadata(file_path)?.len() < min_size {
+            return Ok(None);
+        }
+        let index = self.load_index()?;
+        let profile = self.ref_profile(profile);
+        let rel_path = canonical_rel_path(rel_path);
+        let Some((hash_str, recorded)) = index.refs.iter()
+            .find_map(|(hash_str, refs)| refs.iter().find(|r| r.profile == profile && r.rel_path == rel_path).map(|r| (hash_str, r)))
+        else {
+            return Ok(None);
+        };
+        let Some(recorded_fingerprint) = &recorded.quick_fingerprint else {
+            return Ok(None);
+        };
+
+        let blob_path = self.get_blob_path_from_hash(hash_str)?;
+        if !blob_path.is_file() || Self::quick_fingerprint(file_path)? != *recorded_fingerprint {
+            return Ok(None);
+        }
+        let hash = Hash::from_hex(hash_str)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid hash in index: {}", e)))?;
+        Ok(Some(BlobPath { hash, path: blob_path }))
+    }
+
+    /// Set a file's modification time; on a hardlink this sets it for every link to the same data
+    pub fn set_mtime<P: AsRef<Path>>(path: P, mtime: SystemTime) -> io::Result<()> {
+        fs::File::options().write(true).open(path)?.set_modified(mtime)
+    }
+
+    /// Read cache_meta.json (defaults if the cache has none yet)
+    fn read_meta(cache_dir: &Path) -> io::Result<CacheMeta> {
+        let meta_path = cache_dir.join("blobs").join(CACHE_META_FILE);
+        if !meta_path.exists() {
+            return Ok(CacheMeta::default());
+        }
+
+        let content = fs::read_to_string(meta_path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Load this cache's metadata
+    pub fn load_meta(&self) -> io::Result<CacheMeta> {
+        Self::read_meta(&self.cache_dir)
+    }
+
+    /// Write cache_meta.json recording the active algorithm, unless it already exists
+    pub fn ensure_meta(&self) -> io::Result<()> {
+        let meta_path = self.cache_dir.join("blobs").join(CACHE_META_FILE);
+        if meta_path.exists() {
+            return Ok(());
+        }
+
+        self.save_meta(&CacheMeta { algorithm: self.algorithm, integrity_key: None })
+    }
+
+    fn save_meta(&self, meta: &CacheMeta) -> io::Result<()> {
+        fs::create_dir_all(self.cache_dir.join("blobs"))?;
+        let content = serde_json::to_string_pretty(meta)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(self.cache_dir.join("blobs").join(CACHE_META_FILE), content)
+    }
+
+    /// The cache's integrity key, created and saved to cache_meta.json if it has none yet
+    fn integrity_key(&self) -> io::Result<[u8; 32]> {
+        let _meta_lock = META_LOCK.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        let mut meta = self.load_meta()?;
+        if let Some(key) = &meta.integrity_key {
+            return parse_integrity_key(key);
+        }
+
+        // All 32 bytes straight from the OS random source
+        let mut key = [0u8; 32];
+        getrandom::fill(&mut key).map_err(|e| io::Error::other(format!("Failed to generate integrity key: {}", e)))?;
+        meta.algorithm = self.algorithm;
+        meta.integrity_key = Some(Hash::from_bytes(key).to_hex().to_string());
+        self.save_meta(&meta)?;
+        Ok(key)
+    }
+
+    /// Directory holding the blobs of the active algorithm (`cache/blobs/<algorithm>`)
+    pub fn blobs_dir(&self) -> PathBuf {
+        self.cache_dir.join("blobs").join(self.algorithm.as_str())
+    }
+
+    /// Record references under a namespace (`<namespace>/<profile>` in the index)
+    pub fn with_namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Profile name as stored in the index's references
+    pub fn ref_profile(&self, profile: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, profile),
+            None => profile.to_string(),
+        }
+    }
+
+    /// Profile name of an index reference if it belongs to this cache's namespace
+    /// Profile names can't contain `/`, so un-namespaced caches skip references from namespaced data roots
+    pub fn local_profile<'a>(&self, ref_profile: &'a str) -> Option<&'a str> {
+        match &self.namespace {
+            Some(namespace) => ref_profile.strip_prefix(namespace.as_str())?.strip_prefix('/'),
+            None if ref_profile.contains('/') => None,
+            None => Some(ref_profile),
         }
     }
 
     /// Hash a file using BLAKE3
     pub fn hash_file<P: AsRef<Path>>(file_path: P) -> io::Result<Hash> {
+        Self::hash_file_with_buffer_size(file_path, HASH_BUFFER_SIZE)
+    }
+
+    /// Hash a file using BLAKE3, reading it in chunks of `buffer_size` bytes
+    pub fn hash_file_with_buffer_size<P: AsRef<Path>>(file_path: P, buffer_size: usize) -> io::Result<Hash> {
         let mut file = fs::File::open(file_path)?;
         let mut hasher = Hasher::new();
-        let mut buffer = [0; 8192]; // 8KB buffer for reading
+        // Heap-allocated so large buffers are safe on worker thread stacks
+        let mut buffer = vec![0; buffer_size.max(1)];
 
         loop {
             let bytes_read = file.read(&mut buffer)?;
@@ -56,38 +652,403 @@ impl BlobCache {
         Ok(hasher.finalize())
     }
 
+    /// Hash a file using BLAKE3 across the rayon pool, reading it in large chunks
+    /// Gives the same hash as `hash_file`. Files are read rather than memory-mapped, since a workspace file
+    /// truncated by another program while mapped would crash the app (SIGBUS) instead of failing the read
+    pub fn hash_file_parallel<P: AsRef<Path>>(file_path: P) -> io::Result<Hash> {
+        let mut file = fs::File::open(file_path)?;
+        let mut hasher = Hasher::new();
+        let mut buffer = vec![0; PARALLEL_HASH_BUFFER_SIZE];
+
+        loop {
+            let bytes_read = file.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update_rayon(&buffer[..bytes_read]);
+        }
+
+        Ok(hasher.finalize())
+    }
+
+    /// Hash a file using BLAKE3 in the given mode
+    pub fn hash_file_with_mode<P: AsRef<Path>>(file_path: P, mode: HashMode) -> io::Result<Hash> {
+        let file_path = file_path.as_ref();
+        let parallel = match mode {
+            HashMode::Single => false,
+            HashMode::Parallel => true,
+            HashMode::Auto => fs::metadata(file_path)?.len() >= PARALLEL_HASH_MIN_SIZE,
+        };
+
+        if parallel {
+            Self::hash_file_parallel(file_path)
+        } else {
+            Self::hash_file(file_path)
+        }
+    }
+
+    /// Hash a file in this cache's hash mode
+    pub fn hash<P: AsRef<Path>>(&self, file_path: P) -> io::Result<Hash> {
+        Self::hash_file_with_mode(file_path, self.hash_mode)
+    }
+
+    /// Hash a file and compute its keyed integrity tag in a single read
+    fn hash_file_tagged(file_path: &Path, key: &[u8; 32]) -> io::Result<(Hash, Hash)> {
+        let mut file = fs::File::open(file_path)?;
+        let mut hasher = Hasher::new();
+        let mut tagger = Hasher::new_keyed(key);
+        let mut buffer = vec![0; HASH_BUFFER_SIZE];
+
+        loop {
+            let bytes_read = file.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+            tagger.update(&buffer[..bytes_read]);
+        }
+
+        Ok((hasher.finalize(), tagger.finalize()))
+    }
+
     /// Ensure a file is stored in the blob cache, returning the blob path
     /// If the blob already exists, returns existing path without copying
-    /// If absent, copies the file into the blob storage
+    /// If absent, copies the file into the blob storage with atomic temp → rename,
+    /// so the canonical blob path never holds a partially written file
+    /// With integrity tags enabled, blobs without a tag get one recorded in the index
     pub fn ensure_blob<P: AsRef<Path>>(&self, file_path: P) -> io::Result<BlobPath> {
+        self.store_blob(file_path).map(|(blob, _)| blob)
+    }
+
+    /// `ensure_blob`, also returning whether the content was newly stored (false if the cache already held it)
+    pub fn store_blob<P: AsRef<Path>>(&self, file_path: P) -> io::Result<(BlobPath, bool)> {
         let file_path = file_path.as_ref();
         
         // Hash the file
-        let hash = Self::hash_file(file_path)?;
+        let (hash, tag) = if self.integrity_tags {
+            let (hash, tag) = Self::hash_file_tagged(file_path, &self.integrity_key()?)?;
+            (hash, Some(tag))
+        } else {
+            (self.hash(file_path)?, None)
+        };
         let blob_path = self.get_blob_path(&hash);
         
         // If blob already exists, return it
         if blob_path.exists() {
-            return Ok(BlobPath {
+            if let Some(tag) = tag {
+                self.record_integrity_tag(&hash, &tag)?;
+            }
+            return Ok((BlobPath {
                 hash,
                 path: blob_path,
-            });
+            }, false));
         }
         
         // Create directory structure if it doesn't exist
-        if let Some(parent) = blob_path.parent() {
-            fs::create_dir_all(parent)?;
+        let shard_dir = blob_path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(shard_dir)?;
+        
+        // Copy into a staging file first, so a partial copy never appears at the blob path
+        let temp_path = self.staging_path(shard_dir);
+        let mut copied = fs::copy(file_path, &temp_path);
+        if matches!(&copied, Err(e) if e.kind() == io::ErrorKind::NotFound) && file_path.exists() {
+            // A concurrent compact removed the empty shard directory; recreate it and retry once
+            fs::create_dir_all(shard_dir)?;
+            copied = fs::copy(file_path, &temp_path);
+        }
+        if let Err(e) = copied {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
         }
         
-        // Copy file to blob storage
-        fs::copy(file_path, &blob_path)?;
+        // Another writer may have stored the same blob while we were copying
+        let newly_stored = !blob_path.exists();
+        if !newly_stored {
+            let _ = fs::remove_file(&temp_path);
+        } else if let Err(e) = self.commit_staged(&temp_path, &blob_path) {
+            let _ = fs::remove_file(&temp_path);
+            if !blob_path.exists() {
+                return Err(e);
+            }
+        }
+        if let Some(tag) = tag {
+            self.record_integrity_tag(&hash, &tag)?;
+        }
         
+        Ok((BlobPath {
+            hash,
+            path: blob_path,
+        }, newly_stored))
+    }
+
+    /// Store a file in the blob cache in a single pass, hashing it while copying it to a temporary file
+    /// Halves the IO for new blobs compared to `ensure_blob`, which hashes first and copies second;
+    /// if the blob turns out to exist already the copy is discarded, so prefer `ensure_blob` for content that's likely cached
+    pub fn ensure_blob_streaming<P: AsRef<Path>>(&self, file_path: P) -> io::Result<BlobPath> {
+        let file_path = file_path.as_ref();
+        let key = if self.integrity_tags { Some(self.integrity_key()?) } else { None };
+
+        // The shard isn't known until the hash is, so stream into the temp directory or the algorithm directory
+        let blobs_dir = self.blobs_dir();
+        fs::create_dir_all(&blobs_dir)?;
+        let temp_path = self.staging_path(&blobs_dir);
+        let (hash, tag) = match Self::copy_and_hash(file_path, &temp_path, key.as_ref()) {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e);
+            }
+        };
+
+        let blob_path = self.get_blob_path(&hash);
+        if blob_path.exists() {
+            let _ = fs::remove_file(&temp_path);
+        } else {
+            let shard_dir = blob_path.parent().unwrap_or(Path::new("."));
+            fs::create_dir_all(shard_dir)?;
+            let mut renamed = self.commit_staged(&temp_path, &blob_path);
+            if matches!(&renamed, Err(e) if e.kind() == io::ErrorKind::NotFound) && temp_path.exists() {
+                // A concurrent compact removed the empty shard directory; recreate it and retry once
+                fs::create_dir_all(shard_dir)?;
+                renamed = self.commit_staged(&temp_path, &blob_path);
+            }
+            if let Err(e) = renamed {
+                let _ = fs::remove_file(&temp_path);
+                // Another writer may have stored the same blob in the meantime
+                if !blob_path.exists() {
+                    return Err(e);
+                }
+            }
+        }
+        if let Some(tag) = tag {
+            self.record_integrity_tag(&hash, &tag)?;
+        }
+
         Ok(BlobPath {
             hash,
             path: blob_path,
         })
     }
 
+    /// Path for a staging copy that's moved into `dest_dir` once complete
+    /// Staging copies are freely relocatable, so they go to the temp directory when one is set and usable.
+    /// Temps that replace a file in place (`link_blob_to`, `copy_blob_to`, runtime build directories)
+    /// must stay next to their destination, since only a rename within one directory is atomic
+    fn staging_path(&self, dest_dir: &Path) -> PathBuf {
+        let dir = match &self.temp_dir {
+            Some(temp_dir) if fs::create_dir_all(temp_dir).is_ok() => temp_dir.as_path(),
+            _ => dest_dir,
+        };
+        dir.join(format!(".tmp_{}", Uuid::new_v4()))
+    }
+
+    /// Move a complete staging copy to `dest`
+    /// A temp directory on another volume can't be renamed from, so the copy is then placed next to `dest` first
+    fn commit_staged(&self, staged: &Path, dest: &Path) -> io::Result<()> {
+        let dest_dir = dest.parent().unwrap_or(Path::new("."));
+        match fs::rename(staged, dest) {
+            Err(_) if staged.parent() != Some(dest_dir) && staged.exists() && dest_dir.is_dir() => {
+                let colocated = dest_dir.join(format!(".tmp_{}", Uuid::new_v4()));
+                let result = fs::copy(staged, &colocated).and_then(|_| fs::rename(&colocated, dest));
+                if result.is_err() {
+                    let _ = fs::remove_file(&colocated);
+                }
+                let _ = fs::remove_file(staged);
+                result
+            }
+            renamed => renamed,
+        }
+    }
+
+    /// Copy `file_path` to `dest` while hashing it, plus its integrity tag when a key is given
+    fn copy_and_hash(file_path: &Path, dest: &Path, key: Option<&[u8; 32]>) -> io::Result<(Hash, Option<Hash>)> {
+        let mut source = fs::File::open(file_path)?;
+        let mut dest = fs::File::create(dest)?;
+        let mut hasher = Hasher::new();
+        let mut tagger = key.map(Hasher::new_keyed);
+        let mut buffer = vec![0; HASH_BUFFER_SIZE];
+
+        loop {
+            let bytes_read = source.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+            if let Some(tagger) = &mut tagger {
+                tagger.update(&buffer[..bytes_read]);
+            }
+            dest.write_all(&buffer[..bytes_read])?;
+        }
+        dest.flush()?;
+
+        Ok((hasher.finalize(), tagger.map(|tagger| tagger.finalize())))
+    }
+
+    /// Record a blob's integrity tag unless it already has one (the first tag binds the original content)
+    fn record_integrity_tag(&self, hash: &Hash, tag: &Hash) -> io::Result<()> {
+        let _index_lock = self.lock_index()?;
+        let mut index = self.load_index()?;
+        let hash_str = hash.to_hex().to_string();
+        if index.integrity_tags.contains_key(&hash_str) {
+            return Ok(());
+        }
+
+        index.integrity_tags.insert(hash_str, tag.to_hex().to_string());
+        self.save_index(&index)
+    }
+
+    /// Every blob-backed file a profile references, largest first (files without a recorded size last)
+    pub fn list_profile_blobs(&self, profile: &str) -> io::Result<Vec<ProfileBlobEntry>> {
+        let index = self.load_index()?;
+        let profile = self.ref_profile(profile);
+
+        let mut entries: Vec<ProfileBlobEntry> = index.refs.iter()
+            .flat_map(|(hash_str, refs)| {
+                refs.iter()
+                    .filter(|blob_ref| blob_ref.profile == profile)
+                    .map(move |blob_ref| ProfileBlobEntry {
+                        rel_path: blob_ref.rel_path.clone(),
+                        hash: hash_str.clone(),
+                        size: blob_ref.size,
+                    })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.rel_path.cmp(&b.rel_path)));
+
+        Ok(entries)
+    }
+
+    /// Check in parallel whether each hash's blob is stored, in the order given
+    /// Malformed hashes are flagged and reported as not present instead of failing the batch
+    pub fn blobs_exist(&self, hashes: &[String]) -> io::Result<Vec<BlobPresence>> {
+        Ok(hashes.par_iter()
+            .map(|hash_str| match self.get_blob_path_from_hash(hash_str) {
+                Ok(blob_path) => BlobPresence {
+                    hash: hash_str.clone(),
+                    exists: blob_path.is_file(),
+                    malformed: false,
+                },
+                Err(_) => BlobPresence {
+                    hash: hash_str.clone(),
+                    exists: false,
+                    malformed: true,
+                },
+            })
+            .collect())
+    }
+
+    /// Overrides that two or more profiles carry independently, grouped by (hash, rel_path)
+    /// The blob is already shared on disk; this shows which overrides could move to a common layer.
+    /// Most widely shared first, then by path
+    pub fn find_cross_profile_duplicates(&self) -> io::Result<Vec<DuplicateGroup>> {
+        let index = self.load_index()?;
+        let mut groups = Vec::new();
+
+        for (hash_str, refs) in &index.refs {
+            let mut by_path: HashMap<&str, DuplicateGroup> = HashMap::new();
+            for blob_ref in refs {
+                let Some(profile) = self.local_profile(&blob_ref.profile) else {
+                    continue;
+                };
+                let group = by_path.entry(&blob_ref.rel_path).or_insert_with(|| DuplicateGroup {
+                    hash: hash_str.clone(),
+                    rel_path: blob_ref.rel_path.clone(),
+                    profiles: Vec::new(),
+                    size: None,
+                });
+                group.size = group.size.or(blob_ref.size);
+                if !group.profiles.iter().any(|existing| existing == profile) {
+                    group.profiles.push(profile.to_string());
+                }
+            }
+            groups.extend(by_path.into_values().filter(|group| group.profiles.len() >= 2));
+        }
+
+        for group in &mut groups {
+            group.profiles.sort();
+        }
+        groups.sort_by(|a, b| {
+            b.profiles.len().cmp(&a.profiles.len()).then_with(|| a.rel_path.cmp(&b.rel_path))
+        });
+
+        Ok(groups)
+    }
+
+    /// Usage totals from the index alone, in O(index size) with no disk walk
+    /// Blob sizes come from the sizes recorded with their references
+    pub fn index_totals(&self) -> io::Result<IndexTotals> {
+        let index = self.load_index()?;
+        let mut totals = IndexTotals {
+            total_refs: index.total_refs(),
+            ..IndexTotals::default()
+        };
+
+        for refs in index.refs.values().filter(|refs| !refs.is_empty()) {
+            totals.unique_blobs += 1;
+            match refs.iter().find_map(|blob_ref| blob_ref.size) {
+                Some(size) => totals.unique_bytes += size,
+                None => totals.unsized_blobs += 1,
+            }
+
+            for blob_ref in refs {
+                if let Some(profile) = self.local_profile(&blob_ref.profile) {
+                    let profile_totals = totals.profiles.entry(profile.to_string()).or_default();
+                    profile_totals.refs += 1;
+                    profile_totals.bytes += blob_ref.size.unwrap_or(0);
+                }
+            }
+        }
+
+        Ok(totals)
+    }
+
+    /// Re-read every stored blob and check it against its address hash and, if it has one, its integrity tag
+    /// A tag can only be forged with the cache's key, so a blob swapped in under a matching address is caught too
+    pub fn verify_integrity(&self) -> io::Result<IntegrityReport> {
+        let index = self.load_index()?;
+        let key = match self.load_meta()?.integrity_key {
+            Some(key) => Some(parse_integrity_key(&key)?),
+            None => None,
+        };
+        let mut report = IntegrityReport::default();
+
+        let blobs_dir = self.blobs_dir();
+        if !blobs_dir.exists() {
+            return Ok(report);
+        }
+
+        for entry in WalkDir::new(&blobs_dir).min_depth(2).max_depth(2).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            // Skip in-progress temp files and anything that isn't a blob
+            let hash = match Hash::from_hex(entry.file_name().to_string_lossy().as_ref()) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+            let hash_str = hash.to_hex().to_string();
+            report.blobs_checked += 1;
+
+            let matches = match (index.integrity_tags.get(&hash_str), &key) {
+                (Some(expected_tag), Some(key)) => {
+                    let (actual_hash, actual_tag) = Self::hash_file_tagged(entry.path(), key)?;
+                    actual_hash == hash && actual_tag.to_hex().as_str() == expected_tag.as_str()
+                }
+                _ => {
+                    report.untagged += 1;
+                    Self::hash_file(entry.path())? == hash
+                }
+            };
+            if !matches {
+                warn!("Blob {} failed its integrity check", hash_str);
+                report.tampered.record(hash_str);
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Create a hardlink from a blob to a destination with atomic temp → rename operation
     /// This ensures the destination either gets the complete file or nothing
     /// CRITICAL: This method ONLY creates hardlinks - never copies. If hardlink fails, operation fails.
@@ -99,42 +1060,62 @@ impl BlobCache {
             fs::create_dir_all(parent)?;
         }
         
-        // Generate a temporary filename in the same directory as the destination
+        // Generate a temporary filename in the same directory as the destination (never the temp directory:
+        // the hardlink is renamed over the destination, which is only atomic within one directory)
         let temp_name = format!(".tmp_{}", Uuid::new_v4());
         let temp_path = dst.parent().unwrap_or(Path::new(".")).join(temp_name);
         
         // ONLY create hardlink - no fallback to copy
         // This enforces the zero-overhead workspace principle
         fs::hard_link(&blob.path, &temp_path)
-            .map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Failed to create hardlink from '{}' to '{}': {}. Ensure cache and workspace are on the same NTFS volume.", 
-                            blob.path.display(), temp_path.display(), e)
-                )
-            })?;
+            .map_err(|e| hardlink_error(&blob.path, &temp_path, e))?;
         
         // Hardlink successful, atomically rename to final destination
         fs::rename(&temp_path, dst)?;
         Ok(())
     }
 
-    /// Get the blob directory path following the layout: cache/blobs/blake3/aa/hash
+    /// Copy a blob to a destination as an independent, writable file (atomic temp → rename)
+    /// Used when workspace files are kept as real copies instead of hardlinks
+    pub fn copy_blob_to<P: AsRef<Path>>(&self, dst: P, blob: &BlobPath) -> io::Result<()> {
+        let dst = dst.as_ref();
+        
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let temp_path = dst.parent().unwrap_or(Path::new(".")).join(format!(".tmp_{}", Uuid::new_v4()));
+        let copied = fs::copy(&blob.path, &temp_path).and_then(|_| {
+            if self.preserve_mtimes {
+                Self::set_mtime(&temp_path, fs::metadata(&blob.path)?.modified()?)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = copied {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        
+        fs::rename(&temp_path, dst)?;
+        Ok(())
+    }
+
+    /// Get the blob directory path following the layout: cache/blobs/<algorithm>/aa/hash
     pub fn get_blob_path(&self, hash: &Hash) -> PathBuf {
         let hash_str = hash.to_hex().to_string();
         let prefix = &hash_str[0..2]; // First 2 characters for directory sharding
         
-        self.cache_dir
-            .join("blobs")
-            .join("blake3")
+        self.blobs_dir()
             .join(prefix)
             .join(&hash_str)
     }
 
-    /// Get blob path from a hex hash string
+    /// Get blob path from a hex hash string of the active algorithm
     pub fn get_blob_path_from_hash(&self, hash_str: &str) -> io::Result<PathBuf> {
-        let hash = Hash::from_hex(hash_str)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid hash: {}", e)))?;
+        let hash = match self.algorithm {
+            HashAlgorithm::Blake3 => Hash::from_hex(hash_str)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid hash: {}", e)))?,
+        };
         Ok(self.get_blob_path(&hash))
     }
 
@@ -148,16 +1129,40 @@ impl BlobCache {
         let index_path = self.get_index_path();
         
         if !index_path.exists() {
-            return Ok(BlobIndex::default());
+            return Ok(BlobIndex { algorithm: self.algorithm, ..BlobIndex::default() });
         }
         
         let content = fs::read_to_string(index_path)?;
-        let index: BlobIndex = serde_json::from_str(&content)
+        let mut index: BlobIndex = serde_json::from_str(&content)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // Keys from another algorithm would never match this cache's hashes
+        if index.algorithm != self.algorithm {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Index keys use {} but the cache uses {}", index.algorithm.as_str(), self.algorithm.as_str()),
+            ));
+        }
+
+        // Older indexes recorded native (backslash) paths on Windows
+        for refs in index.refs.values_mut() {
+            if refs.iter().any(|r| r.rel_path.contains('\\')) {
+                for blob_ref in refs.iter_mut() {
+                    blob_ref.rel_path = canonical_rel_path(&blob_ref.rel_path);
+                }
+                let mut seen = HashSet::new();
+                refs.retain(|r| seen.insert((r.profile.clone(), r.rel_path.clone())));
+            }
+        }
         
         Ok(index)
     }
 
+    /// Hold while loading, modifying and saving the index
+    fn lock_index(&self) -> io::Result<MutexGuard<'static, ()>> {
+        INDEX_LOCK.lock().map_err(|e| io::Error::other(e.to_string()))
+    }
+
     /// Save blob index to disk
     fn save_index(&self, index: &BlobIndex) -> io::Result<()> {
         let index_path = self.get_index_path();
@@ -171,35 +1176,59 @@ impl BlobCache {
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
         
         fs::write(index_path, content)?;
-        Ok(())
+        self.ensure_meta()
     }
 
     /// Add a reference to a blob
+    /// The blob's size and mtime are recorded, which match the workspace file when it is hardlinked
     pub fn add_ref(&self, blob: &BlobPath, profile: &str, rel_path: &str) -> io::Result<()> {
+        self.add_ref_for_file(blob, profile, rel_path, &blob.path)
+    }
+
+    /// Add a reference to a blob, recording the size and mtime of `file_path` (the workspace file)
+    /// Use this for workspace copies, whose stats differ from the blob's
+    pub fn add_ref_for_file(&self, blob: &BlobPath, profile: &str, rel_path: &str, file_path: &Path) -> io::Result<()> {
+        let _index_lock = self.lock_index()?;
         let mut index = self.load_index()?;
         let hash_str = blob.hash.to_hex().to_string();
+        let profile = self.ref_profile(profile);
+        let rel_path = canonical_rel_path(rel_path);
+        let (size, mtime) = match file_stat(file_path) {
+            Ok((size, mtime)) => (Some(size), Some(mtime)),
+            Err(_) => (None, None),
+        };
+        let quick_fingerprint = self.quick_fingerprint_for(file_path, size);
         
         let refs = index.refs.entry(hash_str).or_insert_with(Vec::new);
         
-        // Check if reference already exists
-        let new_ref = BlobReference {
-            profile: profile.to_string(),
-            rel_path: rel_path.to_string(),
-        };
-        
-        if !refs.iter().any(|r| r.profile == new_ref.profile && r.rel_path == new_ref.rel_path) {
-            refs.push(new_ref);
-            self.save_index(&index)?;
+        // Refresh the stats if the reference already exists
+        match refs.iter_mut().find(|r| r.profile == profile && r.rel_path == rel_path) {
+            Some(existing) if existing.size == size && existing.mtime == mtime && existing.quick_fingerprint == quick_fingerprint => return Ok(()),
+            Some(existing) => {
+                existing.size = size;
+                existing.mtime = mtime;
+                existing.quick_fingerprint = quick_fingerprint;
+            }
+            None => refs.push(BlobReference {
+                profile,
+                rel_path,
+                size,
+                mtime,
+                quick_fingerprint,
+            }),
         }
         
-        Ok(())
+        self.save_index(&index)
     }
 
     /// Remove a reference from a blob
     /// Returns true if the blob has no more references and can be garbage collected
     pub fn remove_ref(&self, blob: &BlobPath, profile: &str, rel_path: &str) -> io::Result<bool> {
+        let _index_lock = self.lock_index()?;
         let mut index = self.load_index()?;
         let hash_str = blob.hash.to_hex().to_string();
+        let profile = self.ref_profile(profile);
+        let rel_path = canonical_rel_path(rel_path);
         
         let should_remove_blob = if let Some(refs) = index.refs.get_mut(&hash_str) {
             // Remove the specific reference
@@ -232,7 +1261,10 @@ impl BlobCache {
     /// Remove any existing reference for a profile+rel_path combination and return the old blob hash if found
     /// This is used when a file is updated to clean up the old blob reference before adding the new one
     pub fn remove_existing_ref(&self, profile: &str, rel_path: &str) -> io::Result<Option<Hash>> {
+        let _index_lock = self.lock_index()?;
         let mut index = self.load_index()?;
+        let profile = self.ref_profile(profile);
+        let rel_path = canonical_rel_path(rel_path);
         let mut found_hash: Option<Hash> = None;
         let mut entries_to_remove: Vec<String> = Vec::new();
 
@@ -257,6 +1289,10 @@ impl BlobCache {
         // Remove empty entries and clean up unreferenced blobs
         for hash_str in entries_to_remove {
             index.refs.remove(&hash_str);
+            if index.is_pinned(&hash_str) {
+                debug!("Keeping pinned unreferenced blob: {}", hash_str);
+                continue;
+            }
             
             // Delete the unreferenced blob file
             if let Ok(hash) = Hash::from_hex(&hash_str) {
@@ -275,14 +1311,75 @@ impl BlobCache {
         Ok(found_hash)
     }
 
+    /// Point a profile's references under the directory `old_prefix` at the same paths under `new_prefix`,
+    /// in a single index update (e.g. `mods/cars` -> `vehicles/cars` after moving that folder)
+    /// Returns the number of references updated
+    pub fn rename_ref_prefix(&self, profile: &str, old_prefix: &str, new_prefix: &str) -> io::Result<usize> {
+        let _index_lock = self.lock_index()?;
+        let mut index = self.load_index()?;
+        let profile = self.ref_profile(profile);
+        let old_prefix = format!("{}/", canonical_rel_path(old_prefix).trim_matches('/'));
+        let new_prefix = format!("{}/", canonical_rel_path(new_prefix).trim_matches('/'));
+
+        let mut renamed = 0;
+        for blob_ref in index.refs.values_mut().flatten() {
+            if blob_ref.profile != profile {
+                continue;
+            }
+            if let Some(rest) = blob_ref.rel_path.strip_prefix(&old_prefix) {
+                blob_ref.rel_path = format!("{}{}", new_prefix, rest);
+                renamed += 1;
+            }
+        }
+
+        if renamed > 0 {
+            self.save_index(&index)?;
+        }
+        debug!("Renamed {} references from {} to {}", renamed, old_prefix, new_prefix);
+        Ok(renamed)
+    }
+
+    /// Pin a blob so garbage collection keeps it even without references
+    pub fn pin(&self, hash: &Hash) -> io::Result<()> {
+        if !self.get_blob_path(hash).exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("Blob not found: {}", hash.to_hex())));
+        }
+
+        let _index_lock = self.lock_index()?;
+        let mut index = self.load_index()?;
+        if index.pinned.insert(hash.to_hex().to_string()) {
+            self.save_index(&index)?;
+        }
+        Ok(())
+    }
+
+    /// Unpin a blob, making it eligible for garbage collection again once unreferenced
+    /// Returns true if the blob was pinned
+    pub fn unpin(&self, hash: &Hash) -> io::Result<bool> {
+        let _index_lock = self.lock_index()?;
+        let mut index = self.load_index()?;
+        let was_pinned = index.pinned.remove(hash.to_hex().as_str());
+        if was_pinned {
+            self.save_index(&index)?;
+        }
+        Ok(was_pinned)
+    }
+
     /// Manually garbage collect a specific blob if it has no references
-    /// Returns true if the blob was deleted, false if it still has references or doesn't exist
+    /// Returns true if the blob was deleted, false if it still has references, is pinned, or doesn't exist
     pub fn garbage_collect_blob(&self, hash: &Hash) -> io::Result<bool> {
+        let _index_lock = self.lock_index()?;
         let index = self.load_index()?;
+        self.garbage_collect_blob_with_index(&index, hash)
+    }
+
+    /// Garbage collect a blob against an already loaded index
+    /// Lets bulk GC decide for many blobs without reloading the index each time
+    pub fn garbage_collect_blob_with_index(&self, index: &BlobIndex, hash: &Hash) -> io::Result<bool> {
         let hash_str = hash.to_hex().to_string();
         
-        // Check if blob has any references
-        if index.refs.contains_key(&hash_str) {
+        // Check if blob has any references or is pinned
+        if !index.is_collectable(&hash_str) {
             return Ok(false); // Still has references
         }
         
@@ -293,14 +1390,261 @@ impl BlobCache {
             debug!("Garbage collected blob: {}", hash_str);
             return Ok(true);
         }
-        
-        Ok(false) // Blob file didn't exist
+        
+        Ok(false) // Blob file didn't exist
+    }
+
+    /// Garbage collect every blob in storage that has no references
+    /// Loads the index once and returns the number of blobs deleted
+    pub fn garbage_collect_all(&self) -> io::Result<usize> {
+        self.garbage_collect_with(None, |_| {}).map(|summary| summary.blobs_collected)
+    }
+
+    /// Count the blobs `garbage_collect_all` would delete and the bytes it would free, without deleting them
+    pub fn gc_preview(&self) -> io::Result<GcPreview> {
+        let index = self.load_index()?;
+        let blobs_dir = self.blobs_dir();
+        let mut preview = GcPreview::default();
+
+        if !blobs_dir.exists() {
+            return Ok(preview);
+        }
+
+        for entry in WalkDir::new(&blobs_dir).min_depth(2).max_depth(2).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let hash = match Hash::from_hex(entry.file_name().to_string_lossy().as_ref()) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+            if index.is_collectable(&hash.to_hex()) {
+                preview.orphaned_blobs += 1;
+                preview.bytes_reclaimable += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            }
+        }
+
+        Ok(preview)
+    }
+
+    /// Garbage collect a blob an index snapshot found collectable, first confirming it against the index on disk
+    /// under the index lock, since the blob may have been referenced again after the snapshot was taken
+    fn garbage_collect_confirmed(&self, snapshot: &BlobIndex, hash: &Hash) -> io::Result<bool> {
+        if !snapshot.is_collectable(&hash.to_hex()) {
+            return Ok(false);
+        }
+        let _index_lock = self.lock_index()?;
+        let index = self.load_index()?;
+        self.garbage_collect_blob_with_index(&index, hash)
+    }
+
+    /// Garbage collect every unreferenced blob, stopping early once `cancel_flag` is set
+    /// `on_scanned` is called with the number of blobs examined every `MAINTENANCE_PROGRESS_INTERVAL` blobs
+    pub fn garbage_collect_with<F: FnMut(&GcSummary)>(&self, cancel_flag: Option<&AtomicBool>, mut on_scanned: F) -> io::Result<GcSummary> {
+        let index = self.load_index()?;
+        let blobs_dir = self.blobs_dir();
+        let mut summary = GcSummary::default();
+        let mut collected = 0;
+
+        if !blobs_dir.exists() {
+            return Ok(summary);
+        }
+
+        for (scanned, entry) in WalkDir::new(&blobs_dir).min_depth(2).max_depth(2).into_iter().filter_map(|e| e.ok()).enumerate() {
+            if cancel_flag.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                debug!("Garbage collection cancelled after {} blobs", scanned);
+                break;
+            }
+            if scanned > 0 && scanned % MAINTENANCE_PROGRESS_INTERVAL == 0 {
+                on_scanned(&summary);
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            // Skip in-progress temp files and anything that isn't a blob
+            let hash = match Hash::from_hex(entry.file_name().to_string_lossy().as_ref()) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            match self.garbage_collect_confirmed(&index, &hash) {
+                Ok(true) => {
+                    collected += 1;
+                    summary.bytes_reclaimed += size;
+                }
+                Ok(false) => {}
+                Err(e) => warn!("Failed to garbage collect blob {}: {}", hash.to_hex(), e),
+            }
+        }
+        summary.blobs_collected = collected;
+
+        // Drop the integrity tags of collected blobs
+        if collected > 0 && !index.integrity_tags.is_empty() {
+            let _index_lock = self.lock_index()?;
+            let mut index = self.load_index()?;
+            let tag_count = index.integrity_tags.len();
+            index.integrity_tags.retain(|hash_str, _| {
+                self.get_blob_path_from_hash(hash_str).is_ok_and(|path| path.exists())
+            });
+            if index.integrity_tags.len() != tag_count {
+                self.save_index(&index)?;
+            }
+        }
+
+        debug!("Garbage collected {} unreferenced blobs", collected);
+        Ok(summary)
+    }
+
+    /// Run garbage collection, then compaction, reporting what each reclaimed
+    /// Checked for cancellation between blobs and phases; a cancelled run keeps what it already removed
+    pub fn maintain<F: Fn(MaintenanceProgress)>(&self, options: &MaintenanceOptions, cancel_flag: &AtomicBool, on_progress: F) -> io::Result<MaintenanceReport> {
+        let start = Instant::now();
+        let mut report = MaintenanceReport::default();
+        let progress = |phase: MaintenancePhase, blobs_scanned: usize, bytes_reclaimed: u64| {
+            on_progress(MaintenanceProgress { phase, blobs_scanned, bytes_reclaimed });
+        };
+
+        progress(MaintenancePhase::CollectGarbage, 0, 0);
+        let gc_start = Instant::now();
+        let mut blobs_scanned = 0;
+        let gc = self.garbage_collect_with(Some(cancel_flag), |summary| {
+            blobs_scanned += MAINTENANCE_PROGRESS_INTERVAL;
+            progress(MaintenancePhase::CollectGarbage, blobs_scanned, summary.bytes_reclaimed);
+        })?;
+        report.blobs_collected = gc.blobs_collected;
+        report.gc_bytes_reclaimed = gc.bytes_reclaimed;
+        report.gc_time_ms = gc_start.elapsed().as_millis() as u64;
+
+        if options.compact && !cancel_flag.load(Ordering::SeqCst) {
+            progress(MaintenancePhase::Compact, blobs_scanned, gc.bytes_reclaimed);
+            let compact_start = Instant::now();
+            report.directories_removed = self.compact()?;
+            report.compact_time_ms = compact_start.elapsed().as_millis() as u64;
+        }
+
+        report.cancelled = cancel_flag.load(Ordering::SeqCst);
+        report.total_time_ms = start.elapsed().as_millis() as u64;
+        progress(MaintenancePhase::Complete, blobs_scanned, gc.bytes_reclaimed);
+        Ok(report)
+    }
+
+    /// Remove empty shard directories (and the algorithm directory itself if nothing is left)
+    /// Removal only succeeds on empty directories, so a shard that gains a blob mid-compact is kept
+    /// Returns the number of directories removed
+    pub fn compact(&self) -> io::Result<usize> {
+        let blobs_dir = self.blobs_dir();
+        if !blobs_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&blobs_dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            // Only attempt empty-looking shards; remove_dir itself refuses anything non-empty
+            let is_empty = fs::read_dir(&path).map(|mut entries| entries.next().is_none()).unwrap_or(false);
+            if is_empty {
+                match fs::remove_dir(&path) {
+                    Ok(()) => removed += 1,
+                    Err(e) => debug!("Keeping shard directory {}: {}", path.display(), e),
+                }
+            }
+        }
+
+        if fs::remove_dir(&blobs_dir).is_ok() {
+            removed += 1;
+        }
+
+        debug!("Compacted blob cache: removed {} empty directories", removed);
+        Ok(removed)
+    }
+
+    /// Merge another cache into this one: blobs missing here are copied, references are unioned per hash
+    /// `profile_remap` renames the other cache's profiles (old -> new) to avoid collisions
+    pub fn merge_from(&self, other: &BlobCache, profile_remap: &HashMap<String, String>) -> io::Result<MergeReport> {
+        let mut report = MergeReport::default();
+        if other.algorithm != self.algorithm {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Can't merge a {} cache into a {} cache", other.algorithm.as_str(), self.algorithm.as_str()),
+            ));
+        }
+        let other_blobs_dir = other.blobs_dir();
+
+        if other_blobs_dir.exists() {
+            for entry in WalkDir::new(&other_blobs_dir).min_depth(2).max_depth(2).into_iter().filter_map(|e| e.ok()) {
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                // Skip in-progress temp files and anything that isn't a blob
+                let hash = match Hash::from_hex(entry.file_name().to_string_lossy().as_ref()) {
+                    Ok(hash) => hash,
+                    Err(_) => continue,
+                };
+
+                // Content-addressed: a blob with the same hash here already has the same content
+                let blob_path = self.get_blob_path(&hash);
+                if blob_path.exists() {
+                    continue;
+                }
+
+                let shard_dir = blob_path.parent().unwrap_or(Path::new("."));
+                fs::create_dir_all(shard_dir)?;
+                let temp_path = self.staging_path(shard_dir);
+                let bytes = match fs::copy(entry.path(), &temp_path) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        let _ = fs::remove_file(&temp_path);
+                        return Err(e);
+                    }
+                };
+                if let Err(e) = self.commit_staged(&temp_path, &blob_path) {
+                    let _ = fs::remove_file(&temp_path);
+                    if !blob_path.exists() {
+                        return Err(e);
+                    }
+                    continue;
+                }
+
+                report.blobs_added += 1;
+                report.bytes_copied += bytes;
+            }
+        }
+
+        let other_index = other.load_index()?;
+        let _index_lock = self.lock_index()?;
+        let mut index = self.load_index()?;
+
+        for (hash_str, other_refs) in other_index.refs {
+            let refs = index.refs.entry(hash_str).or_default();
+            for other_ref in other_refs {
+                let profile = profile_remap.get(&other_ref.profile).cloned().unwrap_or(other_ref.profile);
+                if !refs.iter().any(|r| r.profile == profile && r.rel_path == other_ref.rel_path) {
+                    refs.push(BlobReference { profile, ..other_ref });
+                    report.refs_merged += 1;
+                }
+            }
+        }
+        index.pinned.extend(other_index.pinned);
+
+        self.save_index(&index)?;
+        debug!("Merged blob cache {}: {} blobs added ({} bytes), {} references merged",
+               other.cache_dir.display(), report.blobs_added, report.bytes_copied, report.refs_merged);
+        Ok(report)
     }
 
     /// Find the blob hash for a specific profile and relative path
     /// This is more efficient than re-hashing files that are already tracked
     pub fn find_blob_hash_for_file(&self, profile: &str, rel_path: &str) -> io::Result<Option<String>> {
         let index = self.load_index()?;
+        let profile = self.ref_profile(profile);
+        let rel_path = canonical_rel_path(rel_path);
         
         // Search through all blob references to find the one matching our profile + rel_path
         for (hash_str, refs) in &index.refs {
@@ -313,6 +1657,117 @@ impl BlobCache {
         
         Ok(None)
     }
+
+    /// Profiles with a reference at `rel_path`, i.e. whose workspace overrides that file, sorted
+    pub fn profiles_overriding(&self, rel_path: &str) -> io::Result<Vec<String>> {
+        let index = self.load_index()?;
+        Ok(self.profiles_overriding_in(&index, rel_path))
+    }
+
+    /// `profiles_overriding` against an already loaded index, for querying many paths at once
+    pub fn profiles_overriding_in(&self, index: &BlobIndex, rel_path: &str) -> Vec<String> {
+        let rel_path = canonical_rel_path(rel_path);
+        let mut profiles: Vec<String> = index.refs.values()
+            .flatten()
+            .filter(|blob_ref| blob_ref.rel_path == rel_path)
+            .filter_map(|blob_ref| self.local_profile(&blob_ref.profile))
+            .map(str::to_string)
+            .collect();
+        profiles.sort();
+        profiles.dedup();
+        profiles
+    }
+
+    /// Audit reference integrity against the profile workspaces and blob storage
+    /// This is read-only: discrepancies are reported, never repaired
+    pub fn audit(&self, profiles_root: &Path) -> io::Result<AuditReport> {
+        let index = self.load_index()?;
+        let mut report = AuditReport::default();
+        let mut referenced: HashSet<(String, String)> = HashSet::new();
+
+        // Check every reference against its workspace file and blob
+        // References recorded by other data roots sharing this cache are not ours to check
+        for (hash_str, refs) in &index.refs {
+            let local_refs: Vec<(&str, &BlobReference)> = refs.iter()
+                .filter_map(|blob_ref| self.local_profile(&blob_ref.profile).map(|profile| (profile, blob_ref)))
+                .collect();
+            if local_refs.is_empty() {
+                continue;
+            }
+            report.total_refs += local_refs.len();
+
+            let blob_path = self.get_blob_path_from_hash(hash_str).ok();
+            let blob_exists = blob_path.as_ref().is_some_and(|path| path.exists());
+            if !blob_exists {
+                report.missing_blobs.record(hash_str.clone());
+            }
+
+            for (profile, blob_ref) in local_refs {
+                let workspace_file = profiles_root
+                    .join(profile)
+                    .join("workspace")
+                    .join(&blob_ref.rel_path);
+                if !workspace_file.is_file() {
+                    report.dangling_refs.record(format!("{}: {}", profile, blob_ref.rel_path));
+                } else if let Some(blob_path) = &blob_path {
+                    // Changed out-of-band (e.g. while the watcher wasn't running)
+                    if blob_ref.is_modified(&workspace_file, blob_path).unwrap_or(false) {
+                        report.modified_files.record(format!("{}: {}", profile, blob_ref.rel_path));
+                    }
+                }
+                referenced.insert((profile.to_string(), blob_ref.rel_path.clone()));
+            }
+        }
+
+        // Check every workspace file has a reference
+        if profiles_root.exists() {
+            for entry in fs::read_dir(profiles_root)? {
+                let entry = entry?;
+                let workspace_dir = entry.path().join("workspace");
+                if !workspace_dir.is_dir() {
+                    continue;
+                }
+                let profile = entry.file_name().to_string_lossy().to_string();
+
+                for file in WalkDir::new(&workspace_dir).into_iter().filter_map(|e| e.ok()) {
+                    if !file.file_type().is_file() {
+                        continue;
+                    }
+
+                    // Skip hidden and temporary files, which the watcher never normalizes
+                    let name = file.file_name().to_string_lossy();
+                    if name.starts_with('.') || name.ends_with(".tmp") {
+                        continue;
+                    }
+
+                    report.total_workspace_files += 1;
+                    let rel_path = match file.path().strip_prefix(&workspace_dir) {
+                        Ok(rel) => match rel_path_key(rel) {
+                            Some(key) => key,
+                            // Never normalized, so it can't have a reference
+                            None => {
+                                report.unreferenced_files.record(format!("{}: {}", profile, rel.display()));
+                                continue;
+                            }
+                        },
+                        Err(_) => continue,
+                    };
+                    if !referenced.contains(&(profile.clone(), rel_path.clone())) {
+                        report.unreferenced_files.record(format!("{}: {}", profile, rel_path));
+                    }
+                }
+            }
+        }
+
+        debug!(
+            "Cache audit: {} dangling refs, {} unreferenced files, {} missing blobs",
+            report.dangling_refs.count,
+            report.unreferenced_files.count,
+            report.missing_blobs.count
+        );
+
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
@@ -341,6 +1796,30 @@ mod tests {
         assert_eq!(blob_path, expected_path);
     }
 
+    #[test]
+    fn test_blob_paths_include_cache_algorithm() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+        assert_eq!(cache.algorithm, HashAlgorithm::Blake3);
+
+        let test_file = temp_dir.path().join("test.txt");
+        fs::write(&test_file, "content").unwrap();
+        let blob = cache.ensure_blob(&test_file).unwrap();
+        assert!(blob.path.starts_with(temp_dir.path().join("blobs").join(cache.algorithm.as_str())));
+        assert_eq!(cache.get_blob_path_from_hash(&blob.hash.to_hex()).unwrap(), blob.path);
+
+        // Saving the index records the algorithm in both the metadata and the index
+        cache.add_ref(&blob, "profile", "test.txt").unwrap();
+        assert_eq!(cache.load_meta().unwrap().algorithm, HashAlgorithm::Blake3);
+        let index_json = fs::read_to_string(temp_dir.path().join("blobs").join("index.json")).unwrap();
+        assert!(index_json.contains("\"algorithm\": \"blake3\""));
+
+        // A reopened cache reads the algorithm back from cache_meta.json
+        let reopened = BlobCache::new(temp_dir.path());
+        assert_eq!(reopened.algorithm, cache.algorithm);
+        assert_eq!(reopened.get_blob_path(&blob.hash), blob.path);
+    }
+
     #[test]
     fn test_hash_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -359,6 +1838,50 @@ mod tests {
         assert_eq!(hash, expected_hash);
     }
 
+    #[test]
+    fn test_hash_file_independent_of_buffer_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("archive.img");
+        
+        // Larger than the default buffer and not a multiple of any tested size
+        let content: Vec<u8> = (0..HASH_BUFFER_SIZE * 2 + 12345).map(|i| (i % 251) as u8).collect();
+        fs::write(&test_file, &content).unwrap();
+        let expected_hash = blake3::hash(&content);
+        
+        assert_eq!(BlobCache::hash_file(&test_file).unwrap(), expected_hash);
+        for buffer_size in [4096, 8192, 64 * 1024, HASH_BUFFER_SIZE, content.len() + 1] {
+            assert_eq!(
+                BlobCache::hash_file_with_buffer_size(&test_file, buffer_size).unwrap(),
+                expected_hash,
+                "hash differs with buffer size {}", buffer_size
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_hashing_matches_single_threaded() {
+        let temp_dir = TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty.txt");
+        let large = temp_dir.path().join("models.img");
+        fs::write(&empty, b"").unwrap();
+        // More than one read, each of several blake3 chunks so the parallel path actually splits the work
+        let content: Vec<u8> = (0..PARALLEL_HASH_BUFFER_SIZE + HASH_BUFFER_SIZE * 3 + 777).map(|i| (i % 253) as u8).collect();
+        fs::write(&large, &content).unwrap();
+
+        for file in [&empty, &large] {
+            let expected_hash = BlobCache::hash_file(file).unwrap();
+            assert_eq!(BlobCache::hash_file_parallel(file).unwrap(), expected_hash);
+            for mode in [HashMode::Single, HashMode::Parallel, HashMode::Auto] {
+                assert_eq!(BlobCache::hash_file_with_mode(file, mode).unwrap(), expected_hash, "{:?}", mode);
+            }
+        }
+
+        // Blobs stored in parallel mode land at the same address
+        let cache = BlobCache::new(temp_dir.path().join("cache")).with_hash_mode(HashMode::Parallel);
+        let blob = cache.ensure_blob(&large).unwrap();
+        assert_eq!(blob.hash, blake3::hash(&content));
+    }
+
     #[test]
     fn test_ensure_blob() {
         let temp_dir = TempDir::new().unwrap();
@@ -386,6 +1909,105 @@ mod tests {
         assert_eq!(blob_path.hash, blob_path2.hash);
     }
 
+    #[test]
+    fn test_ensure_blob_streaming() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache")).with_integrity_tags(true);
+        let test_file = temp_dir.path().join("test.bin");
+
+        // Spans several buffers so the hash is fed in chunks
+        let content: Vec<u8> = (0..HASH_BUFFER_SIZE * 2 + 17).map(|i| (i % 251) as u8).collect();
+        fs::write(&test_file, &content).unwrap();
+
+        let blob = cache.ensure_blob_streaming(&test_file).unwrap();
+        assert_eq!(blob.hash, BlobCache::hash_file(&test_file).unwrap());
+        assert_eq!(blob.path, cache.get_blob_path(&blob.hash));
+        assert_eq!(fs::read(&blob.path).unwrap(), content);
+
+        // Storing the same content again discards the copy and keeps the existing blob
+        let again = cache.ensure_blob_streaming(&test_file).unwrap();
+        assert_eq!(again.path, blob.path);
+        let temps: Vec<_> = WalkDir::new(cache.blobs_dir()).into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".tmp_"))
+            .collect();
+        assert!(temps.is_empty());
+
+        // The integrity tag is computed in the same pass
+        cache.add_ref(&blob, "profile", "test.bin").unwrap();
+        let report = cache.verify_integrity().unwrap();
+        assert_eq!(report.untagged, 0);
+        assert_eq!(report.tampered.count, 0);
+    }
+
+    #[test]
+    fn test_staging_copies_use_the_temp_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging_dir = temp_dir.path().join("tmp");
+        let cache = BlobCache::new(temp_dir.path().join("cache")).with_temp_dir(Some(staging_dir.clone()));
+        let shard_dir = cache.blobs_dir().join("ab");
+
+        // Staging copies go to the temp directory, created on demand
+        assert_eq!(cache.staging_path(&shard_dir).parent(), Some(staging_dir.as_path()));
+        assert!(staging_dir.is_dir());
+        let unconfigured = BlobCache::new(temp_dir.path().join("cache"));
+        assert_eq!(unconfigured.staging_path(&shard_dir).parent(), Some(shard_dir.as_path()));
+
+        // Both ways of storing a blob move the staged copy into the cache and leave nothing behind
+        for (name, streaming) in [("copied.txt", false), ("streamed.txt", true)] {
+            let file = temp_dir.path().join(name);
+            fs::write(&file, name).unwrap();
+            let blob = if streaming {
+                cache.ensure_blob_streaming(&file).unwrap()
+            } else {
+                cache.ensure_blob(&file).unwrap()
+            };
+            assert_eq!(fs::read(&blob.path).unwrap(), name.as_bytes());
+        }
+        assert_eq!(fs::read_dir(&staging_dir).unwrap().count(), 0);
+
+        // Links into workspaces and runtimes are still staged next to their destination
+        let blob = cache.ensure_blob(temp_dir.path().join("copied.txt")).unwrap();
+        let linked = temp_dir.path().join("workspace").join("copied.txt");
+        cache.link_blob_to(&linked, &blob).unwrap();
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "copied.txt");
+        assert_eq!(fs::read_dir(&staging_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_ensure_blob_never_leaves_partial_blob() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let test_file = temp_dir.path().join("test.txt");
+
+        let content = b"Content that must never be stored truncated";
+        fs::write(&test_file, content).unwrap();
+
+        let hash = blake3::hash(content);
+        let blob_path = cache.get_blob_path(&hash);
+        let shard_dir = blob_path.parent().unwrap().to_path_buf();
+
+        // Simulate a copy that was interrupted mid-way: only a partial temp file is left behind
+        fs::create_dir_all(&shard_dir).unwrap();
+        let stale_temp = shard_dir.join(".tmp_interrupted");
+        fs::write(&stale_temp, &content[..10]).unwrap();
+
+        // The canonical blob path must not be considered present
+        assert!(!blob_path.exists());
+
+        // A fresh ensure_blob stores the complete content at the canonical path
+        let blob = cache.ensure_blob(&test_file).unwrap();
+        assert_eq!(blob.path, blob_path);
+        assert_eq!(fs::read(&blob.path).unwrap(), content);
+
+        // Only the stale temp from the interrupted copy remains next to the blob
+        let leftovers: Vec<_> = fs::read_dir(&shard_dir).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(".tmp_"))
+            .collect();
+        assert_eq!(leftovers, vec![".tmp_interrupted".to_string()]);
+    }
+
     #[test]
     fn test_link_blob_to() {
         let temp_dir = TempDir::new().unwrap();
@@ -432,10 +2054,16 @@ mod tests {
                 BlobReference {
                     profile: "profile1".to_string(),
                     rel_path: "data/test.txt".to_string(),
+                    size: None,
+                    mtime: None,
+                    quick_fingerprint: None,
                 },
                 BlobReference {
                     profile: "profile2".to_string(),
                     rel_path: "mods/test.txt".to_string(),
+                    size: Some(4),
+                    mtime: Some(1_700_000_000_000),
+                    quick_fingerprint: None,
                 },
             ]
         );
@@ -492,6 +2120,500 @@ mod tests {
         assert_eq!(refs.len(), 0);
     }
 
+    #[test]
+    fn test_refcounts_and_garbage_collect_all() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        let shared_file = temp_dir.path().join("shared.txt");
+        let orphan_file = temp_dir.path().join("orphan.txt");
+        fs::write(&shared_file, "shared").unwrap();
+        fs::write(&orphan_file, "orphan").unwrap();
+
+        let shared = cache.ensure_blob(&shared_file).unwrap();
+        let orphan = cache.ensure_blob(&orphan_file).unwrap();
+        cache.add_ref(&shared, "profile1", "data/shared.txt").unwrap();
+        cache.add_ref(&shared, "profile2", "data/shared.txt").unwrap();
+
+        let index = cache.load_index().unwrap();
+        assert_eq!(index.refcount(&shared.hash.to_hex()), 2);
+        assert_eq!(index.refcount(&orphan.hash.to_hex()), 0);
+        assert_eq!(index.total_refs(), 2);
+
+        // Only the unreferenced blob is collected
+        assert_eq!(cache.garbage_collect_all().unwrap(), 1);
+        assert!(shared.path.exists());
+        assert!(!orphan.path.exists());
+        assert_eq!(cache.garbage_collect_all().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_gc_preview_matches_collection() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        let mut blobs = Vec::new();
+        for (name, contents) in [("kept.txt", "kept"), ("pinned.txt", "pinned"), ("orphan1.txt", "orphan"), ("orphan2.txt", "another orphan")] {
+            let file = temp_dir.path().join(name);
+            fs::write(&file, contents).unwrap();
+            blobs.push(cache.ensure_blob(&file).unwrap());
+        }
+        cache.add_ref(&blobs[0], "profile1", "data/kept.txt").unwrap();
+        cache.pin(&blobs[1].hash).unwrap();
+
+        let preview = cache.gc_preview().unwrap();
+        assert_eq!(preview.orphaned_blobs, 2);
+        assert_eq!(preview.bytes_reclaimable, ("orphan".len() + "another orphan".len()) as u64);
+
+        // The preview deletes nothing, and matches what GC then reclaims
+        assert!(blobs.iter().all(|blob| blob.path.exists()));
+        let summary = cache.garbage_collect_with(None, |_| {}).unwrap();
+        assert_eq!(summary.blobs_collected, preview.orphaned_blobs);
+        assert_eq!(summary.bytes_reclaimed, preview.bytes_reclaimable);
+        assert_eq!(cache.gc_preview().unwrap().orphaned_blobs, 0);
+    }
+
+    #[test]
+    fn test_quick_fingerprints_detect_changes_at_the_ends() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache")).with_quick_fingerprints(Some(1024));
+        let span = QUICK_FINGERPRINT_SPAN as usize;
+        let file = temp_dir.path().join("gta3.img");
+        let mut contents = vec![7u8; span * 3];
+        fs::write(&file, &contents).unwrap();
+
+        let blob = cache.ensure_blob(&file).unwrap();
+        cache.add_ref_for_file(&blob, "profile1", "models/gta3.img", &file).unwrap();
+        let recorded = cache.get_refs(&blob).unwrap()[0].quick_fingerprint.clone();
+        assert_eq!(recorded, Some(BlobCache::quick_fingerprint(&file).unwrap()));
+        assert!(cache.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).unwrap().is_some());
+
+        // Changes at either end, or to the size, are caught
+        contents[span * 3 - 1] = 8;
+        fs::write(&file, &contents).unwrap();
+        assert!(cache.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).unwrap().is_none());
+        contents[span * 3 - 1] = 7;
+        contents.push(7);
+        fs::write(&file, &contents).unwrap();
+        assert!(cache.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).unwrap().is_none());
+
+        // The documented blind spot: a same-size edit in the middle keeps the fingerprint
+        contents.pop();
+        contents[span + span / 2] = 8;
+        fs::write(&file, &contents).unwrap();
+        assert!(cache.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).unwrap().is_some());
+
+        // Small files and disabled caches never use fingerprints
+        let small = temp_dir.path().join("gta.dat");
+        fs::write(&small, b"small").unwrap();
+        let small_blob = cache.ensure_blob(&small).unwrap();
+        cache.add_ref_for_file(&small_blob, "profile1", "data/gta.dat", &small).unwrap();
+        assert!(cache.get_refs(&small_blob).unwrap()[0].quick_fingerprint.is_none());
+        let disabled = BlobCache::new(temp_dir.path().join("cache"));
+        assert!(disabled.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).unwrap().is_none());
+
+        // Small files are turned away by size alone, without reading the index
+        fs::write(cache.get_index_path(), b"not an index").unwrap();
+        assert!(cache.unchanged_by_fingerprint("profile1", "data/gta.dat", &small).unwrap().is_none());
+        assert!(cache.unchanged_by_fingerprint("profile1", "models/gta3.img", &file).is_err());
+    }
+
+    #[test]
+    fn test_pinned_blobs_survive_gc() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        let archive = temp_dir.path().join("gta3.img");
+        fs::write(&archive, b"expensive archive").unwrap();
+        let pinned_blob = cache.ensure_blob(&archive).unwrap();
+        let loose = temp_dir.path().join("loose.txt");
+        fs::write(&loose, b"cheap file").unwrap();
+        let loose_blob = cache.ensure_blob(&loose).unwrap();
+
+        cache.pin(&pinned_blob.hash).unwrap();
+        assert!(cache.load_index().unwrap().is_pinned(&pinned_blob.hash.to_hex()));
+
+        // A pinned, zero-ref blob survives both single and bulk GC
+        assert!(!cache.garbage_collect_blob(&pinned_blob.hash).unwrap());
+        assert_eq!(cache.garbage_collect_all().unwrap(), 1);
+        assert!(pinned_blob.path.exists());
+        assert!(!loose_blob.path.exists());
+
+        // Dropping its last reference doesn't delete it either
+        cache.add_ref(&pinned_blob, "test", "models/gta3.img").unwrap();
+        cache.remove_existing_ref("test", "models/gta3.img").unwrap();
+        assert!(pinned_blob.path.exists());
+
+        // Once unpinned it's collected like any other unreferenced blob
+        assert!(cache.unpin(&pinned_blob.hash).unwrap());
+        assert!(!cache.unpin(&pinned_blob.hash).unwrap());
+        assert!(cache.garbage_collect_blob(&pinned_blob.hash).unwrap());
+
+        // Only stored blobs can be pinned
+        assert!(cache.pin(&loose_blob.hash).is_err());
+    }
+
+    #[test]
+    fn test_gc_rechecks_blobs_referenced_after_its_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let file = temp_dir.path().join("mod.asi");
+        fs::write(&file, b"mod").unwrap();
+        let blob = cache.ensure_blob(&file).unwrap();
+
+        // The watcher references the blob after a collection loaded its index
+        let snapshot = cache.load_index().unwrap();
+        assert!(snapshot.is_collectable(&blob.hash.to_hex()));
+        cache.add_ref(&blob, "test", "mod.asi").unwrap();
+
+        assert!(!cache.garbage_collect_confirmed(&snapshot, &blob.hash).unwrap());
+        assert!(blob.path.exists());
+
+        cache.remove_existing_ref("test", "mod.asi").unwrap();
+        assert!(!blob.path.exists());
+    }
+
+    #[test]
+    fn test_compact_removes_only_empty_shards() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        assert_eq!(cache.compact().unwrap(), 0);
+
+        let kept_file = temp_dir.path().join("kept.txt");
+        fs::write(&kept_file, b"kept").unwrap();
+        let kept = cache.ensure_blob(&kept_file).unwrap();
+        cache.add_ref(&kept, "test", "kept.txt").unwrap();
+        let dropped_file = temp_dir.path().join("dropped.txt");
+        fs::write(&dropped_file, b"dropped").unwrap();
+        let dropped = cache.ensure_blob(&dropped_file).unwrap();
+        assert_ne!(kept.path.parent(), dropped.path.parent());
+
+        // GC leaves the dropped blob's shard empty; compact removes just that one
+        assert_eq!(cache.garbage_collect_all().unwrap(), 1);
+        assert_eq!(cache.compact().unwrap(), 1);
+        assert!(!dropped.path.parent().unwrap().exists());
+        assert!(kept.path.exists());
+
+        // Blobs can still be stored into a compacted shard
+        let restored = cache.ensure_blob(&dropped_file).unwrap();
+        assert!(restored.path.exists());
+
+        // Once every blob is gone the blake3 directory goes too
+        fs::remove_file(&kept.path).unwrap();
+        fs::remove_file(&restored.path).unwrap();
+        assert_eq!(cache.compact().unwrap(), 3);
+        assert!(!cache.cache_dir.join("blobs").join("blake3").exists());
+    }
+
+    #[test]
+    fn test_merge_from_unions_blobs_and_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache_a"));
+        let other = BlobCache::new(temp_dir.path().join("cache_b"));
+
+        let write = |name: &str, content: &[u8]| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let shared = write("shared.txt", b"shared content");
+        let only_other = write("only_other.txt", b"only in the other cache");
+
+        let shared_blob = cache.ensure_blob(&shared).unwrap();
+        cache.add_ref(&shared_blob, "default", "shared.txt").unwrap();
+
+        let shared_in_other = other.ensure_blob(&shared).unwrap();
+        other.add_ref(&shared_in_other, "default", "shared.txt").unwrap();
+        let other_blob = other.ensure_blob(&only_other).unwrap();
+        other.add_ref(&other_blob, "default", "only_other.txt").unwrap();
+        other.add_ref(&other_blob, "modded", "only_other.txt").unwrap();
+
+        let remap = HashMap::from([("default".to_string(), "default-old".to_string())]);
+        let report = cache.merge_from(&other, &remap).unwrap();
+
+        assert_eq!(report.blobs_added, 1);
+        assert_eq!(report.bytes_copied, b"only in the other cache".len() as u64);
+        assert_eq!(report.refs_merged, 3);
+        assert!(cache.get_blob_path(&other_blob.hash).exists());
+
+        let shared_refs = cache.get_refs(&shared_blob).unwrap();
+        assert_eq!(shared_refs.len(), 2);
+        assert!(shared_refs.iter().any(|r| r.profile == "default-old"));
+
+        let merged_refs = cache.get_refs(&other_blob).unwrap();
+        assert!(merged_refs.iter().any(|r| r.profile == "default-old"));
+        assert!(merged_refs.iter().any(|r| r.profile == "modded"));
+
+        // Merging again adds nothing
+        let again = cache.merge_from(&other, &remap).unwrap();
+        assert_eq!((again.blobs_added, again.refs_merged), (0, 0));
+    }
+
+    #[test]
+    fn test_integrity_tags_detect_tampering() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path()).with_integrity_tags(true);
+
+        let test_file = temp_dir.path().join("test.txt");
+        fs::write(&test_file, "original content").unwrap();
+        let blob = cache.ensure_blob(&test_file).unwrap();
+        assert!(cache.load_meta().unwrap().integrity_key.is_some());
+        assert!(cache.load_index().unwrap().integrity_tags.contains_key(&blob.hash.to_hex().to_string()));
+
+        let report = cache.verify_integrity().unwrap();
+        assert_eq!(report.blobs_checked, 1);
+        assert_eq!(report.untagged, 0);
+        assert!(report.is_clean());
+
+        // Flip the blob's content in place
+        fs::write(&blob.path, "tampered content").unwrap();
+        let report = cache.verify_integrity().unwrap();
+        assert_eq!(report.tampered.count, 1);
+        assert_eq!(report.tampered.examples, vec![blob.hash.to_hex().to_string()]);
+
+        // A cache with a different key rejects a tag it didn't record, even for intact content
+        fs::write(&blob.path, "original content").unwrap();
+        assert!(cache.verify_integrity().unwrap().is_clean());
+        let other_dir = TempDir::new().unwrap();
+        let other = BlobCache::new(other_dir.path()).with_integrity_tags(true);
+        other.ensure_blob(&test_file).unwrap();
+        let mut index = other.load_index().unwrap();
+        index.integrity_tags = cache.load_index().unwrap().integrity_tags;
+        other.save_index(&index).unwrap();
+        assert_eq!(other.verify_integrity().unwrap().tampered.count, 1);
+    }
+
+    #[test]
+    fn test_untagged_blobs_are_checked_by_address() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+
+        let test_file = temp_dir.path().join("test.txt");
+        fs::write(&test_file, "content").unwrap();
+        let blob = cache.ensure_blob(&test_file).unwrap();
+        assert!(cache.load_index().unwrap().integrity_tags.is_empty());
+
+        let report = cache.verify_integrity().unwrap();
+        assert_eq!((report.blobs_checked, report.untagged), (1, 1));
+        assert!(report.is_clean());
+
+        fs::write(&blob.path, "rotten").unwrap();
+        assert_eq!(cache.verify_integrity().unwrap().tampered.count, 1);
+
+        // Enabling tags later tags existing blobs the next time they're stored, and GC drops the tag again
+        fs::write(&blob.path, "content").unwrap();
+        let tagged = BlobCache::new(temp_dir.path()).with_integrity_tags(true);
+        tagged.ensure_blob(&test_file).unwrap();
+        assert_eq!(tagged.verify_integrity().unwrap().untagged, 0);
+        assert_eq!(tagged.garbage_collect_all().unwrap(), 1);
+        assert!(tagged.load_index().unwrap().integrity_tags.is_empty());
+    }
+
+    #[test]
+    fn test_index_totals() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+
+        let shared = temp_dir.path().join("shared.txt");
+        fs::write(&shared, "shared").unwrap();
+        let only_b = temp_dir.path().join("only_b.txt");
+        fs::write(&only_b, "only in profile b").unwrap();
+        let shared_blob = cache.ensure_blob(&shared).unwrap();
+        let only_b_blob = cache.ensure_blob(&only_b).unwrap();
+        cache.add_ref(&shared_blob, "a", "shared.txt").unwrap();
+        cache.add_ref(&shared_blob, "b", "shared.txt").unwrap();
+        cache.add_ref(&only_b_blob, "b", "only_b.txt").unwrap();
+
+        let totals = cache.index_totals().unwrap();
+        assert_eq!(totals.unique_blobs, 2);
+        assert_eq!(totals.unique_bytes, 6 + 17);
+        assert_eq!(totals.unsized_blobs, 0);
+        assert_eq!(totals.total_refs, 3);
+        assert_eq!(totals.profiles["a"].refs, 1);
+        assert_eq!(totals.profiles["a"].bytes, 6);
+        assert_eq!(totals.profiles["b"].refs, 2);
+        assert_eq!(totals.profiles["b"].bytes, 6 + 17);
+
+        // Totals come from the index, so they don't change when blob storage is touched
+        fs::remove_file(&only_b_blob.path).unwrap();
+        assert_eq!(cache.index_totals().unwrap().unique_bytes, 6 + 17);
+    }
+
+    #[test]
+    fn test_list_profile_blobs() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+
+        let mut added = Vec::new();
+        for (rel_path, content) in [("data/gta.dat", "dat"), ("models/cars.img", "a large archive"), ("mod.asi", "plugin")] {
+            let file = temp_dir.path().join(rel_path.replace('/', "_"));
+            fs::write(&file, content).unwrap();
+            let blob = cache.ensure_blob(&file).unwrap();
+            cache.add_ref(&blob, "profile", rel_path).unwrap();
+            added.push(ProfileBlobEntry {
+                rel_path: rel_path.to_string(),
+                hash: blob.hash.to_hex().to_string(),
+                size: Some(content.len() as u64),
+            });
+        }
+        let other_file = temp_dir.path().join("other.asi");
+        fs::write(&other_file, "other profile").unwrap();
+        cache.add_ref(&cache.ensure_blob(&other_file).unwrap(), "other", "other.asi").unwrap();
+
+        // Largest first, and only this profile's references
+        added.sort_by_key(|entry| std::cmp::Reverse(entry.size));
+        assert_eq!(cache.list_profile_blobs("profile").unwrap(), added);
+        assert_eq!(cache.list_profile_blobs("other").unwrap().len(), 1);
+        assert!(cache.list_profile_blobs("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blobs_exist() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+        let file = temp_dir.path().join("stored.txt");
+        fs::write(&file, "stored").unwrap();
+        let stored = cache.ensure_blob(&file).unwrap().hash.to_hex().to_string();
+        let missing = blake3::hash(b"never stored").to_hex().to_string();
+
+        let presence = cache.blobs_exist(&[stored.clone(), "not-a-hash".to_string(), missing.clone()]).unwrap();
+        assert_eq!(presence, vec![
+            BlobPresence { hash: stored, exists: true, malformed: false },
+            BlobPresence { hash: "not-a-hash".to_string(), exists: false, malformed: true },
+            BlobPresence { hash: missing, exists: false, malformed: false },
+        ]);
+        assert!(cache.blobs_exist(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_cross_profile_duplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+
+        let shared_file = temp_dir.path().join("shared.asi");
+        fs::write(&shared_file, "common plugin").unwrap();
+        let shared = cache.ensure_blob(&shared_file).unwrap();
+        for profile in ["gamma", "alpha", "beta"] {
+            cache.add_ref(&shared, profile, "scripts/common.asi").unwrap();
+        }
+        // Same blob at a different path, and a profile-specific override, aren't duplicates
+        cache.add_ref(&shared, "alpha", "scripts/renamed.asi").unwrap();
+        let own_file = temp_dir.path().join("own.asi");
+        fs::write(&own_file, "alpha only").unwrap();
+        cache.add_ref(&cache.ensure_blob(&own_file).unwrap(), "alpha", "own.asi").unwrap();
+
+        let groups = cache.find_cross_profile_duplicates().unwrap();
+        assert_eq!(groups, vec![DuplicateGroup {
+            hash: shared.hash.to_hex().to_string(),
+            rel_path: "scripts/common.asi".to_string(),
+            profiles: vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()],
+            size: Some("common plugin".len() as u64),
+        }]);
+    }
+
+    #[test]
+    fn test_maintain_collects_then_compacts() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path());
+
+        let kept_file = temp_dir.path().join("kept.txt");
+        fs::write(&kept_file, "kept").unwrap();
+        let kept = cache.ensure_blob(&kept_file).unwrap();
+        cache.add_ref(&kept, "profile", "kept.txt").unwrap();
+        let orphan_file = temp_dir.path().join("orphan.txt");
+        fs::write(&orphan_file, "orphaned content").unwrap();
+        let orphan = cache.ensure_blob(&orphan_file).unwrap();
+
+        // A cancelled run does nothing
+        let cancelled = AtomicBool::new(true);
+        let report = cache.maintain(&MaintenanceOptions::default(), &cancelled, |_| {}).unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.blobs_collected, 0);
+        assert!(orphan.path.exists());
+
+        let phases = Mutex::new(Vec::new());
+        let report = cache.maintain(&MaintenanceOptions::default(), &AtomicBool::new(false), |progress| {
+            phases.lock().unwrap().push(progress.phase);
+        }).unwrap();
+        assert!(!report.cancelled);
+        assert_eq!(report.blobs_collected, 1);
+        assert_eq!(report.gc_bytes_reclaimed, 16);
+        assert!(!orphan.path.exists() && kept.path.exists());
+        assert_eq!(phases.into_inner().unwrap(), vec![MaintenancePhase::CollectGarbage, MaintenancePhase::Compact, MaintenancePhase::Complete]);
+        if kept.hash.to_hex()[..2] != orphan.hash.to_hex()[..2] {
+            assert_eq!(report.directories_removed, 1);
+        }
+    }
+
+    #[test]
+    fn test_namespaces_share_one_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("shared_cache");
+        let root_a = BlobCache::new(&cache_dir).with_namespace(Some("root_a".to_string()));
+        let root_b = BlobCache::new(&cache_dir).with_namespace(Some("root_b".to_string()));
+
+        // Both data roots have a "default" profile with the same file
+        let mut profiles = Vec::new();
+        for (cache, root) in [(&root_a, "a"), (&root_b, "b")] {
+            let profiles_root = temp_dir.path().join(root).join("profiles");
+            let workspace = profiles_root.join("default").join("workspace");
+            fs::create_dir_all(&workspace).unwrap();
+            fs::write(workspace.join("mod.txt"), b"shared mod").unwrap();
+            let blob = cache.ensure_blob(workspace.join("mod.txt")).unwrap();
+            cache.add_ref_for_file(&blob, "default", "mod.txt", &workspace.join("mod.txt")).unwrap();
+            profiles.push((profiles_root, blob));
+        }
+
+        // One blob, one reference per data root
+        let blob = &profiles[0].1;
+        assert_eq!(blob.hash, profiles[1].1.hash);
+        let refs = root_a.get_refs(blob).unwrap();
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().any(|r| r.profile == "root_a/default"));
+        assert!(refs.iter().any(|r| r.profile == "root_b/default"));
+        assert!(root_b.find_blob_hash_for_file("default", "mod.txt").unwrap().is_some());
+
+        // Each data root only audits its own references
+        for (cache, (profiles_root, _)) in [(&root_a, &profiles[0]), (&root_b, &profiles[1])] {
+            let report = cache.audit(profiles_root).unwrap();
+            assert!(report.is_clean());
+            assert_eq!(report.total_refs, 1);
+        }
+
+        // The blob survives while the other data root still references it
+        root_a.remove_ref(blob, "default", "mod.txt").unwrap();
+        assert!(!root_a.garbage_collect_blob(&blob.hash).unwrap());
+        assert!(blob.path.exists());
+        assert!(root_a.find_blob_hash_for_file("default", "mod.txt").unwrap().is_none());
+
+        root_b.remove_ref(blob, "default", "mod.txt").unwrap();
+        assert!(root_b.garbage_collect_blob(&blob.hash).unwrap());
+    }
+
+    #[test]
+    fn test_reference_paths_use_forward_slashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let file = temp_dir.path().join("handling.cfg");
+        fs::write(&file, b"handling").unwrap();
+        let blob = cache.ensure_blob(&file).unwrap();
+
+        // Either separator records and finds the same reference
+        cache.add_ref(&blob, "default", "data\\handling.cfg").unwrap();
+        cache.add_ref(&blob, "default", "data/handling.cfg").unwrap();
+        let refs = cache.get_refs(&blob).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].rel_path, "data/handling.cfg");
+        assert!(cache.find_blob_hash_for_file("default", "data\\handling.cfg").unwrap().is_some());
+
+        // Indexes written with backslashes are read back canonicalized
+        let index_path = cache.get_index_path();
+        let legacy = fs::read_to_string(&index_path).unwrap().replace("data/handling.cfg", "data\\\\handling.cfg");
+        fs::write(&index_path, legacy).unwrap();
+        assert_eq!(cache.get_refs(&blob).unwrap()[0].rel_path, "data/handling.cfg");
+        assert_eq!(cache.remove_existing_ref("default", "data/handling.cfg").unwrap(), Some(blob.hash));
+    }
+
     #[test]
     fn test_remove_existing_ref_cross_profile() {
         let temp_dir = TempDir::new().unwrap();
@@ -568,6 +2690,83 @@ mod tests {
         assert!(!blob.path.exists()); // Now it should be deleted
     }
 
+    #[test]
+    fn test_audit_reports_discrepancies() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let profiles_root = temp_dir.path().join("profiles");
+        let workspace = profiles_root.join("profile1").join("workspace");
+        fs::create_dir_all(workspace.join("data")).unwrap();
+
+        // Consistent: workspace file, reference and blob all present
+        let tracked = workspace.join("data").join("tracked.txt");
+        fs::write(&tracked, b"tracked content").unwrap();
+        let blob = cache.ensure_blob(&tracked).unwrap();
+        cache.add_ref(&blob, "profile1", "data/tracked.txt").unwrap();
+
+        // Workspace file with no reference
+        fs::write(workspace.join("untracked.txt"), b"untracked content").unwrap();
+
+        // Reference whose workspace file and blob are both gone
+        let gone = temp_dir.path().join("gone.txt");
+        fs::write(&gone, b"gone content").unwrap();
+        let gone_blob = cache.ensure_blob(&gone).unwrap();
+        cache.add_ref(&gone_blob, "profile1", "data/gone.txt").unwrap();
+        fs::remove_file(&gone_blob.path).unwrap();
+
+        let report = cache.audit(&profiles_root).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.total_refs, 2);
+        assert_eq!(report.total_workspace_files, 2);
+        assert_eq!(report.dangling_refs.count, 1);
+        assert_eq!(report.dangling_refs.examples, vec!["profile1: data/gone.txt".to_string()]);
+        assert_eq!(report.unreferenced_files.count, 1);
+        assert_eq!(report.unreferenced_files.examples, vec!["profile1: untracked.txt".to_string()]);
+        assert_eq!(report.missing_blobs.count, 1);
+        assert_eq!(report.missing_blobs.examples, vec![gone_blob.hash.to_hex().to_string()]);
+    }
+
+    #[test]
+    fn test_reference_stats_detect_out_of_band_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let profiles_root = temp_dir.path().join("profiles");
+        let workspace = profiles_root.join("profile1").join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+
+        // Indexes written before the stats existed still load
+        fs::create_dir_all(temp_dir.path().join("cache").join("blobs")).unwrap();
+        fs::write(
+            temp_dir.path().join("cache").join("blobs").join("index.json"),
+            r#"{"refs": {"abc": [{"profile": "old", "rel_path": "old.txt"}]}}"#,
+        ).unwrap();
+        let index = cache.load_index().unwrap();
+        assert_eq!(index.refs["abc"][0].size, None);
+        fs::remove_file(temp_dir.path().join("cache").join("blobs").join("index.json")).unwrap();
+
+        let file = workspace.join("mod.ini");
+        fs::write(&file, b"speed=1").unwrap();
+        let blob = cache.ensure_blob(&file).unwrap();
+        cache.add_ref_for_file(&blob, "profile1", "mod.ini", &file).unwrap();
+
+        let blob_ref = cache.get_refs(&blob).unwrap().remove(0);
+        assert_eq!(blob_ref.size, Some(7));
+        assert!(blob_ref.mtime.is_some());
+        assert!(!blob_ref.is_modified(&file, &blob.path).unwrap());
+        assert_eq!(cache.audit(&profiles_root).unwrap().modified_files.count, 0);
+
+        // Recorded stats are persisted with the reference
+        let saved = fs::read_to_string(temp_dir.path().join("cache").join("blobs").join("index.json")).unwrap();
+        assert!(saved.contains("\"size\": 7"));
+
+        // An edit behind the watcher's back changes the size, caught without re-hashing
+        fs::write(&file, b"speed=100").unwrap();
+        assert!(blob_ref.is_modified(&file, &blob.path).unwrap());
+        let report = cache.audit(&profiles_root).unwrap();
+        assert_eq!(report.modified_files.examples, vec!["profile1: mod.ini".to_string()]);
+        assert!(!report.is_clean());
+    }
+
     #[test]
     fn test_milestone3_requirements() {
         let temp_dir = TempDir::new().unwrap();
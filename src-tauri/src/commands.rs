@@ -1,24 +1,100 @@
-use tauri::{State, Emitter};
+use tauri::{State, Emitter, Manager};
 use std::sync::Mutex;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use log::debug;
+use uuid::Uuid;
 
-use crate::settings::{Settings, ValidationResult};
-use crate::path_utils::{get_drive_letter, is_ntfs_volume, get_free_space, format_size, same_volume};
-use crate::profiles::{ProfileManager, Profile};
-use crate::virtual_fs::{VirtualFileSystem, VirtualNode};
-use crate::workspace_watcher::WorkspaceWatcher;
-use crate::runtime_planner::{RuntimePlanner, RuntimePlan};
-use crate::runtime_builder::{RuntimeBuilder, BuildProgress, BuildResult};
+use crate::settings::{Settings, ValidationResult, DataRootRelocation, ProfileSettingsOverrides, BaseIntegrityCheck};
+use crate::safe_mode::SafeMode;
+use crate::path_utils::{get_drive_letter, is_ntfs_volume, get_free_space, format_size, same_volume, are_files_hardlinked};
+use crate::profiles::{ProfileManager, Profile, SavesMode, ImportSummary};
+use crate::virtual_fs::{VirtualFileSystem, VirtualNode, VirtualNodeSource, WorkspaceDeleteOutcome, RevertReport, DirCopyProgress, PruneReport};
+use crate::workspace_watcher::{WorkspaceWatcher, WatcherStatus, NormalizationStatus, QuarantinedFile, ActivityEntry, ACTIVITY_CAPACITY, WATCHDOG_INTERVAL};
+use crate::blob_cache::{AuditReport, IntegrityReport, IndexTotals, ProfileBlobEntry, DuplicateGroup, BlobPresence, MaintenanceOptions, MaintenanceProgress, MaintenanceReport, GcPreview};
+use crate::runtime_planner::{RuntimePlanner, RuntimePlan, RuntimePlanDiff, PlanExplanation, BaseChangeCheck, BaseFileImpact, BaseCoverage};
+use crate::runtime_builder::{RuntimeBuilder, BuildOptions, BuildProgress, BuildResult, BuildEstimate, FileApplyResult, LaunchCheck, LinkIssue, OrphanedRuntimeCleanup, ProgressCallback};
+use crate::runtime_launcher::{RuntimeLauncher, LaunchOptions, LaunchHandle, GameExit};
+use crate::logging::{self, LogFollower};
+use crate::diagnostics::{self, AppStatus, DiagnosticsExport, DiagnosticsInput, DIAGNOSTICS_LOG_LINES};
+use crate::progress::{ProgressCadence, ProgressThrottle};
 use tracing::{info, warn};
 
 /// Application state for settings
 pub type SettingsState = Mutex<Option<Settings>>;
 
+/// Application state tracking the active workspace watcher for each profile
+pub type WatcherRegistry = Mutex<HashMap<String, WorkspaceWatcher>>;
+
+/// Application state holding builds started with `start_build`, keyed by build id
+/// A finished build stays until it's awaited, or for `FINISHED_BUILD_RETENTION` if it never is
+pub type BuildRegistry = Arc<Mutex<HashMap<String, BackgroundBuild>>>;
+
+/// How long a finished build's result is kept for `await_build` before it's dropped
+const FINISHED_BUILD_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// A build started with `start_build`: its cancel flag, and its result once it ends
+pub struct BackgroundBuild {
+    cancel_flag: Arc<AtomicBool>,
+    result: tokio::sync::watch::Receiver<Option<Result<BuildResult, String>>>,
+}
+
+/// Sent as a `build_finished` event when a build started with `start_build` ends, awaited or not
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildFinished {
+    pub build_id: String,
+    pub result: Result<BuildResult, String>,
+}
+
+/// Application state holding the cancel flag of the cache maintenance in progress, if any
+pub type CacheMaintenance = Arc<Mutex<Option<Arc<AtomicBool>>>>;
+
+/// Application state holding the cancel flag of each profile switch in progress
+pub type ProfileSwitches = Mutex<HashMap<String, Arc<AtomicBool>>>;
+
+/// Application state holding the cancel flag of each profile's directory copy into its workspace in progress
+pub type WorkspaceCopies = Mutex<HashMap<String, Arc<AtomicBool>>>;
+
+/// Check the registered watchers every `WATCHDOG_INTERVAL` for as long as the app runs,
+/// restarting any whose debounce thread died
+pub fn start_watcher_watchdog(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WATCHDOG_INTERVAL);
+        let registry = app_handle.state::<WatcherRegistry>();
+        let Ok(mut watchers) = registry.lock() else {
+            continue;
+        };
+        for watcher in watchers.values_mut() {
+            watcher.supervise();
+        }
+    });
+}
+
+/// Marks a profile as building until dropped, so concurrent builds can't race on the same runtime
+/// Taken through the launcher, which refuses while the profile's game is running and won't launch it meanwhile
+struct BuildLockGuard {
+    launcher: RuntimeLauncher,
+    profile_name: String,
+}
+
+impl BuildLockGuard {
+    fn acquire(launcher: &RuntimeLauncher, profile_name: &str) -> Result<Self, String> {
+        launcher.begin_build(profile_name).map_err(|e| e.to_string())?;
+        Ok(Self { launcher: launcher.clone(), profile_name: profile_name.to_string() })
+    }
+}
+
+impl Drop for BuildLockGuard {
+    fn drop(&mut self) {
+        self.launcher.end_build(&self.profile_name);
+    }
+}
+
 /// Response for drive validation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DriveInfo {
@@ -28,6 +104,9 @@ pub struct DriveInfo {
     pub free_space_formatted: String,
     pub is_valid: bool,
     pub error_message: Option<String>,
+    /// Set when free space can't hold the files the largest profile's runtime build copies
+    #[serde(default)]
+    pub low_space_warning: Option<String>,
 }
 
 /// Response for path validation
@@ -59,15 +138,41 @@ impl From<ValidationResult> for SettingsValidation {
 }
 
 /// Load existing settings if available
+/// In safe mode settings are only read: nothing is self-healed and no maintenance is started
 #[tauri::command]
-pub async fn load_settings(state: State<'_, SettingsState>) -> Result<Option<Settings>, String> {
+pub async fn load_settings(
+    state: State<'_, SettingsState>,
+    maintenance: State<'_, CacheMaintenance>,
+    safe_mode: State<'_, SafeMode>,
+    app_handle: tauri::AppHandle
+) -> Result<Option<Settings>, String> {
     info!("Loading settings...");
+    let safe_mode = safe_mode.is_enabled();
     
     // Try to load existing settings
     if let Some(existing) = Settings::try_load_existing() {
+        // Self-heal directories a user or cleaner tool deleted, instead of failing later mid-build
+        if !existing.needs_wizard() && !safe_mode {
+            if let Err(e) = existing.ensure_data_structure() {
+                warn!("Failed to restore data directory structure: {}", e);
+            }
+        }
+        
         let mut settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
         *settings_guard = Some(existing.clone());
+        drop(settings_guard);
         info!("Existing settings loaded");
+        
+        // Opt-in maintenance runs in the background so startup isn't held up by a large cache
+        if existing.preferences.auto_maintain_cache && !existing.needs_wizard() && !safe_mode {
+            let settings = existing.clone();
+            let maintenance = maintenance.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_cache_maintenance(settings, MaintenanceOptions::default(), maintenance, app_handle).await {
+                    warn!("Automatic cache maintenance failed: {}", e);
+                }
+            });
+        }
         return Ok(Some(existing));
     }
     
@@ -75,6 +180,12 @@ pub async fn load_settings(state: State<'_, SettingsState>) -> Result<Option<Set
     Ok(None)
 }
 
+/// Whether the app was started in safe mode, with only diagnostic and repair commands available
+#[tauri::command]
+pub async fn is_safe_mode(safe_mode: State<'_, SafeMode>) -> Result<bool, String> {
+    Ok(safe_mode.is_enabled())
+}
+
 /// Check if wizard needs to be shown
 #[tauri::command]
 pub async fn needs_wizard(state: State<'_, SettingsState>) -> Result<bool, String> {
@@ -94,7 +205,7 @@ pub async fn validate_gta_base_path(path: String) -> Result<PathValidation, Stri
     let path_buf = PathBuf::from(&path);
     let exists = path_buf.exists();
     let is_directory = path_buf.is_dir();
-    let has_gta_exe = path_buf.join("gta_sa.exe").exists();
+    let has_gta_exe = path_buf.join(Settings::GAME_EXECUTABLE).exists();
     
     let is_valid = exists && is_directory && has_gta_exe;
     let error_message = if !exists {
@@ -121,19 +232,46 @@ pub async fn validate_gta_base_path(path: String) -> Result<PathValidation, Stri
 pub async fn get_drive_info(path: String) -> Result<DriveInfo, String> {
     info!("Getting drive info for path: {}", path);
     
-    let path_buf = PathBuf::from(&path);
+    drive_info_for(&PathBuf::from(&path))
+}
+
+/// Get current drive information for the configured data root, for polling free space outside the wizard
+/// Warns when free space drops below what the largest profile's runtime build copies
+#[tauri::command]
+pub async fn get_data_root_drive_info(state: State<'_, SettingsState>) -> Result<DriveInfo, String> {
+    info!("Getting drive info for the data root");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let mut drive_info = drive_info_for(&settings.data_root)?;
+    
+    let largest_copy_size = RuntimePlanner::new(settings).largest_build_copy_size()
+        .map_err(|e| format!("Failed to read runtime plans: {}", e))?;
+    if drive_info.free_space_bytes < largest_copy_size {
+        drive_info.low_space_warning = Some(format!(
+            "Only {} free, but the largest runtime build copies up to {}",
+            drive_info.free_space_formatted, format_size(largest_copy_size)
+        ));
+    }
     
-    let drive_letter = get_drive_letter(&path_buf)
+    Ok(drive_info)
+}
+
+fn drive_info_for(path_buf: &Path) -> Result<DriveInfo, String> {
+    let drive_letter = get_drive_letter(path_buf)
         .map_err(|e| format!("Failed to get drive letter: {}", e))?;
     
     let is_ntfs = if drive_letter.is_some() {
-        is_ntfs_volume(&path_buf)
+        is_ntfs_volume(path_buf)
             .map_err(|e| format!("Failed to check NTFS: {}", e))?
     } else {
         false
     };
     
-    let free_space_bytes = get_free_space(&path_buf)
+    let free_space_bytes = get_free_space(path_buf)
         .unwrap_or(0);
     
     let free_space_formatted = format_size(free_space_bytes);
@@ -156,19 +294,87 @@ pub async fn get_drive_info(path: String) -> Result<DriveInfo, String> {
         free_space_formatted,
         is_valid,
         error_message,
+        low_space_warning: None,
     })
 }
 
+/// Validate a prospective base path and data root together before setup
+/// Runs the full settings checks (same volume, NTFS, free space, game executable) without creating anything
+#[tauri::command]
+pub async fn validate_setup(base_path: String, data_root: String) -> Result<SettingsValidation, String> {
+    info!("Validating setup - Base: {}, Data: {}", base_path, data_root);
+    
+    let settings = Settings::for_wizard(PathBuf::from(base_path), PathBuf::from(data_root));
+    let validation = settings.validate()
+        .map_err(|e| format!("Validation failed: {}", e))?;
+    
+    Ok(validation.into())
+}
+
+/// Report whether each expected directory under the data root still exists
+#[tauri::command]
+pub async fn check_data_structure(
+    state: State<'_, SettingsState>
+) -> Result<Vec<(PathBuf, bool)>, String> {
+    info!("Checking data directory structure");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    Ok(settings.check_data_structure())
+}
+
+/// Recreate any missing directories under the data root, returning the ones recreated
+#[tauri::command]
+pub async fn ensure_data_structure(
+    state: State<'_, SettingsState>
+) -> Result<Vec<PathBuf>, String> {
+    info!("Restoring data directory structure");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    settings.ensure_data_structure()
+        .map_err(|e| format!("Failed to restore data structure: {}", e))
+}
+
 /// Create data root directory structure
+/// Refuses to complete setup if the base/data root pair fails validation for the overlay mode
 #[tauri::command]
-pub async fn create_data_structure(base_path: String, data_root: String, state: State<'_, SettingsState>) -> Result<(), String> {
-    info!("Creating data structure - Base: {}, Data: {}", base_path, data_root);
+pub async fn create_data_structure(
+    base_path: String,
+    data_root: String,
+    overlay_mode: Option<String>,
+    state: State<'_, SettingsState>
+) -> Result<(), String> {
+    info!("Creating data structure - Base: {}, Data: {}, Mode: {:?}", base_path, data_root, overlay_mode);
     
     let base_path_buf = PathBuf::from(base_path);
     let data_root_buf = PathBuf::from(data_root);
     
-    // Create settings
-    let mut settings = Settings::for_wizard(base_path_buf, data_root_buf);
+    // Create settings, keeping existing preferences when the wizard is re-run on the same data root
+    let existing = state.lock().map_err(|e| format!("State lock error: {}", e))?.clone();
+    let mut settings = match existing {
+        Some(mut existing) if existing.data_root == data_root_buf => {
+            existing.base_path = base_path_buf;
+            existing
+        }
+        _ => Settings::for_wizard(base_path_buf, data_root_buf),
+    };
+    if let Some(overlay_mode) = overlay_mode {
+        settings.overlay_mode = overlay_mode;
+    }
+    
+    // Validate before touching the disk so a broken configuration can't complete the wizard
+    let validation = settings.validate_for_setup()
+        .map_err(|e| e.to_string())?;
+    for warning in &validation.warnings {
+        warn!("Setup warning: {}", warning);
+    }
     
     // Create directory structure
     settings.create_data_structure()
@@ -181,6 +387,11 @@ pub async fn create_data_structure(base_path: String, data_root: String, state:
     settings.save_to_data_root()
         .map_err(|e| format!("Failed to save settings: {}", e))?;
     
+    // Remember where the data root is so settings can be found on next launch
+    if let Err(e) = settings.save_data_root_pointer() {
+        warn!("Failed to save data root pointer: {}", e);
+    }
+    
     // Update state
     let mut settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     *settings_guard = Some(settings);
@@ -189,6 +400,68 @@ pub async fn create_data_structure(base_path: String, data_root: String, state:
     Ok(())
 }
 
+/// Move the data root to a new location (or re-point settings at one already moved by hand)
+/// Watchers are stopped first since they hold handles inside the workspaces
+#[tauri::command]
+pub async fn relocate_data_root(
+    new_root: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<DataRootRelocation, String> {
+    info!("Relocating data root to: {}", new_root);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    {
+        let mut watchers = watchers.lock()
+            .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+        for (profile_name, mut watcher) in watchers.drain() {
+            watcher.stop_watching();
+            info!("Stopped workspace watcher for profile: {}", profile_name);
+        }
+    }
+    
+    let relocation = settings.relocate_data_root(PathBuf::from(new_root))
+        .map_err(|e| format!("Failed to relocate data root: {}", e))?;
+    
+    if let Err(e) = relocation.settings.save_data_root_pointer() {
+        warn!("Failed to save data root pointer: {}", e);
+    }
+    
+    let mut settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    *settings_guard = Some(relocation.settings.clone());
+    
+    Ok(relocation)
+}
+
+/// Reset the wizard so it is shown again, optionally forgetting the configured paths
+/// Only settings are changed; profiles and the cache on disk are left alone
+#[tauri::command]
+pub async fn reset_wizard(clear_paths: bool, state: State<'_, SettingsState>) -> Result<Settings, String> {
+    info!("Resetting wizard (clear paths: {})", clear_paths);
+    
+    let mut settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_mut()
+        .ok_or("Settings not loaded")?;
+    
+    // Persist to the current data root before its path is possibly cleared
+    let settings_path = settings.data_root.join(Settings::SETTINGS_FILE);
+    settings.reset_wizard(clear_paths);
+    settings.save(&settings_path)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    
+    Ok(settings.clone())
+}
+
+/// Re-run the wizard keeping the current data root so the base path can be re-picked
+#[tauri::command]
+pub async fn relaunch_wizard(state: State<'_, SettingsState>) -> Result<Settings, String> {
+    reset_wizard(false, state).await
+}
+
 /// Validate current settings
 #[tauri::command]
 pub async fn validate_settings(state: State<'_, SettingsState>) -> Result<SettingsValidation, String> {
@@ -208,6 +481,37 @@ pub async fn validate_settings(state: State<'_, SettingsState>) -> Result<Settin
     }
 }
 
+/// Hash the base game executable and check it against the built-in and configured known-good hashes
+/// With a profile, checks the base install that profile runs on, which may be its own override
+#[tauri::command]
+pub async fn verify_base_integrity(
+    profile_name: Option<String>,
+    state: State<'_, SettingsState>
+) -> Result<BaseIntegrityCheck, String> {
+    info!("Verifying the base game executable");
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let base_path = match &profile_name {
+        Some(profile_name) => {
+            let manager = ProfileManager::new(settings.data_root.join("profiles"));
+            let profile = manager.get_profile(profile_name)
+                .map_err(|e| format!("Failed to get profile: {}", e))?
+                .ok_or(format!("Profile '{}' not found", profile_name))?;
+            profile.effective_base_path(&settings.base_path)
+        }
+        None => settings.base_path.clone(),
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || settings.verify_base_integrity(&base_path))
+        .await
+        .map_err(|e| format!("Base verification task failed: {}", e))?
+        .map_err(|e| format!("Failed to verify base integrity: {}", e))
+}
+
 /// Get current settings
 #[tauri::command]
 pub async fn get_settings(state: State<'_, SettingsState>) -> Result<Option<Settings>, String> {
@@ -335,17 +639,27 @@ pub struct ProfileInfo {
     pub description: Option<String>,
     pub workspace_path: String,
     pub saves_path: String,
+    pub base_path_override: Option<String>,
+    pub saves_mode: SavesMode,
 }
 
-impl From<Profile> for ProfileInfo {
-    fn from(profile: Profile) -> Self {
+impl ProfileInfo {
+    /// Describe a profile for the frontend; the saves path is its shared pool's when it uses one
+    fn new(profile: Profile, data_root: &Path) -> Self {
+        let saves_dir = profile.effective_saves_dir(data_root).unwrap_or_else(|e| {
+            warn!("Failed to resolve saves directory for profile {}: {}", profile.metadata.name, e);
+            profile.saves_dir.clone()
+        });
         Self {
             name: profile.metadata.name,
             created_at: profile.metadata.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
             last_used: profile.metadata.last_used.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
             description: profile.metadata.description,
             workspace_path: profile.workspace_dir.to_string_lossy().to_string(),
-            saves_path: profile.saves_dir.to_string_lossy().to_string(),
+            saves_path: saves_dir.to_string_lossy().to_string(),
+            base_path_override: profile.metadata.base_path_override
+                .map(|path| path.to_string_lossy().to_string()),
+            saves_mode: profile.metadata.saves_mode,
         }
     }
 }
@@ -364,7 +678,7 @@ pub async fn create_profile(
         .ok_or("Settings not loaded")?;
     
     let profiles_root = settings.data_root.join("profiles");
-    let cache_root = settings.data_root.join("cache");
+    let cache_root = settings.get_cache_directory();
     
     // CRITICAL: Validate that profiles and cache are on the same volume for hardlinks
     let same_vol = same_volume(&profiles_root, &cache_root)
@@ -383,15 +697,20 @@ pub async fn create_profile(
     let profile = manager.create_profile(name)
         .map_err(|e| format!("Failed to create profile: {}", e))?;
     
-    Ok(ProfileInfo::from(profile))
+    Ok(ProfileInfo::new(profile, &settings.data_root))
 }
 
-/// List all profiles
+/// List profiles, most recently used first
+///
+/// `filter` matches names case-insensitively (substring or wildcard); `offset`/`limit` page the sorted results.
 #[tauri::command]
 pub async fn list_profiles(
+    filter: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
     state: State<'_, SettingsState>
 ) -> Result<Vec<ProfileInfo>, String> {
-    info!("Listing profiles");
+    info!("Listing profiles (filter: {:?}, offset: {:?}, limit: {:?})", filter, offset, limit);
     
     // Get settings to find profiles directory
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
@@ -401,10 +720,14 @@ pub async fn list_profiles(
     let profiles_root = settings.data_root.join("profiles");
     let manager = ProfileManager::new(profiles_root);
     
-    let profiles = manager.list_profiles()
+    let profiles = manager.list_profiles_filtered(filter.as_deref())
         .map_err(|e| format!("Failed to list profiles: {}", e))?;
     
-    Ok(profiles.into_iter().map(ProfileInfo::from).collect())
+    Ok(profiles.into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|profile| ProfileInfo::new(profile, &settings.data_root))
+        .collect())
 }
 
 /// Rename a profile
@@ -412,10 +735,14 @@ pub async fn list_profiles(
 pub async fn rename_profile(
     old_name: String,
     new_name: String,
-    state: State<'_, SettingsState>
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>
 ) -> Result<ProfileInfo, String> {
     info!("Renaming profile: {} -> {}", old_name, new_name);
     
+    // The watcher is keyed by name and watches the old workspace path
+    stop_workspace_watcher_internal(&old_name, &watchers).await?;
+    
     // Get settings to find profiles directory
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
@@ -427,17 +754,21 @@ pub async fn rename_profile(
     let profile = manager.rename_profile(&old_name, new_name)
         .map_err(|e| format!("Failed to rename profile: {}", e))?;
     
-    Ok(ProfileInfo::from(profile))
+    Ok(ProfileInfo::new(profile, &settings.data_root))
 }
 
 /// Delete a profile
 #[tauri::command]
 pub async fn delete_profile(
     name: String,
-    state: State<'_, SettingsState>
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>
 ) -> Result<(), String> {
     info!("Deleting profile: {}", name);
     
+    // Stop watching the workspace before it is removed
+    stop_workspace_watcher_internal(&name, &watchers).await?;
+    
     // Get settings to find profiles directory
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
@@ -452,6 +783,154 @@ pub async fn delete_profile(
     Ok(())
 }
 
+/// Set or clear the base installation override for a profile
+#[tauri::command]
+pub async fn set_profile_base_path(
+    name: String,
+    base_path: Option<String>,
+    state: State<'_, SettingsState>
+) -> Result<ProfileInfo, String> {
+    info!("Setting base path override for profile {}: {:?}", name, base_path);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let mut profile = manager.get_profile(&name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", name))?;
+    
+    // Validate the override the same way builds will before persisting it
+    let base_path = base_path.map(PathBuf::from);
+    profile.metadata.base_path_override = base_path.clone();
+    RuntimePlanner::new(settings.clone()).resolve_base_path(&profile)
+        .map_err(|e| format!("Invalid base path override: {}", e))?;
+    
+    let profile = manager.set_base_path_override(&name, base_path)
+        .map_err(|e| format!("Failed to set base path override: {}", e))?;
+    
+    Ok(ProfileInfo::new(profile, &settings.data_root))
+}
+
+/// Result of importing an existing modded install
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileImport {
+    pub profile: ProfileInfo,
+    pub summary: ImportSummary,
+}
+
+/// Create a profile from an existing modded install, keeping only what differs from the base game
+#[tauri::command]
+pub async fn import_profile(
+    name: String,
+    modded_dir: String,
+    state: State<'_, SettingsState>
+) -> Result<ProfileImport, String> {
+    info!("Importing profile {} from: {}", name, modded_dir);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    let cache = settings.blob_cache();
+    
+    let (profile, summary) = manager.import_from_directory(name, &PathBuf::from(modded_dir), &settings.base_path, &cache, settings.preferences.keeps_workspace_copies())
+        .map_err(|e| format!("Failed to import profile: {}", e))?;
+    
+    Ok(ProfileImport {
+        profile: ProfileInfo::new(profile, &settings.data_root),
+        summary,
+    })
+}
+
+/// Set whether a profile's saves are isolated or shared through a named pool
+#[tauri::command]
+pub async fn set_profile_saves_mode(
+    name: String,
+    saves_mode: SavesMode,
+    state: State<'_, SettingsState>
+) -> Result<ProfileInfo, String> {
+    info!("Setting saves mode for profile {}: {:?}", name, saves_mode);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.set_saves_mode(&name, saves_mode)
+        .map_err(|e| format!("Failed to set saves mode: {}", e))?;
+    
+    // Create the shared pool up front so it exists before the first launch
+    profile.effective_saves_dir(&settings.data_root)
+        .map_err(|e| format!("Failed to prepare saves directory: {}", e))?;
+    
+    Ok(ProfileInfo::new(profile, &settings.data_root))
+}
+
+/// Set the preferences a profile overrides, validated like the global preferences
+/// Takes effect the next time the profile's watcher starts or the profile is built
+#[tauri::command]
+pub async fn set_profile_settings_overrides(
+    name: String,
+    overrides: ProfileSettingsOverrides,
+    state: State<'_, SettingsState>
+) -> Result<ProfileInfo, String> {
+    info!("Setting settings overrides for profile {}: {:?}", name, overrides);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let validation = settings.validate_overrides(&overrides);
+    if !validation.is_valid() {
+        return Err(format!("Invalid settings overrides: {}", validation.errors.join("; ")));
+    }
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.set_settings_overrides(&name, overrides)
+        .map_err(|e| format!("Failed to set settings overrides: {}", e))?;
+    
+    Ok(ProfileInfo::new(profile, &settings.data_root))
+}
+
+/// Open a profile in the UI: bump its last used time and make sure its workspace is watched
+#[tauri::command]
+pub async fn open_profile(
+    name: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle
+) -> Result<ProfileInfo, String> {
+    info!("Opening profile: {}", name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    let profile = manager.touch_profile(&name)
+        .map_err(|e| format!("Failed to open profile: {}", e))?;
+    
+    ensure_workspace_watcher_running(&name, &state, &watchers, app_handle).await?;
+    
+    Ok(ProfileInfo::new(profile, &settings.data_root))
+}
+
 /// Open profile workspace in file explorer
 #[tauri::command]
 pub async fn open_profile_workspace(
@@ -501,12 +980,13 @@ pub async fn get_virtual_file_tree(
     profile_name: String,
     virtual_path: Option<String>,
     state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
     app_handle: tauri::AppHandle,
 ) -> Result<VirtualNode, String> {
     info!("Getting virtual file tree for profile: {} at path: {:?}", profile_name, virtual_path);
     
     // Auto-start workspace watcher for this profile
-    let _ = ensure_workspace_watcher_running(&profile_name, &state, app_handle).await;
+    let _ = ensure_workspace_watcher_running(&profile_name, &state, &watchers, app_handle).await;
     
     // Get settings to find paths
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
@@ -521,7 +1001,12 @@ pub async fn get_virtual_file_tree(
         .ok_or(format!("Profile '{}' not found", profile_name))?;
     
     // Create virtual file system
-    let mut vfs = VirtualFileSystem::new(settings.base_path.clone(), profile.workspace_dir);
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    vfs.set_follow_links(settings.preferences.follow_links);
+    vfs.set_data_root(settings.data_root.clone());
+    vfs.set_excluded_base_globs(&settings.preferences.excluded_base_globs)
+        .map_err(|e| format!("Failed to set excluded base globs: {:#}", e))?;
     vfs.initialize()
         .map_err(|e| format!("Failed to initialize virtual file system: {}", e))?;
     
@@ -532,15 +1017,23 @@ pub async fn get_virtual_file_tree(
     Ok(tree)
 }
 
-/// Revert a workspace file to original (remove workspace override)
-/// This only works on files that exist in the workspace AND have a base file
+/// Physical location of a virtual file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhysicalPathInfo {
+    pub physical_path: String,
+    pub source: VirtualNodeSource,
+    pub writable: bool,
+    pub warning: Option<String>,
+}
+
+/// Resolve a virtual path to the real on-disk file backing it
 #[tauri::command]
-pub async fn revert_to_original(
+pub async fn resolve_virtual_path(
     profile_name: String,
     virtual_path: String,
     state: State<'_, SettingsState>
-) -> Result<(), String> {
-    info!("Reverting to original: {} in profile: {}", virtual_path, profile_name);
+) -> Result<PhysicalPathInfo, String> {
+    info!("Resolving physical path: {} in profile: {}", virtual_path, profile_name);
     
     // Get settings to find paths
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
@@ -554,28 +1047,166 @@ pub async fn revert_to_original(
         .map_err(|e| format!("Failed to get profile: {}", e))?
         .ok_or(format!("Profile '{}' not found", profile_name))?;
     
-    // Create virtual file system and use its revert method
-    let vfs = VirtualFileSystem::new(settings.base_path.clone(), profile.workspace_dir);
-    vfs.revert_to_original(&virtual_path)
-        .map_err(|e| format!("Failed to revert to original: {}", e))?;
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    let node = vfs.get_node(&virtual_path)
+        .map_err(|e| format!("Failed to get virtual node: {}", e))?;
+    let physical_path = vfs.resolve_physical(&virtual_path)
+        .map_err(|e| format!("Failed to resolve physical path: {}", e))?;
+    
+    // Editing a base file in place would modify the clean install
+    let warning = if node.source == VirtualNodeSource::Base {
+        warn!("Resolved base file {} - editing it directly modifies the clean install", virtual_path);
+        Some("This file belongs to the clean base install. Copy it to the workspace before editing.".to_string())
+    } else if node.source == VirtualNodeSource::Shared {
+        warn!("Resolved shared file {} - editing it directly changes every profile", virtual_path);
+        Some("This file belongs to the shared workspace used by every profile. Copy it to the workspace to change it for this profile only.".to_string())
+    } else {
+        None
+    };
+    
+    Ok(PhysicalPathInfo {
+        physical_path: physical_path.to_string_lossy().to_string(),
+        source: node.source,
+        writable: node.writable,
+        warning,
+    })
+}
 
-    // Clean up blob reference if the file was normalized
-    let cache_dir = settings.get_cache_directory();
-    let cache = crate::blob_cache::BlobCache::new(cache_dir);
+/// Open a file for editing, copying base files into the workspace first
+/// Returns the physical path that was opened
+#[tauri::command]
+pub async fn edit_file(
+    profile_name: String,
+    virtual_path: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    info!("Opening file for editing: {} in profile: {}", virtual_path, profile_name);
     
-    // Try to find and remove any blob reference for this workspace file
-    match crate::workspace_watcher::WorkspaceWatcher::find_blob_by_reference(
-        &cache, 
-        &profile_name, 
-        &virtual_path
-    ) {
-        Ok(blob_hash) => {
-            let blob_path = crate::blob_cache::BlobPath {
-                hash: blob_hash,
-                path: cache.get_blob_path(&blob_hash),
-            };
-            
-            match cache.remove_ref(&blob_path, &profile_name, &virtual_path) {
+    // Make sure edits get normalized once the editor saves them; without a watcher the edit still opens
+    if let Err(e) = ensure_workspace_watcher_running(&profile_name, &state, &watchers, app_handle).await {
+        warn!("Workspace watcher failed to start for profile {}: {}", profile_name, e);
+    }
+    
+    // Get settings to find paths
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?;
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    vfs.set_keep_workspace_copies(settings.preferences.keeps_workspace_copies());
+    let node = vfs.get_node(&virtual_path)
+        .map_err(|e| format!("Failed to get virtual node: {}", e))?;
+    
+    if node.is_directory {
+        return Err(format!("Cannot edit a directory: {}", virtual_path));
+    }
+    
+    // Base and shared files are read-only, so make a workspace copy to edit instead
+    if matches!(node.source, VirtualNodeSource::Base | VirtualNodeSource::Shared) {
+        let cache = settings.blob_cache();
+        vfs.copy_to_workspace(&profile_name, &virtual_path, &cache)
+            .map_err(|e| format!("Failed to copy to workspace: {}", e))?;
+    }
+    
+    let physical_path = vfs.resolve_physical(&virtual_path)
+        .map_err(|e| format!("Failed to resolve physical path: {}", e))?;
+    let physical_path = crate::long_path::from_long_path(&physical_path);
+    
+    shell_open(&physical_path)?;
+    
+    info!("Opened {} in editor: {}", virtual_path, physical_path.display());
+    Ok(physical_path.to_string_lossy().to_string())
+}
+
+/// Open a file with its associated application via the Windows shell "open" verb
+#[cfg(windows)]
+fn shell_open(path: &std::path::Path) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+    
+    let path_wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            w!("open"),
+            PCWSTR(path_wide.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    
+    // ShellExecuteW returns a value greater than 32 on success
+    if result.0 as isize <= 32 {
+        return Err(format!("Failed to open {} (shell error {})", path.display(), result.0 as isize));
+    }
+    
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn shell_open(_path: &std::path::Path) -> Result<(), String> {
+    Err("Opening files in an editor is only supported on Windows".to_string())
+}
+
+/// Revert a workspace file to original (remove workspace override)
+/// This only works on files that exist in the workspace AND have a base file
+#[tauri::command]
+pub async fn revert_to_original(
+    profile_name: String,
+    virtual_path: String,
+    state: State<'_, SettingsState>
+) -> Result<(), String> {
+    info!("Reverting to original: {} in profile: {}", virtual_path, profile_name);
+    
+    // Get settings to find paths
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?;
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    // Create virtual file system and use its revert method
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    vfs.revert_to_original(&virtual_path)
+        .map_err(|e| format!("Failed to revert to original: {}", e))?;
+
+    // Clean up blob reference if the file was normalized
+    let cache = settings.blob_cache();
+    
+    // Try to find and remove any blob reference for this workspace file
+    match crate::workspace_watcher::WorkspaceWatcher::find_blob_by_reference(
+        &cache, 
+        &profile_name, 
+        &virtual_path
+    ) {
+        Ok(blob_hash) => {
+            let blob_path = crate::blob_cache::BlobPath {
+                hash: blob_hash,
+                path: cache.get_blob_path(&blob_hash),
+            };
+            
+            match cache.remove_ref(&blob_path, &profile_name, &virtual_path) {
                 Ok(should_gc) => {
                     info!("Removed blob reference for reverted file: {} | Should GC: {}", virtual_path, should_gc);
                     
@@ -602,14 +1233,14 @@ pub async fn revert_to_original(
     Ok(())
 }
 
-/// Delete a workspace file that doesn't exist in base
-/// This removes workspace-only files and cleans up blob references
+/// Delete a workspace file and clean up its blob reference
+/// Reports whether a base file is revealed (override) or the file is gone (workspace-only)
 #[tauri::command]
 pub async fn delete_workspace_file(
     profile_name: String,
     virtual_path: String,
     state: State<'_, SettingsState>
-) -> Result<(), String> {
+) -> Result<WorkspaceDeleteOutcome, String> {
     info!("Deleting workspace file: {} in profile: {}", virtual_path, profile_name);
     
     // Get settings to find paths
@@ -624,54 +1255,73 @@ pub async fn delete_workspace_file(
         .map_err(|e| format!("Failed to get profile: {}", e))?
         .ok_or(format!("Profile '{}' not found", profile_name))?;
     
-    let workspace_file_path = profile.workspace_dir.join(&virtual_path);
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir.clone());
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    let cache = settings.blob_cache();
     
-    // Only delete if file exists in workspace
-    if !workspace_file_path.exists() {
-        return Err("File does not exist in workspace".to_string());
-    }
+    let outcome = vfs.delete_workspace_file(&profile_name, &virtual_path, &cache)
+        .map_err(|e| format!("Failed to delete workspace file: {}", e))?;
     
-    // Remove blob reference before deleting file
-    let cache_dir = settings.get_cache_directory();
-    let cache = crate::blob_cache::BlobCache::new(cache_dir);
+    info!("Deleted workspace file: {} in profile: {} ({:?})", virtual_path, profile_name, outcome);
+    Ok(outcome)
+}
+
+/// Revert a selection of workspace files in one go, continuing past individual failures
+/// Workspace-only files are only deleted when `delete_workspace_only` is set, since there's no base file to reveal
+#[tauri::command]
+pub async fn revert_many(
+    profile_name: String,
+    rel_paths: Vec<String>,
+    delete_workspace_only: bool,
+    state: State<'_, SettingsState>
+) -> Result<RevertReport, String> {
+    info!("Reverting {} files in profile: {}", rel_paths.len(), profile_name);
     
-    if let Ok(blob_hash) = crate::workspace_watcher::WorkspaceWatcher::find_blob_by_reference(
-        &cache, 
-        &profile_name, 
-        &virtual_path
-    ) {
-        let blob_path = crate::blob_cache::BlobPath {
-            hash: blob_hash,
-            path: cache.get_blob_path(&blob_hash),
-        };
-        
-        // Remove reference and check if blob should be GC'd
-        match cache.remove_ref(&blob_path, &profile_name, &virtual_path) {
-            Ok(should_gc) => {
-                info!("Removed blob reference for {}: should_gc={}", virtual_path, should_gc);
-                if should_gc {
-                    // Delete the blob immediately since no profiles reference it
-                    if blob_path.path.exists() {
-                        if let Err(e) = std::fs::remove_file(&blob_path.path) {
-                            warn!("Failed to delete unreferenced blob {}: {}", blob_path.path.display(), e);
-                        } else {
-                            debug!("Deleted unreferenced blob: {}", blob_path.path.display());
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                warn!("Failed to remove blob reference for {}: {}", virtual_path, e);
-            }
-        }
-    }
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
     
-    // Delete the workspace file
-    std::fs::remove_file(&workspace_file_path)
-        .map_err(|e| format!("Failed to delete workspace file: {}", e))?;
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
     
-    info!("Deleted workspace file: {} in profile: {}", virtual_path, profile_name);
-    Ok(())
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir.clone());
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    
+    vfs.revert_many(&profile_name, &rel_paths, &settings.blob_cache(), delete_workspace_only)
+        .map_err(|e| format!("Failed to revert files: {}", e))
+}
+
+/// Move (rename) a directory in a profile's workspace; its files keep their blobs instead of being re-normalized
+/// Returns the number of blob references moved
+#[tauri::command]
+pub async fn move_workspace_dir(
+    profile_name: String,
+    from: String,
+    to: String,
+    state: State<'_, SettingsState>
+) -> Result<usize, String> {
+    info!("Moving workspace directory: {} -> {} in profile: {}", from, to, profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    let vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir.clone());
+    vfs.move_workspace_dir(&profile_name, &from, &to, &settings.blob_cache())
+        .map_err(|e| format!("Failed to move workspace directory: {}", e))
 }
 
 /// Debug command to inspect blob cache state for a file
@@ -693,8 +1343,7 @@ pub async fn debug_blob_cache(
         .map_err(|e| format!("Failed to get profile: {}", e))?
         .ok_or(format!("Profile '{}' not found", profile_name))?;
     
-    let cache_dir = settings.get_cache_directory();
-    let cache = crate::blob_cache::BlobCache::new(cache_dir);
+    let cache = settings.blob_cache();
     let workspace_file_path = profile.workspace_dir.join(&virtual_path);
     
     let mut debug_info = Vec::new();
@@ -713,14 +1362,10 @@ pub async fn debug_blob_cache(
                 debug_info.push(format!("Blob exists in cache: {}", blob_path.exists()));
                 debug_info.push(format!("Expected blob path: {}", blob_path.display()));
                 
-                // Check if files are hardlinked (Windows)
+                // Check if files are hardlinked
                 if blob_path.exists() {
-                    #[cfg(windows)]
-                    {
-                        use crate::workspace_watcher::are_files_hardlinked;
-                        let is_hardlinked = are_files_hardlinked(&workspace_file_path, &blob_path);
-                        debug_info.push(format!("Files are hardlinked: {}", is_hardlinked));
-                    }
+                    let is_hardlinked = are_files_hardlinked(&workspace_file_path, &blob_path);
+                    debug_info.push(format!("Files are hardlinked: {}", is_hardlinked));
                 }
                 
                 // Check blob references
@@ -766,7 +1411,7 @@ pub async fn debug_blob_cache(
         Ok(index) => {
             let profile_refs: Vec<_> = index.refs.iter()
                 .flat_map(|(hash, refs)| {
-                    refs.iter().filter(|r| r.profile == profile_name)
+                    refs.iter().filter(|r| cache.local_profile(&r.profile) == Some(profile_name.as_str()))
                         .map(|r| (hash.clone(), r.rel_path.clone()))
                 })
                 .collect();
@@ -784,179 +1429,1226 @@ pub async fn debug_blob_cache(
     Ok(debug_info.join("\n"))
 }
 
-/// Copy a base file to workspace (make it editable)
+/// Get the blob cache directory in use (the configured cache root or data_root/cache)
 #[tauri::command]
-pub async fn copy_to_workspace(
-    profile_name: String,
-    virtual_path: String,
+pub async fn get_cache_directory(
     state: State<'_, SettingsState>
-) -> Result<(), String> {
-    info!("Copying to workspace: {} in profile: {}", virtual_path, profile_name);
-    
-    // Get settings to find paths
+) -> Result<String, String> {
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
         .ok_or("Settings not loaded")?;
     
-    let profiles_root = settings.data_root.join("profiles");
-    let manager = ProfileManager::new(profiles_root);
-    
-    let profile = manager.get_profile(&profile_name)
-        .map_err(|e| format!("Failed to get profile: {}", e))?
-        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    Ok(settings.get_cache_directory().to_string_lossy().to_string())
+}
+
+/// Result of a cache garbage collection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheCleanup {
+    /// Unreferenced blobs deleted
+    pub blobs_collected: usize,
+    /// Empty shard directories removed by compaction
+    pub directories_removed: usize,
+}
+
+/// Delete unreferenced, unpinned blobs, optionally compacting empty shard directories afterwards
+#[tauri::command]
+pub async fn garbage_collect_cache(
+    compact: bool,
+    state: State<'_, SettingsState>
+) -> Result<CacheCleanup, String> {
+    info!("Garbage collecting blob cache (compact: {})", compact);
     
-    // Create virtual file system
-    let vfs = VirtualFileSystem::new(settings.base_path.clone(), profile.workspace_dir);
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
     
-    // Copy to workspace
-    vfs.copy_to_workspace(&virtual_path)
-        .map_err(|e| format!("Failed to copy to workspace: {}", e))?;
+    let cache = settings.blob_cache();
+    let blobs_collected = cache.garbage_collect_all()
+        .map_err(|e| format!("Failed to garbage collect cache: {}", e))?;
+    let directories_removed = if compact {
+        cache.compact()
+            .map_err(|e| format!("Failed to compact cache: {}", e))?
+    } else {
+        0
+    };
     
-    Ok(())
+    info!("Cache cleanup complete: {} blobs collected, {} directories removed", blobs_collected, directories_removed);
+    Ok(CacheCleanup { blobs_collected, directories_removed })
 }
 
-// =============================================================================
-// Workspace Watcher (Auto-running)
-// =============================================================================
-
-use once_cell::sync::Lazy;
-
-type WatcherRegistry = Arc<Mutex<HashMap<String, crate::workspace_watcher::WorkspaceWatcher>>>;
-
-static ACTIVE_WATCHERS: Lazy<WatcherRegistry> = Lazy::new(|| {
-    Arc::new(Mutex::new(HashMap::new()))
-});
-
-/// Automatically ensure workspace watcher is running for a profile
-/// Called internally whenever a profile is accessed
-async fn ensure_workspace_watcher_running(
-    profile_name: &str,
-    state: &State<'_, SettingsState>,
-    app_handle: tauri::AppHandle,
-) -> Result<(), String> {
-    let mut watchers = ACTIVE_WATCHERS.lock()
-        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
-    
-    // Check if watcher is already running for this profile
-    if watchers.contains_key(profile_name) {
-        debug!("Workspace watcher already running for profile: {}", profile_name);
-        return Ok(());
-    }
+/// Count the blobs and bytes garbage collection would reclaim, without deleting anything
+#[tauri::command]
+pub async fn gc_preview(state: State<'_, SettingsState>) -> Result<GcPreview, String> {
+    info!("Previewing blob cache garbage collection");
     
-    // Get settings and profile info
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
-        .ok_or("Settings not loaded")?;
-    
-    let profiles_root = settings.data_root.join("profiles");
-    let manager = ProfileManager::new(profiles_root);
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
     
-    let profile = manager.get_profile(profile_name)
-        .map_err(|e| format!("Failed to get profile: {}", e))?
-        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    settings.blob_cache().gc_preview()
+        .map_err(|e| format!("Failed to preview garbage collection: {}", e))
+}
+
+/// Optimize the cache in one step: garbage collect, then compact empty shard directories
+/// Progress is emitted as `cache_maintenance_progress` events; `cancel_cache_maintenance` stops it between blobs
+#[tauri::command]
+pub async fn maintain_cache(
+    options: Option<MaintenanceOptions>,
+    state: State<'_, SettingsState>,
+    maintenance: State<'_, CacheMaintenance>,
+    app_handle: tauri::AppHandle
+) -> Result<MaintenanceReport, String> {
+    info!("Running cache maintenance");
     
-    // Create and start workspace watcher
-    let mut watcher = WorkspaceWatcher::new(
-        profile_name.to_string(),
-        profile.workspace_dir,
-    ).map_err(|e| format!("Failed to create workspace watcher: {}", e))?;
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
     
-    watcher.set_app_handle(app_handle);
-    watcher.start_watching()
-        .map_err(|e| format!("Failed to start workspace watcher: {}", e))?;
+    run_cache_maintenance(settings, options.unwrap_or_default(), maintenance.inner().clone(), app_handle).await
+}
+
+/// Run cache maintenance on a blocking thread, refusing to start while another run is in progress
+async fn run_cache_maintenance(
+    settings: Settings,
+    options: MaintenanceOptions,
+    maintenance: CacheMaintenance,
+    app_handle: tauri::AppHandle
+) -> Result<MaintenanceReport, String> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = maintenance.lock().map_err(|e| format!("Maintenance lock error: {}", e))?;
+        if active.is_some() {
+            return Err("Cache maintenance is already running".to_string());
+        }
+        *active = Some(cancel_flag.clone());
+    }
     
-    info!("Auto-started workspace watcher for profile: {}", profile_name);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        settings.blob_cache().maintain(&options, &cancel_flag, |progress: MaintenanceProgress| {
+            if let Err(e) = app_handle.emit("cache_maintenance_progress", &progress) {
+                warn!("Failed to emit cache maintenance progress: {}", e);
+            }
+        })
+    }).await;
     
-    // Store the watcher
-    watchers.insert(profile_name.to_string(), watcher);
+    if let Ok(mut active) = maintenance.lock() {
+        *active = None;
+    }
     
-    Ok(())
+    let report = result
+        .map_err(|e| format!("Maintenance task failed: {}", e))?
+        .map_err(|e| format!("Failed to maintain cache: {}", e))?;
+    info!(
+        "Cache maintenance complete: {} blobs ({} bytes) collected, {} directories removed in {}ms{}",
+        report.blobs_collected,
+        report.gc_bytes_reclaimed,
+        report.directories_removed,
+        report.total_time_ms,
+        if report.cancelled { " (cancelled)" } else { "" }
+    );
+    Ok(report)
 }
 
-/// Compute runtime plan for a profile
+/// Cancel the cache maintenance in progress; returns false if none is running
 #[tauri::command]
-pub async fn compute_runtime_plan(
-    profile_name: String,
+pub async fn cancel_cache_maintenance(
+    maintenance: State<'_, CacheMaintenance>
+) -> Result<bool, String> {
+    info!("Cancelling cache maintenance");
+    
+    let active = maintenance.lock().map_err(|e| format!("Maintenance lock error: {}", e))?;
+    match active.as_ref() {
+        Some(cancel_flag) => {
+            cancel_flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Pin a blob so garbage collection keeps it even when no profile references it
+#[tauri::command]
+pub async fn pin_blob(
+    hash: String,
     state: State<'_, SettingsState>
-) -> Result<RuntimePlan, String> {
-    info!("Computing runtime plan for profile: {}", profile_name);
+) -> Result<(), String> {
+    info!("Pinning blob: {}", hash);
     
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
         .ok_or("Settings not loaded")?.clone();
     drop(settings_guard);
     
-    let planner = RuntimePlanner::new(settings);
-    planner.compute_plan(&profile_name)
-        .map_err(|e| format!("Failed to compute runtime plan: {}", e))
+    let hash = blake3::Hash::from_hex(&hash)
+        .map_err(|e| format!("Invalid blob hash: {}", e))?;
+    settings.blob_cache().pin(&hash)
+        .map_err(|e| format!("Failed to pin blob: {}", e))
 }
 
-/// Build runtime for a profile with progress updates
+/// Unpin a blob, returning whether it was pinned
 #[tauri::command]
-pub async fn build_runtime(
-    profile_name: String,
-    state: State<'_, SettingsState>,
-    app_handle: tauri::AppHandle
-) -> Result<BuildResult, String> {
-    info!("Building runtime for profile: {}", profile_name);
+pub async fn unpin_blob(
+    hash: String,
+    state: State<'_, SettingsState>
+) -> Result<bool, String> {
+    info!("Unpinning blob: {}", hash);
     
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
         .ok_or("Settings not loaded")?.clone();
     drop(settings_guard);
     
-    let builder = RuntimeBuilder::new(settings);
-    
-    // Create progress callback that emits events to the frontend
-    let app_handle_clone = app_handle.clone();
-    let progress_callback = Arc::new(move |progress: BuildProgress| {
-        if let Err(e) = app_handle_clone.emit("build_progress", &progress) {
-            warn!("Failed to emit build progress: {}", e);
-        }
-    });
-    
-    builder.build_runtime(&profile_name, Some(progress_callback))
-        .map_err(|e| format!("Failed to build runtime: {}", e))
+    let hash = blake3::Hash::from_hex(&hash)
+        .map_err(|e| format!("Invalid blob hash: {}", e))?;
+    settings.blob_cache().unpin(&hash)
+        .map_err(|e| format!("Failed to unpin blob: {}", e))
 }
 
-/// Get or load existing runtime plan for a profile
+/// Audit blob references against workspaces and blob storage (read-only)
 #[tauri::command]
-pub async fn get_runtime_plan(
-    profile_name: String,
+pub async fn audit_cache(
     state: State<'_, SettingsState>
-) -> Result<Option<RuntimePlan>, String> {
-    info!("Getting runtime plan for profile: {}", profile_name);
+) -> Result<AuditReport, String> {
+    info!("Auditing blob cache references");
     
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
         .ok_or("Settings not loaded")?.clone();
     drop(settings_guard);
     
-    let planner = RuntimePlanner::new(settings);
-    planner.load_plan(&profile_name)
-        .map_err(|e| format!("Failed to load runtime plan: {}", e))
+    let cache = settings.blob_cache();
+    let report = cache.audit(&settings.data_root.join("profiles"))
+        .map_err(|e| format!("Failed to audit cache: {}", e))?;
+    
+    info!(
+        "Cache audit complete: {} dangling refs, {} unreferenced files, {} missing blobs",
+        report.dangling_refs.count,
+        report.unreferenced_files.count,
+        report.missing_blobs.count
+    );
+    Ok(report)
 }
 
-/// Clean up temporary runtime directories
+/// Cache usage totals read from the index alone; cheap enough to refresh after every normalization
 #[tauri::command]
-pub async fn cleanup_temp_runtimes(
+pub async fn get_index_totals(
     state: State<'_, SettingsState>
-) -> Result<(), String> {
-    info!("Cleaning up temporary runtime directories");
-    
+) -> Result<IndexTotals, String> {
     let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
     let settings = settings_guard.as_ref()
         .ok_or("Settings not loaded")?.clone();
     drop(settings_guard);
     
-    let builder = RuntimeBuilder::new(settings);
-    builder.cleanup_temp_runtimes()
-        .map_err(|e| format!("Failed to cleanup temp runtimes: {}", e))
+    settings.blob_cache().index_totals()
+        .map_err(|e| format!("Failed to read index totals: {}", e))
+}
+
+/// List the blob-backed files a profile references, largest first
+#[tauri::command]
+pub async fn list_profile_contents(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<Vec<ProfileBlobEntry>, String> {
+    info!("Listing blob contents of profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    settings.blob_cache().list_profile_blobs(&profile_name)
+        .map_err(|e| format!("Failed to list profile contents: {}", e))
+}
+
+/// Check in one call whether each of a list of blob hashes is stored in the cache
+#[tauri::command]
+pub async fn check_blobs_exist(
+    hashes: Vec<String>,
+    state: State<'_, SettingsState>
+) -> Result<Vec<BlobPresence>, String> {
+    info!("Checking {} blobs exist in the cache", hashes.len());
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    settings.blob_cache().blobs_exist(&hashes)
+        .map_err(|e| format!("Failed to check blobs: {}", e))
+}
+
+/// Report overrides that several profiles carry independently, which could be managed once
+#[tauri::command]
+pub async fn find_duplicate_overrides(
+    state: State<'_, SettingsState>
+) -> Result<Vec<DuplicateGroup>, String> {
+    info!("Finding overrides duplicated across profiles");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    settings.blob_cache().find_cross_profile_duplicates()
+        .map_err(|e| format!("Failed to find duplicate overrides: {}", e))
+}
+
+/// Delete a profile's workspace files that are identical to their base file, reporting the space reclaimed
+#[tauri::command]
+pub async fn prune_redundant_overrides(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<PruneReport, String> {
+    info!("Pruning redundant overrides from profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || RuntimePlanner::new(settings).remove_identical_overrides(&profile_name))
+        .await
+        .map_err(|e| format!("Prune task failed: {}", e))?
+        .map_err(|e| format!("Failed to prune redundant overrides: {}", e))
+}
+
+/// Re-read every blob in the cache and report any whose content no longer matches its hash or integrity tag
+#[tauri::command]
+pub async fn verify_cache_integrity(
+    state: State<'_, SettingsState>
+) -> Result<IntegrityReport, String> {
+    info!("Verifying blob cache integrity");
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let report = tauri::async_runtime::spawn_blocking(move || settings.blob_cache().verify_integrity())
+        .await
+        .map_err(|e| format!("Integrity check failed: {}", e))?
+        .map_err(|e| format!("Failed to verify cache integrity: {}", e))?;
+    
+    info!(
+        "Cache integrity check complete: {} blobs checked, {} untagged, {} failed",
+        report.blobs_checked,
+        report.untagged,
+        report.tampered.count
+    );
+    Ok(report)
+}
+
+/// Copy a base file to workspace (make it editable)
+#[tauri::command]
+pub async fn copy_to_workspace(
+    profile_name: String,
+    virtual_path: String,
+    state: State<'_, SettingsState>
+) -> Result<(), String> {
+    info!("Copying to workspace: {} in profile: {}", virtual_path, profile_name);
+    
+    // Get settings to find paths
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?;
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    // Create virtual file system
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    vfs.set_keep_workspace_copies(settings.preferences.keeps_workspace_copies());
+    
+    // Copy to workspace through the blob cache
+    let cache = settings.blob_cache();
+    vfs.copy_to_workspace(&profile_name, &virtual_path, &cache)
+        .map_err(|e| format!("Failed to copy to workspace: {}", e))?;
+    
+    Ok(())
+}
+
+/// Copy a whole base directory into a profile's workspace (make it writable), deduplicating through the blob cache
+/// Progress is emitted as `workspace_copy_progress` events; `cancel_workspace_copy` stops it between files,
+/// keeping the files already copied
+#[tauri::command]
+pub async fn copy_dir_to_workspace(
+    profile_name: String,
+    virtual_path: String,
+    state: State<'_, SettingsState>,
+    copies: State<'_, WorkspaceCopies>,
+    app_handle: tauri::AppHandle
+) -> Result<DirCopyProgress, String> {
+    info!("Copying directory to workspace: {} in profile: {}", virtual_path, profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    let mut vfs = VirtualFileSystem::new(profile.effective_base_path(&settings.base_path), profile.workspace_dir);
+    vfs.set_shared_path(settings.get_shared_workspace_directory());
+    vfs.set_keep_workspace_copies(settings.preferences.keeps_workspace_copies());
+    vfs.set_data_root(settings.data_root.clone());
+    vfs.set_excluded_base_globs(&settings.preferences.excluded_base_globs)
+        .map_err(|e| format!("Failed to set excluded base globs: {:#}", e))?;
+    
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = copies.lock().map_err(|e| format!("Copy lock error: {}", e))?;
+        if active.contains_key(&profile_name) {
+            return Err(format!("A directory copy into profile '{}' is already in progress", profile_name));
+        }
+        active.insert(profile_name.clone(), cancel_flag.clone());
+    }
+    let _copy_guard = CancelFlagGuard { flags: copies.inner(), profile_name: profile_name.clone() };
+    
+    let cadence = ProgressCadence::from_preferences(&settings.preferences);
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let mut throttle: Option<ProgressThrottle> = None;
+        vfs.copy_dir_to_workspace(&profile_name, &virtual_path, &settings.blob_cache(), Some(&cancel_flag), |progress| {
+            let throttle = throttle.get_or_insert_with(|| ProgressThrottle::new(cadence, progress.total_files));
+            if throttle.should_emit(progress.files_copied + progress.files_skipped) {
+                if let Err(e) = app_handle.emit("workspace_copy_progress", progress) {
+                    warn!("Failed to emit workspace copy progress: {}", e);
+                }
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("Copy task failed: {}", e))?
+    .map_err(|e| format!("Failed to copy directory to workspace: {}", e))?;
+    
+    Ok(report)
+}
+
+/// Cancel a directory copy into a profile's workspace; returns false if none is in progress
+#[tauri::command]
+pub async fn cancel_workspace_copy(
+    profile_name: String,
+    copies: State<'_, WorkspaceCopies>
+) -> Result<bool, String> {
+    info!("Cancelling directory copy into profile: {}", profile_name);
+    
+    let copies = copies.lock().map_err(|e| format!("Copy lock error: {}", e))?;
+    match copies.get(&profile_name) {
+        Some(cancel_flag) => {
+            cancel_flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// =============================================================================
+// Workspace Watcher (Auto-running)
+// =============================================================================
+
+/// Create a profile's workspace watcher configured from the settings, without starting it
+fn configured_watcher(
+    settings: &Settings,
+    profile_name: &str,
+    app_handle: tauri::AppHandle,
+) -> Result<WorkspaceWatcher, String> {
+    let profiles_root = settings.data_root.join("profiles");
+    let manager = ProfileManager::new(profiles_root);
+    
+    let profile = manager.get_profile(profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    
+    let mut watcher = WorkspaceWatcher::for_profile(settings, &profile)
+        .map_err(|e| format!("Failed to create workspace watcher: {}", e))?;
+    
+    watcher.set_app_handle(app_handle);
+    Ok(watcher)
+}
+
+/// The settings to use for a profile, with its overrides applied
+fn profile_settings(settings: &Settings, profile_name: &str) -> Result<Settings, String> {
+    let manager = ProfileManager::new(settings.data_root.join("profiles"));
+    let profile = manager.get_profile(profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or(format!("Profile '{}' not found", profile_name))?;
+    Ok(settings.with_overrides(&profile.metadata.overrides))
+}
+
+/// Normalize every file in a profile's workspace now
+/// Works even when the workspace can't be watched; returns the number of files normalized
+#[tauri::command]
+pub async fn normalize_workspace(
+    profile_name: String,
+    state: State<'_, SettingsState>,
+    app_handle: tauri::AppHandle
+) -> Result<usize, String> {
+    info!("Normalizing workspace for profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let watcher = configured_watcher(&settings, &profile_name, app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || watcher.normalize_all())
+        .await
+        .map_err(|e| format!("Normalization task failed: {}", e))
+}
+
+/// Automatically ensure workspace watcher is running for a profile
+/// Called internally whenever a profile is accessed
+async fn ensure_workspace_watcher_running(
+    profile_name: &str,
+    state: &State<'_, SettingsState>,
+    watchers: &State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle,
+) -> Result<WatcherStatus, String> {
+    // Watchers normalize workspace files, which safe mode must not do on its own
+    if app_handle.state::<SafeMode>().is_enabled() {
+        debug!("Safe mode: not starting workspace watcher for profile: {}", profile_name);
+        return Ok(WatcherStatus::default());
+    }
+    
+    let mut watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+    
+    // Reuse the existing watcher for this profile unless it has stopped
+    if let Some(watcher) = watchers.get(profile_name) {
+        let status = watcher.status();
+        if status.running {
+            debug!("Workspace watcher already running for profile: {}", profile_name);
+            return Ok(status);
+        }
+        warn!("Workspace watcher for profile {} stopped ({:?}), restarting", profile_name, status.last_error);
+    }
+    
+    if let Some(mut stale) = watchers.remove(profile_name) {
+        stale.stop_watching();
+    }
+    
+    // Get settings and profile info
+    // Without settings there is no known cache directory, so refuse to start
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded; cannot start workspace watcher without a cache directory")?;
+    
+    // Create and start workspace watcher
+    let mut watcher = configured_watcher(settings, profile_name, app_handle)?;
+    watcher.start_watching()
+        .map_err(|e| format!("Failed to start workspace watcher: {}", e))?;
+    
+    info!("Auto-started workspace watcher for profile: {}", profile_name);
+    
+    // Store the watcher
+    let status = watcher.status();
+    watchers.insert(profile_name.to_string(), watcher);
+    
+    Ok(status)
+}
+
+/// Compute runtime plan for a profile
+#[tauri::command]
+pub async fn compute_runtime_plan(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<RuntimePlan, String> {
+    info!("Computing runtime plan for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    planner.compute_plan(&profile_name)
+        .map_err(|e| format!("Failed to compute runtime plan: {}", e))
+}
+
+/// Report which base files a profile's runtime includes, overrides and excludes
+#[tauri::command]
+pub async fn get_base_coverage(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<BaseCoverage, String> {
+    info!("Computing base coverage for profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let planner = RuntimePlanner::new(settings);
+    tauri::async_runtime::spawn_blocking(move || planner.base_coverage(&profile_name))
+        .await
+        .map_err(|e| format!("Base coverage task failed: {}", e))?
+        .map_err(|e| format!("Failed to compute base coverage: {}", e))
+}
+
+/// Explain where the runtime will take a single file from
+#[tauri::command]
+pub async fn explain_file(
+    profile_name: String,
+    rel_path: String,
+    state: State<'_, SettingsState>
+) -> Result<PlanExplanation, String> {
+    info!("Explaining runtime source for {}/{}", profile_name, rel_path);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    planner.explain(&profile_name, &rel_path)
+        .map_err(|e| format!("Failed to explain file: {}", e))
+}
+
+/// Check whether a profile's base installation changed since its runtime was last built (e.g. a game update)
+#[tauri::command]
+pub async fn check_base_changed(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<BaseChangeCheck, String> {
+    info!("Checking base installation for changes: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    let base_path = planner.base_path_for_profile(&profile_name)
+        .map_err(|e| format!("Failed to resolve base path: {}", e))?;
+    planner.check_base_fingerprint(&profile_name, &base_path)
+        .map_err(|e| format!("Failed to check base installation: {}", e))
+}
+
+/// List the base files changed since they were last recorded (e.g. by a game update) that profiles override,
+/// with the profiles overriding each, to triage overrides that may no longer fit
+#[tauri::command]
+pub async fn profiles_affected_by_base_changes(
+    state: State<'_, SettingsState>
+) -> Result<Vec<BaseFileImpact>, String> {
+    info!("Finding profiles affected by base installation changes");
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        RuntimePlanner::new(settings).profiles_affected_by_base_changes()
+            .map_err(|e| format!("Failed to find profiles affected by base changes: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Base change task failed: {}", e))?
+}
+
+/// Get a fingerprint of a profile's effective runtime content, which changes whenever the runtime would
+#[tauri::command]
+pub async fn get_profile_digest(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<String, String> {
+    info!("Computing content digest for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    RuntimePlanner::new(settings).profile_digest(&profile_name)
+        .map_err(|e| format!("Failed to compute profile digest: {}", e))
+}
+
+/// Build runtime for a profile with progress updates
+/// Pass options with `fail_fast: false` to collect per-file errors instead of aborting
+#[tauri::command]
+pub async fn build_runtime(
+    profile_name: String,
+    options: Option<BuildOptions>,
+    state: State<'_, SettingsState>,
+    launcher: State<'_, RuntimeLauncher>,
+    app_handle: tauri::AppHandle
+) -> Result<BuildResult, String> {
+    info!("Building runtime for profile: {}", profile_name);
+    
+    spawn_build(profile_name, options, &state, &launcher, emit_build_progress(app_handle), None)?
+        .await
+        .map_err(|e| format!("Build task failed: {}", e))?
+}
+
+/// Start building a runtime in the background and return its build id immediately
+/// Progress is emitted as `build_progress` events; the result is collected with `await_build`
+/// or from the `build_finished` event, and `cancel_build` stops it
+#[tauri::command]
+pub async fn start_build(
+    profile_name: String,
+    options: Option<BuildOptions>,
+    state: State<'_, SettingsState>,
+    registry: State<'_, BuildRegistry>,
+    launcher: State<'_, RuntimeLauncher>,
+    app_handle: tauri::AppHandle
+) -> Result<String, String> {
+    info!("Starting background build for profile: {}", profile_name);
+    
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let handle = spawn_build(profile_name, options, &state, &launcher, emit_build_progress(app_handle.clone()), Some(cancel_flag.clone()))?;
+    let build_id = Uuid::new_v4().to_string();
+    let (result_sender, result_receiver) = tokio::sync::watch::channel(None);
+    
+    registry.lock()
+        .map_err(|e| format!("Build registry lock error: {}", e))?
+        .insert(build_id.clone(), BackgroundBuild { cancel_flag, result: result_receiver });
+    
+    let registry = registry.inner().clone();
+    let finished_id = build_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match handle.await {
+            Ok(result) => result,
+            Err(e) => Err(format!("Build task failed: {}", e)),
+        };
+        let _ = result_sender.send(Some(result.clone()));
+        
+        let finished = BuildFinished { build_id: finished_id.clone(), result };
+        if let Err(e) = app_handle.emit("build_finished", &finished) {
+            warn!("Failed to emit build finished event: {}", e);
+        }
+        
+        // Drop the result eventually, so builds nobody awaits don't pile up in the registry
+        tokio::time::sleep(FINISHED_BUILD_RETENTION).await;
+        if let Ok(mut registry) = registry.lock() {
+            registry.remove(&finished_id);
+        }
+    });
+    
+    Ok(build_id)
+}
+
+/// Wait for a build started with `start_build` to finish and return its result
+/// Each build can be awaited once, while it's running or for `FINISHED_BUILD_RETENTION` after it ends
+#[tauri::command]
+pub async fn await_build(
+    build_id: String,
+    registry: State<'_, BuildRegistry>
+) -> Result<BuildResult, String> {
+    let mut result_receiver = {
+        let builds = registry.lock()
+            .map_err(|e| format!("Build registry lock error: {}", e))?;
+        builds.get(&build_id)
+            .map(|build| build.result.clone())
+            .ok_or_else(|| format!("No build with id '{}'", build_id))?
+    };
+    
+    let result = result_receiver.wait_for(Option::is_some).await
+        .map_err(|e| format!("Build task failed: {}", e))?
+        .clone();
+    
+    // Awaited, so the result doesn't need to be kept any longer
+    if let Ok(mut builds) = registry.lock() {
+        builds.remove(&build_id);
+    }
+    
+    result.ok_or("Build ended without a result")?
+}
+
+/// Cancel a build started with `start_build`; it stops between files and leaves the previous runtime in place
+/// Returns false if no build with the id is running
+#[tauri::command]
+pub async fn cancel_build(
+    build_id: String,
+    registry: State<'_, BuildRegistry>
+) -> Result<bool, String> {
+    info!("Cancelling build: {}", build_id);
+    
+    let builds = registry.lock()
+        .map_err(|e| format!("Build registry lock error: {}", e))?;
+    match builds.get(&build_id) {
+        Some(build) if build.result.borrow().is_none() => {
+            build.cancel_flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Run a build on a blocking thread so the async runtime stays free for other commands
+/// The profile's build lock is taken here, so a second build fails immediately, and released when the build ends
+fn spawn_build(
+    profile_name: String,
+    options: Option<BuildOptions>,
+    state: &State<'_, SettingsState>,
+    launcher: &State<'_, RuntimeLauncher>,
+    progress_callback: ProgressCallback,
+    cancel_flag: Option<Arc<AtomicBool>>
+) -> Result<tauri::async_runtime::JoinHandle<Result<BuildResult, String>>, String> {
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    let settings = profile_settings(&settings, &profile_name)?;
+    
+    // Rebuilding replaces the runtime directory the game is running from
+    let build_lock = BuildLockGuard::acquire(launcher.inner(), &profile_name)?;
+    
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        // Held until the build finishes or fails
+        let _build_lock = build_lock;
+        
+        let mut builder = RuntimeBuilder::new(settings)
+            .with_options(options.unwrap_or_default());
+        if let Some(cancel_flag) = cancel_flag {
+            builder = builder.with_cancel_flag(cancel_flag);
+        }
+        
+        builder.build_runtime(&profile_name, Some(progress_callback))
+            .map_err(|e| format!("Failed to build runtime: {}", e))
+    }))
+}
+
+/// Progress callback that emits `build_progress` events to the frontend
+fn emit_build_progress(app_handle: tauri::AppHandle) -> ProgressCallback {
+    Arc::new(move |progress: BuildProgress| {
+        if let Err(e) = app_handle.emit("build_progress", &progress) {
+            warn!("Failed to emit build progress: {}", e);
+        }
+    })
+}
+
+/// Step of a profile switch, reported in `profile_switch_progress` events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileSwitchStep {
+    Opening,
+    StartingWatcher,
+    Building,
+    Ready,
+}
+
+/// Progress of a profile switch; carries the build's progress while rebuilding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSwitchProgress {
+    pub profile_name: String,
+    pub step: ProfileSwitchStep,
+    pub build_progress: Option<BuildProgress>,
+}
+
+/// Result of switching to a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSwitchResult {
+    pub profile: ProfileInfo,
+    /// Whether the runtime had to be rebuilt (false when it was already up to date)
+    pub rebuilt: bool,
+    pub runtime_path: Option<String>,
+    /// Problems that didn't stop the switch, such as the watcher failing to start
+    pub warnings: Vec<String>,
+}
+
+/// Removes a profile's cancel flag (of a switch or workspace copy) when the operation ends
+struct CancelFlagGuard<'a> {
+    flags: &'a Mutex<HashMap<String, Arc<AtomicBool>>>,
+    profile_name: String,
+}
+
+impl Drop for CancelFlagGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.remove(&self.profile_name);
+        }
+    }
+}
+
+/// Switch to a profile in one step: open it, make sure its workspace is watched and
+/// rebuild its runtime only if it's stale, returning once the runtime is ready to launch
+/// Progress is emitted as `profile_switch_progress` events; `cancel_profile_switch` stops it
+#[tauri::command]
+pub async fn switch_to_profile(
+    name: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    launcher: State<'_, RuntimeLauncher>,
+    switches: State<'_, ProfileSwitches>,
+    app_handle: tauri::AppHandle
+) -> Result<ProfileSwitchResult, String> {
+    info!("Switching to profile: {}", name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = switches.lock().map_err(|e| format!("Switch lock error: {}", e))?;
+        if active.contains_key(&name) {
+            return Err(format!("A switch to profile '{}' is already in progress", name));
+        }
+        active.insert(name.clone(), cancel_flag.clone());
+    }
+    let _switch_guard = CancelFlagGuard { flags: switches.inner(), profile_name: name.clone() };
+    
+    let emit_step = {
+        let app_handle = app_handle.clone();
+        let name = name.clone();
+        move |step: ProfileSwitchStep, build_progress: Option<BuildProgress>| {
+            let progress = ProfileSwitchProgress { profile_name: name.clone(), step, build_progress };
+            if let Err(e) = app_handle.emit("profile_switch_progress", &progress) {
+                warn!("Failed to emit profile switch progress: {}", e);
+            }
+        }
+    };
+    let check_cancelled = || {
+        if cancel_flag.load(Ordering::SeqCst) {
+            Err(format!("Switch to profile '{}' was cancelled", name))
+        } else {
+            Ok(())
+        }
+    };
+    let mut warnings = Vec::new();
+    
+    emit_step(ProfileSwitchStep::Opening, None);
+    let manager = ProfileManager::new(settings.data_root.join("profiles"));
+    let profile = manager.touch_profile(&name)
+        .map_err(|e| format!("Failed to open profile: {}", e))?;
+    check_cancelled()?;
+    
+    // A stopped watcher only means edits aren't tracked, so the switch carries on
+    emit_step(ProfileSwitchStep::StartingWatcher, None);
+    if let Err(e) = ensure_workspace_watcher_running(&name, &state, &watchers, app_handle.clone()).await {
+        warn!("Workspace watcher failed to start for profile {}: {}", name, e);
+        warnings.push(format!("Workspace watcher failed to start: {}", e));
+    }
+    check_cancelled()?;
+    
+    // The running runtime can't be replaced, so it's used as it is
+    if launcher.is_running(&name) {
+        warnings.push("The game is running, so the runtime wasn't checked for changes".to_string());
+        emit_step(ProfileSwitchStep::Ready, None);
+        let runtime_path = settings.data_root.join("runtimes").join(format!("{}-latest", name));
+        return Ok(ProfileSwitchResult {
+            profile: ProfileInfo::new(profile, &settings.data_root),
+            rebuilt: false,
+            runtime_path: Some(runtime_path.to_string_lossy().to_string()),
+            warnings,
+        });
+    }
+    
+    emit_step(ProfileSwitchStep::Building, None);
+    let build_progress: ProgressCallback = {
+        let emit_step = emit_step.clone();
+        Arc::new(move |progress: BuildProgress| emit_step(ProfileSwitchStep::Building, Some(progress)))
+    };
+    let options = BuildOptions { skip_if_fresh: true, ..BuildOptions::default() };
+    let result = spawn_build(name.clone(), Some(options), &state, &launcher, build_progress, Some(cancel_flag.clone()))?
+        .await
+        .map_err(|e| format!("Build task failed: {}", e))??;
+    if !result.success {
+        return Err(format!("Failed to build runtime: {}", result.error.unwrap_or_default()));
+    }
+    warnings.extend(result.file_errors.iter()
+        .map(|file_error| format!("{}: {}", file_error.rel_path, file_error.error)));
+    
+    emit_step(ProfileSwitchStep::Ready, None);
+    Ok(ProfileSwitchResult {
+        profile: ProfileInfo::new(profile, &settings.data_root),
+        rebuilt: !result.up_to_date,
+        runtime_path: result.runtime_path.map(|path| path.to_string_lossy().to_string()),
+        warnings,
+    })
+}
+
+/// Cancel a profile switch in progress; a build it started stops and leaves the previous runtime in place
+/// Returns false if no switch to the profile is in progress
+#[tauri::command]
+pub async fn cancel_profile_switch(
+    name: String,
+    switches: State<'_, ProfileSwitches>
+) -> Result<bool, String> {
+    info!("Cancelling switch to profile: {}", name);
+    
+    let switches = switches.lock().map_err(|e| format!("Switch lock error: {}", e))?;
+    match switches.get(&name) {
+        Some(cancel_flag) => {
+            cancel_flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Relink a single edited file into a profile's existing runtime without rebuilding it
+/// The file must be normalized; takes the profile's build lock so it can't race a full build
+#[tauri::command]
+pub async fn apply_file_to_runtime(
+    profile_name: String,
+    rel_path: String,
+    state: State<'_, SettingsState>,
+    launcher: State<'_, RuntimeLauncher>
+) -> Result<FileApplyResult, String> {
+    info!("Applying {} to the runtime of profile: {}", rel_path, profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    let settings = profile_settings(&settings, &profile_name)?;
+    
+    // Relinking swaps a file the running game may have open
+    let build_lock = BuildLockGuard::acquire(launcher.inner(), &profile_name)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let _build_lock = build_lock;
+        RuntimeBuilder::new(settings).apply_file_to_runtime(&profile_name, &rel_path)
+            .map_err(|e| format!("Failed to apply file to runtime: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Apply task failed: {}", e))?
+}
+
+/// Check that a profile's built runtime contains an intact game executable before launching
+#[tauri::command]
+pub async fn verify_runtime_launchable(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<LaunchCheck, String> {
+    info!("Verifying runtime is launchable for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    RuntimeBuilder::new(settings).verify_launchable(&profile_name)
+        .map_err(|e| format!("Failed to verify runtime: {}", e))
+}
+
+/// Find runtime files that are no longer hardlinked to the cache blobs they were built from
+#[tauri::command]
+pub async fn verify_runtime_links(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<Vec<LinkIssue>, String> {
+    info!("Verifying runtime links for profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    let settings = profile_settings(&settings, &profile_name)?;
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        RuntimeBuilder::new(settings).verify_runtime_links(&profile_name)
+            .map_err(|e| format!("Failed to verify runtime links: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Verify task failed: {}", e))?
+}
+
+/// Relink runtime files that lost their hardlink to the cache; returns the number relinked
+/// Takes the profile's build lock so it can't race a build
+#[tauri::command]
+pub async fn repair_runtime_links(
+    profile_name: String,
+    state: State<'_, SettingsState>,
+    launcher: State<'_, RuntimeLauncher>
+) -> Result<usize, String> {
+    info!("Repairing runtime links for profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    let settings = profile_settings(&settings, &profile_name)?;
+    
+    let build_lock = BuildLockGuard::acquire(launcher.inner(), &profile_name)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let _build_lock = build_lock;
+        RuntimeBuilder::new(settings).repair_runtime_links(&profile_name)
+            .map_err(|e| format!("Failed to repair runtime links: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Repair task failed: {}", e))?
+}
+
+/// Launch a profile's built runtime and track the game process
+/// A `runtime-exited` event is emitted with the exit details once the game closes
+#[tauri::command]
+pub async fn launch_runtime(
+    profile_name: String,
+    options: Option<LaunchOptions>,
+    state: State<'_, SettingsState>,
+    launcher: State<'_, RuntimeLauncher>,
+    app_handle: tauri::AppHandle
+) -> Result<LaunchHandle, String> {
+    info!("Launching runtime for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let on_exit = Box::new(move |exit: GameExit| {
+        if let Err(e) = app_handle.emit("runtime-exited", &exit) {
+            warn!("Failed to emit runtime exit: {}", e);
+        }
+    });
+    
+    launcher.launch(&settings, &profile_name, options.unwrap_or_default(), Some(on_exit))
+        .map_err(|e| format!("Failed to launch runtime: {}", e))
+}
+
+/// Check whether a profile's game is currently running
+#[tauri::command]
+pub async fn is_runtime_running(
+    profile_name: String,
+    launcher: State<'_, RuntimeLauncher>
+) -> Result<bool, String> {
+    Ok(launcher.is_running(&profile_name))
+}
+
+/// Stop a profile's running game
+/// Returns false if the profile wasn't running
+#[tauri::command]
+pub async fn terminate_runtime(
+    profile_name: String,
+    launcher: State<'_, RuntimeLauncher>
+) -> Result<bool, String> {
+    info!("Terminating runtime for profile: {}", profile_name);
+    
+    launcher.terminate(&profile_name)
+        .map_err(|e| format!("Failed to terminate runtime: {}", e))
+}
+
+/// Estimate how long building a profile would take, without building it
+#[tauri::command]
+pub async fn estimate_build_time(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<BuildEstimate, String> {
+    info!("Estimating build time for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    RuntimeBuilder::new(settings).estimate_build_time(&profile_name)
+        .map_err(|e| format!("Failed to estimate build time: {}", e))
+}
+
+/// Recompute and persist the runtime plan for a profile without building
+/// Returns the path the plan was saved to
+#[tauri::command]
+pub async fn save_runtime_plan(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<String, String> {
+    info!("Saving runtime plan for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    let plan = planner.compute_plan(&profile_name)
+        .map_err(|e| format!("Failed to compute runtime plan: {}", e))?;
+    
+    let plan_path = planner.save_plan(&plan)
+        .map_err(|e| format!("Failed to save runtime plan: {}", e))?;
+    
+    Ok(plan_path.to_string_lossy().to_string())
+}
+
+/// Get or load existing runtime plan for a profile
+#[tauri::command]
+pub async fn get_runtime_plan(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<Option<RuntimePlan>, String> {
+    info!("Getting runtime plan for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    planner.load_plan(&profile_name)
+        .map_err(|e| format!("Failed to load runtime plan: {}", e))
+}
+
+/// Get what the last build changed: the current runtime plan diffed against the one it replaced
+/// Returns None until the profile has been built twice
+#[tauri::command]
+pub async fn diff_runtime_plan_against_previous(
+    profile_name: String,
+    state: State<'_, SettingsState>
+) -> Result<Option<RuntimePlanDiff>, String> {
+    info!("Diffing runtime plan against previous for profile: {}", profile_name);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let planner = RuntimePlanner::new(settings);
+    planner.diff_against_previous(&profile_name)
+        .map_err(|e| format!("Failed to diff runtime plans: {}", e))
+}
+
+/// Clean up temporary runtime directories
+#[tauri::command]
+pub async fn cleanup_temp_runtimes(
+    state: State<'_, SettingsState>
+) -> Result<(), String> {
+    info!("Cleaning up temporary runtime directories");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let builder = RuntimeBuilder::new(settings);
+    builder.cleanup_temp_runtimes()
+        .map_err(|e| format!("Failed to cleanup temp runtimes: {}", e))
+}
+
+/// Remove runtime directories whose profile has been deleted
+#[tauri::command]
+pub async fn clean_orphaned_runtimes(
+    state: State<'_, SettingsState>
+) -> Result<OrphanedRuntimeCleanup, String> {
+    info!("Cleaning up runtimes of deleted profiles");
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profiles_root = settings.data_root.join("profiles");
+    let builder = RuntimeBuilder::new(settings);
+    builder.clean_orphaned_runtimes(&profiles_root)
+        .map_err(|e| format!("Failed to clean orphaned runtimes: {}", e))
 }
 
 /// Stop workspace watcher for a profile (called when switching profiles)
-async fn stop_workspace_watcher_internal(profile_name: &str) -> Result<(), String> {
-    let mut watchers = ACTIVE_WATCHERS.lock()
+async fn stop_workspace_watcher_internal(
+    profile_name: &str,
+    watchers: &State<'_, WatcherRegistry>,
+) -> Result<(), String> {
+    let mut watchers = watchers.lock()
         .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
     
     if let Some(mut watcher) = watchers.remove(profile_name) {
@@ -965,4 +2657,270 @@ async fn stop_workspace_watcher_internal(profile_name: &str) -> Result<(), Strin
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+/// Get the status of the workspace watcher for a profile
+/// Profiles without an active watcher report a stopped status
+#[tauri::command]
+pub async fn get_watcher_status(
+    profile_name: String,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<WatcherStatus, String> {
+    let watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+    
+    Ok(watchers.get(&profile_name)
+        .map(|watcher| watcher.status())
+        .unwrap_or_default())
+}
+
+/// Start watching a profile's workspace, reusing the existing watcher if one is running
+#[tauri::command]
+pub async fn start_profile_watch(
+    profile_name: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle
+) -> Result<WatcherStatus, String> {
+    info!("Starting workspace watch for profile: {}", profile_name);
+    ensure_workspace_watcher_running(&profile_name, &state, &watchers, app_handle).await
+}
+
+/// Stop watching a profile's workspace (called when the profile is closed)
+#[tauri::command]
+pub async fn stop_profile_watch(
+    profile_name: String,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<(), String> {
+    info!("Stopping workspace watch for profile: {}", profile_name);
+    stop_workspace_watcher_internal(&profile_name, &watchers).await
+}
+
+/// Drop a profile's pending normalization backlog and stop its in-flight batch
+/// Returns false if the profile has no active watcher
+#[tauri::command]
+pub async fn clear_pending_normalization(
+    profile_name: String,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<bool, String> {
+    info!("Clearing pending normalization for profile: {}", profile_name);
+    
+    let watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+    
+    match watchers.get(&profile_name) {
+        Some(watcher) => {
+            watcher.clear_pending_normalization();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Get the files a profile's watcher most recently normalized or saw deleted, newest first
+/// Activity is kept in memory by the running watcher, so profiles without one report none
+#[tauri::command]
+pub async fn get_recent_activity(
+    profile_name: String,
+    limit: Option<usize>,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<Vec<ActivityEntry>, String> {
+    let watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+    
+    Ok(watchers.get(&profile_name)
+        .map(|watcher| watcher.recent_activity(limit.unwrap_or(ACTIVITY_CAPACITY)))
+        .unwrap_or_default())
+}
+
+/// List the workspace files the watcher stopped retrying after repeated normalization failures
+#[tauri::command]
+pub async fn get_quarantined_files(
+    profile_name: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle
+) -> Result<Vec<QuarantinedFile>, String> {
+    let watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+    if let Some(watcher) = watchers.get(&profile_name) {
+        return Ok(watcher.quarantined_files());
+    }
+    drop(watchers);
+    
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    Ok(configured_watcher(&settings, &profile_name, app_handle)?.quarantined_files())
+}
+
+/// Take a file out of quarantine once the user has resolved the problem, and retry normalizing it
+/// Returns false if the file wasn't quarantined
+#[tauri::command]
+pub async fn unquarantine_file(
+    profile_name: String,
+    rel_path: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    app_handle: tauri::AppHandle
+) -> Result<bool, String> {
+    info!("Unquarantining file: {} | Profile: {}", rel_path, profile_name);
+    
+    // Retry through a copy of the running watcher so the retry shows in its status and activity,
+    // without holding the registry lock while the file is normalized
+    let running = {
+        let watchers = watchers.lock()
+            .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?;
+        watchers.get(&profile_name).map(|watcher| watcher.detached())
+    };
+    
+    let watcher = match running {
+        Some(watcher) => watcher,
+        None => {
+            let settings = {
+                let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+                settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+            };
+            configured_watcher(&settings, &profile_name, app_handle)?
+        }
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || watcher.unquarantine_file(&rel_path))
+        .await
+        .map_err(|e| format!("Unquarantine task failed: {}", e))
+}
+
+/// Replace a profile's hardlinked workspace files with independent copies, e.g. before exporting the workspace
+/// The profile's watcher is stopped first so the copies aren't normalized back into hardlinks
+/// Returns the number of files rehydrated
+#[tauri::command]
+pub async fn rehydrate_workspace(
+    profile_name: String,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>
+) -> Result<usize, String> {
+    info!("Rehydrating workspace for profile: {}", profile_name);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.as_ref().ok_or("Settings not loaded")?.clone()
+    };
+    
+    let profile = ProfileManager::new(settings.data_root.join("profiles"))
+        .get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    
+    stop_workspace_watcher_internal(&profile_name, &watchers).await?;
+    
+    let cache = settings.blob_cache();
+    WorkspaceWatcher::rehydrate_workspace(&profile_name, &profile.workspace_dir, &cache)
+        .map_err(|e| format!("Failed to rehydrate workspace: {}", e))
+}
+
+/// Report whether a workspace file is hardlinked to its blob and has a matching index reference
+#[tauri::command]
+pub async fn get_normalization_status(
+    profile_name: String,
+    rel_path: String,
+    state: State<'_, SettingsState>
+) -> Result<NormalizationStatus, String> {
+    let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+    let settings = settings_guard.as_ref()
+        .ok_or("Settings not loaded")?.clone();
+    drop(settings_guard);
+    
+    let profile = ProfileManager::new(settings.data_root.join("profiles"))
+        .get_profile(&profile_name)
+        .map_err(|e| format!("Failed to get profile: {}", e))?
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    
+    let cache = settings.blob_cache();
+    WorkspaceWatcher::normalization_status(&profile_name, &profile.workspace_dir, &rel_path, &cache)
+        .map_err(|e| format!("Failed to get normalization status: {}", e))
+}
+
+/// Export a zip of diagnostics for a bug report: settings, app status, recent logs, cache stats,
+/// a cache audit and the profile's saved runtime plan. Never includes blob contents or saves
+/// With `redact_paths`, the base, data root, cache, temp and home directories are replaced with placeholders
+/// Works without loaded settings (safe mode), bundling what's left: the app status and logs
+#[tauri::command]
+pub async fn export_diagnostics(
+    dest: String,
+    profile_name: Option<String>,
+    redact_paths: bool,
+    state: State<'_, SettingsState>,
+    watchers: State<'_, WatcherRegistry>,
+    safe_mode: State<'_, SafeMode>
+) -> Result<DiagnosticsExport, String> {
+    info!("Exporting diagnostics to: {}", dest);
+    
+    let settings = {
+        let settings_guard = state.lock().map_err(|e| format!("State lock error: {}", e))?;
+        settings_guard.clone()
+    };
+    
+    let profiles = match &settings {
+        Some(settings) => ProfileManager::new(settings.data_root.join("profiles"))
+            .list_profiles()
+            .map(|profiles| profiles.into_iter().map(|profile| profile.metadata.name).collect())
+            .unwrap_or_else(|e| {
+                warn!("Failed to list profiles for diagnostics: {}", e);
+                Vec::new()
+            }),
+        None => Vec::new(),
+    };
+    let watchers = watchers.lock()
+        .map_err(|e| format!("Failed to acquire watcher lock: {}", e))?
+        .iter()
+        .map(|(name, watcher)| (name.clone(), watcher.status()))
+        .collect();
+    let input = DiagnosticsInput {
+        app_status: AppStatus {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            safe_mode: safe_mode.is_enabled(),
+            profiles,
+            watchers,
+        },
+        log_lines: logging::tail_logs(DIAGNOSTICS_LOG_LINES).unwrap_or_else(|e| vec![format!("Failed to read logs: {}", e)]),
+        profile_name,
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || diagnostics::export_diagnostics(settings.as_ref(), &input, Path::new(&dest), redact_paths))
+        .await
+        .map_err(|e| format!("Diagnostics export task failed: {}", e))?
+        .map_err(|e| format!("Failed to export diagnostics: {}", e))
+}
+
+/// Get the last `lines` lines of the current application log
+#[tauri::command]
+pub async fn tail_logs(lines: usize) -> Result<Vec<String>, String> {
+    logging::tail_logs(lines)
+        .map_err(|e| format!("Failed to read logs: {}", e))
+}
+
+/// Start emitting new application log lines as `log-lines` events
+/// Returns false if the logs were already being followed
+#[tauri::command]
+pub async fn follow_logs(
+    follower: State<'_, LogFollower>,
+    app_handle: tauri::AppHandle
+) -> Result<bool, String> {
+    info!("Following application logs");
+    follower.start(move |lines| {
+        if let Err(e) = app_handle.emit("log-lines", &lines) {
+            warn!("Failed to emit log lines: {}", e);
+        }
+    }).map_err(|e| format!("Failed to follow logs: {}", e))
+}
+
+/// Stop emitting application log lines
+#[tauri::command]
+pub async fn stop_following_logs(follower: State<'_, LogFollower>) -> Result<(), String> {
+    info!("Stopped following application logs");
+    follower.stop();
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::profiles::ProfileManager;
+use crate::runtime_planner::RuntimePlanner;
+use crate::settings::Settings;
+use crate::workspace_watcher::WatcherStatus;
+
+/// Log lines included in a diagnostics bundle
+pub const DIAGNOSTICS_LOG_LINES: usize = 2000;
+
+/// Snapshot of the running app, included in a diagnostics bundle
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppStatus {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub safe_mode: bool,
+    pub profiles: Vec<String>,
+    /// Status of each running watcher, by profile
+    pub watchers: BTreeMap<String, WatcherStatus>,
+}
+
+/// Everything gathered for a diagnostics bundle that doesn't come from the data root itself
+pub struct DiagnosticsInput {
+    pub app_status: AppStatus,
+    /// Most recent log lines, oldest first
+    pub log_lines: Vec<String>,
+    /// Profile whose saved runtime plan is included
+    pub profile_name: Option<String>,
+}
+
+/// What an exported diagnostics bundle contains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsExport {
+    pub path: PathBuf,
+    /// Names of the entries in the zip
+    pub entries: Vec<String>,
+    /// Parts that couldn't be gathered, left out of the bundle
+    pub skipped: Vec<String>,
+}
+
+/// Replaces known absolute paths in the bundle with placeholders
+struct PathRedactor {
+    /// (path, placeholder), longest path first so nested locations get the most specific placeholder
+    replacements: Vec<(String, String)>,
+}
+
+impl PathRedactor {
+    /// Without settings (safe mode before they load) only the home directory is known
+    fn new(settings: Option<&Settings>) -> Self {
+        let mut locations: Vec<(PathBuf, String)> = Vec::new();
+        if let Some(settings) = settings {
+            locations.push((settings.get_cache_directory(), "<cache>".to_string()));
+            locations.push((settings.get_temp_directory(), "<temp>".to_string()));
+            locations.push((settings.data_root.clone(), "<data_root>".to_string()));
+            locations.push((settings.base_path.clone(), "<base>".to_string()));
+            match ProfileManager::new(settings.data_root.join("profiles")).list_profiles() {
+                Ok(profiles) => locations.extend(profiles.into_iter().filter_map(|profile| {
+                    let base_path = profile.metadata.base_path_override?;
+                    Some((base_path, format!("<base:{}>", profile.metadata.name)))
+                })),
+                Err(e) => warn!("Failed to list profiles to redact their base paths: {}", e),
+            }
+        }
+        if let Some(home) = dirs::home_dir() {
+            locations.push((home, "<home>".to_string()));
+        }
+
+        let mut replacements: Vec<(String, String)> = locations.into_iter()
+            .filter(|(path, _)| !path.as_os_str().is_empty())
+            .flat_map(|(path, placeholder)| {
+                // Paths can show up with either separator
+                let native = path.to_string_lossy().to_string();
+                let forward = native.replace('\\', "/");
+                [(native, placeholder.clone()), (forward, placeholder)]
+            })
+            .collect();
+        replacements.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+        replacements.dedup();
+        Self { replacements }
+    }
+
+    fn redact_str(&self, text: &str) -> String {
+        self.replacements.iter().fold(text.to_string(), |text, (path, placeholder)| text.replace(path.as_str(), placeholder))
+    }
+
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact_str(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(fields) => fields.values_mut().for_each(|field| self.redact_value(field)),
+            _ => {}
+        }
+    }
+}
+
+/// Write a zip of diagnostics for a bug report to `dest`: settings, app status, recent logs,
+/// cache stats, a cache audit and the profile's saved runtime plan
+/// Only metadata is included, never blob contents or saves. With `redact_paths`, the base (global and per profile),
+/// data root, cache, temp and home directories are replaced with placeholders throughout.
+/// Without settings, e.g. in safe mode when they failed to load, the bundle still holds the app status and logs
+pub fn export_diagnostics(settings: Option<&Settings>, input: &DiagnosticsInput, dest: &Path, redact_paths: bool) -> Result<DiagnosticsExport> {
+    let redactor = redact_paths.then(|| PathRedactor::new(settings));
+    let mut json_entries: Vec<(&str, Result<Value>)> = vec![
+        ("app_status.json", serde_json::to_value(&input.app_status).map_err(Into::into)),
+    ];
+
+    match settings {
+        Some(settings) => {
+            json_entries.push(("settings.json", serde_json::to_value(settings).map_err(Into::into)));
+            let cache = settings.blob_cache();
+            json_entries.push(("cache_stats.json", cache.index_totals()
+                .context("Failed to read cache stats")
+                .and_then(|totals| Ok(serde_json::to_value(totals)?))));
+            json_entries.push(("cache_audit.json", cache.audit(&settings.data_root.join("profiles"))
+                .context("Failed to audit the cache")
+                .and_then(|audit| Ok(serde_json::to_value(audit)?))));
+            if let Some(profile_name) = &input.profile_name {
+                let plan = RuntimePlanner::new(settings.clone()).load_plan(profile_name)
+                    .and_then(|plan| Ok(serde_json::to_value(plan)?));
+                json_entries.push(("runtime_plan.json", plan));
+            }
+        }
+        None => {
+            for name in ["settings.json", "cache_stats.json", "cache_audit.json"] {
+                json_entries.push((name, Err(anyhow!("Settings not loaded"))));
+            }
+        }
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let file = fs::File::create(dest)
+        .with_context(|| format!("Failed to create diagnostics bundle: {}", dest.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut export = DiagnosticsExport { path: dest.to_path_buf(), entries: Vec::new(), skipped: Vec::new() };
+    for (name, value) in json_entries {
+        let mut value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Leaving {} out of the diagnostics bundle: {}", name, e);
+                export.skipped.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+        if let Some(redactor) = &redactor {
+            redactor.redact_value(&mut value);
+        }
+        zip.start_file(name, options)?;
+        zip.write_all(serde_json::to_string_pretty(&value)?.as_bytes())?;
+        export.entries.push(name.to_string());
+    }
+
+    let mut log = input.log_lines.join("\n");
+    if let Some(redactor) = &redactor {
+        log = redactor.redact_str(&log);
+    }
+    zip.start_file("recent.log", options)?;
+    zip.write_all(log.as_bytes())?;
+    export.entries.push("recent.log".to_string());
+
+    zip.finish().context("Failed to finish diagnostics bundle")?;
+    info!("Exported diagnostics bundle with {} entries: {}", export.entries.len(), dest.display());
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use crate::blob_cache::BlobCache;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_holds_metadata_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let mut settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        let temp_root = temp_dir.path().join("scratch");
+        settings.temp_root = Some(temp_root.clone());
+        let profiles = ProfileManager::new(data_root.join("profiles"));
+        let profile = profiles.create_profile("test".to_string()).unwrap();
+        let override_base = temp_dir.path().join("other install");
+        fs::create_dir_all(&override_base).unwrap();
+        profiles.create_profile("other".to_string()).unwrap();
+        profiles.set_base_path_override("other", Some(override_base.clone())).unwrap();
+
+        let secret = "private mod content";
+        fs::write(profile.workspace_dir.join("mod.asi"), secret).unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(profile.workspace_dir.join("mod.asi")).unwrap();
+        cache.add_ref(&blob, "test", "mod.asi").unwrap();
+        let planner = RuntimePlanner::new(settings.clone());
+        planner.save_plan(&planner.compute_plan("test").unwrap()).unwrap();
+
+        let input = DiagnosticsInput {
+            app_status: AppStatus { profiles: vec!["test".to_string()], ..AppStatus::default() },
+            log_lines: vec![
+                format!("Building runtime from {}", base_dir.display()),
+                format!("Staging in {}", temp_root.display()),
+                format!("Building runtime from {}", override_base.display()),
+            ],
+            profile_name: Some("test".to_string()),
+        };
+        let dest = temp_dir.path().join("out/diagnostics.zip");
+        let export = export_diagnostics(Some(&settings), &input, &dest, true).unwrap();
+        assert!(export.skipped.is_empty(), "{:?}", export.skipped);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(|name| name.to_string()).collect();
+        names.sort();
+        assert_eq!(names, [
+            "app_status.json", "cache_audit.json", "cache_stats.json", "recent.log", "runtime_plan.json", "settings.json",
+        ]);
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            assert!(!content.contains(secret), "{} holds blob contents", entry.name());
+            assert!(!content.contains(&*base_dir.to_string_lossy()), "{} holds the base path", entry.name());
+            assert!(!content.contains(&*data_root.to_string_lossy()), "{} holds the data root", entry.name());
+            assert!(!content.contains(&*temp_root.to_string_lossy()), "{} holds the temp root", entry.name());
+            assert!(!content.contains(&*override_base.to_string_lossy()), "{} holds a profile's base path", entry.name());
+            if entry.name() == "recent.log" {
+                assert_eq!(content, "Building runtime from <base>\nStaging in <temp>\nBuilding runtime from <base:other>");
+            }
+        }
+    }
+
+    #[test]
+    fn test_bundle_without_settings_keeps_status_and_logs() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = DiagnosticsInput {
+            app_status: AppStatus { safe_mode: true, ..AppStatus::default() },
+            log_lines: vec!["Failed to load settings".to_string()],
+            profile_name: None,
+        };
+        let dest = temp_dir.path().join("diagnostics.zip");
+        let export = export_diagnostics(None, &input, &dest, true).unwrap();
+        assert_eq!(export.entries, ["app_status.json", "recent.log"]);
+        assert_eq!(export.skipped.len(), 3);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+        let mut log = String::new();
+        archive.by_name("recent.log").unwrap().read_to_string(&mut log).unwrap();
+        assert_eq!(log, "Failed to load settings");
+    }
+}
@@ -2,6 +2,9 @@
 pub mod long_path;
 pub mod path_utils;
 pub mod logging;
+pub mod diagnostics;
+pub mod progress;
+pub mod safe_mode;
 pub mod settings;
 pub mod commands;
 pub mod profiles;
@@ -10,8 +13,12 @@ pub mod blob_cache;
 pub mod workspace_watcher;
 pub mod runtime_planner;
 pub mod runtime_builder;
+pub mod runtime_launcher;
 
-use commands::SettingsState;
+use commands::{SettingsState, WatcherRegistry, BuildRegistry, ProfileSwitches, CacheMaintenance, WorkspaceCopies};
+use logging::LogFollower;
+use runtime_launcher::RuntimeLauncher;
+use safe_mode::SafeMode;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -23,35 +30,159 @@ pub fn run() {
   // Log startup information
   logging::log_startup_info();
   
-  tauri::Builder::default()
+  // Safe mode (`--safe-mode` or DELTARUNTIME_SAFE_MODE=1) only registers diagnostic and repair commands
+  let safe_mode = SafeMode::from_launch();
+  if safe_mode.is_enabled() {
+    tracing::warn!("Starting in safe mode: no automatic filesystem changes, diagnostic and repair commands only");
+  }
+  
+  let builder = tauri::Builder::default()
+    .manage(safe_mode)
     .manage(SettingsState::new(None))
-    .invoke_handler(tauri::generate_handler![
+    .manage(WatcherRegistry::default())
+    .manage(BuildRegistry::default())
+    .manage(ProfileSwitches::default())
+    .manage(CacheMaintenance::default())
+    .manage(WorkspaceCopies::default())
+    .manage(RuntimeLauncher::default())
+    .manage(LogFollower::default());
+  
+  let builder = if safe_mode.is_enabled() {
+    builder.invoke_handler(tauri::generate_handler![
+            commands::is_safe_mode,
+            commands::load_settings,
+            commands::needs_wizard,
+            commands::validate_gta_base_path,
+            commands::get_drive_info,
+            commands::get_data_root_drive_info,
+            commands::validate_setup,
+            commands::create_data_structure,
+            commands::check_data_structure,
+            commands::ensure_data_structure,
+            commands::reset_wizard,
+            commands::relaunch_wizard,
+            commands::relocate_data_root,
+            commands::validate_settings,
+            commands::verify_base_integrity,
+            commands::get_settings,
+            commands::open_data_root,
+            commands::open_gta_base,
+            commands::pick_directory,
+            commands::list_profiles,
+            commands::get_cache_directory,
+            commands::audit_cache,
+            commands::verify_cache_integrity,
+            commands::get_index_totals,
+            commands::check_base_changed,
+            commands::profiles_affected_by_base_changes,
+            commands::export_diagnostics,
+            commands::tail_logs,
+            commands::follow_logs,
+            commands::stop_following_logs
+        ])
+  } else {
+    builder.invoke_handler(tauri::generate_handler![
+            commands::is_safe_mode,
             commands::load_settings,
             commands::needs_wizard,
             commands::validate_gta_base_path,
             commands::get_drive_info,
+            commands::get_data_root_drive_info,
+            commands::validate_setup,
             commands::create_data_structure,
+            commands::check_data_structure,
+            commands::ensure_data_structure,
+            commands::reset_wizard,
+            commands::relaunch_wizard,
+            commands::relocate_data_root,
             commands::validate_settings,
+            commands::verify_base_integrity,
             commands::get_settings,
             commands::open_data_root,
             commands::open_gta_base,
             commands::pick_directory,
             commands::create_profile,
+            commands::import_profile,
             commands::list_profiles,
             commands::rename_profile,
+            commands::open_profile,
             commands::delete_profile,
+            commands::set_profile_base_path,
+            commands::set_profile_saves_mode,
+            commands::set_profile_settings_overrides,
             commands::open_profile_workspace,
             commands::get_virtual_file_tree,
+            commands::resolve_virtual_path,
+            commands::edit_file,
             commands::revert_to_original,
             commands::copy_to_workspace,
+            commands::copy_dir_to_workspace,
+            commands::cancel_workspace_copy,
             commands::delete_workspace_file,
+            commands::revert_many,
+            commands::move_workspace_dir,
             commands::debug_blob_cache,
+            commands::get_cache_directory,
+            commands::audit_cache,
+            commands::verify_cache_integrity,
+            commands::get_index_totals,
+            commands::list_profile_contents,
+            commands::find_duplicate_overrides,
+            commands::prune_redundant_overrides,
+            commands::check_blobs_exist,
+            commands::garbage_collect_cache,
+            commands::gc_preview,
+            commands::maintain_cache,
+            commands::cancel_cache_maintenance,
+            commands::pin_blob,
+            commands::unpin_blob,
             commands::compute_runtime_plan,
+            commands::get_base_coverage,
+            commands::explain_file,
+            commands::get_profile_digest,
+            commands::check_base_changed,
+            commands::profiles_affected_by_base_changes,
             commands::build_runtime,
+            commands::apply_file_to_runtime,
+            commands::start_build,
+            commands::switch_to_profile,
+            commands::cancel_profile_switch,
+            commands::await_build,
+            commands::cancel_build,
+            commands::verify_runtime_launchable,
+            commands::verify_runtime_links,
+            commands::repair_runtime_links,
+            commands::estimate_build_time,
+            commands::launch_runtime,
+            commands::is_runtime_running,
+            commands::terminate_runtime,
+            commands::save_runtime_plan,
             commands::get_runtime_plan,
-            commands::cleanup_temp_runtimes
+            commands::diff_runtime_plan_against_previous,
+            commands::cleanup_temp_runtimes,
+            commands::clean_orphaned_runtimes,
+            commands::get_watcher_status,
+            commands::start_profile_watch,
+            commands::stop_profile_watch,
+            commands::clear_pending_normalization,
+            commands::get_recent_activity,
+            commands::get_quarantined_files,
+            commands::unquarantine_file,
+            commands::normalize_workspace,
+            commands::rehydrate_workspace,
+            commands::get_normalization_status,
+            commands::export_diagnostics,
+            commands::tail_logs,
+            commands::follow_logs,
+            commands::stop_following_logs
         ])
-    .setup(|_app| {
+  };
+  
+  builder
+    .setup(|app| {
+      // Restart watchers whose debounce thread dies during long sessions
+      commands::start_watcher_watchdog(app.handle().clone());
+      
       // Setup complete - our logging is already initialized
       tracing::info!("Tauri app setup complete");
       Ok(())
@@ -1,10 +1,20 @@
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use tracing_appender::rolling;
 use anyhow::{Result, Context};
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use dirs::config_dir;
 
+/// Prefix of the rolling log files (the daily appender adds a `.YYYY-MM-DD` suffix)
+const LOG_FILE_PREFIX: &str = "deltaruntime.log";
+
+/// How often the log follower checks for new lines
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Initialize the logging system with file rotation and console output
 ///
 /// This function sets up tracing with:
@@ -22,7 +32,7 @@ pub fn init_logging() -> Result<()> {
         .with_context(|| format!("Failed to create logs directory: {}", logs_dir.display()))?;
 
     // Create file appender with daily rotation
-    let file_appender = rolling::daily(&logs_dir, "deltaruntime.log");
+    let file_appender = rolling::daily(&logs_dir, LOG_FILE_PREFIX);
 
     // Create environment filter
     // Default to INFO level, but allow override via RUST_LOG environment variable
@@ -172,6 +182,172 @@ pub fn get_logs_info() -> Result<(PathBuf, u64, usize)> {
     Ok((logs_dir, total_size, file_count))
 }
 
+/// Find the newest rolling log file in a directory
+///
+/// Daily suffixes sort chronologically, so the greatest file name is the current day's log.
+fn newest_log_file_in(logs_dir: &Path) -> Result<Option<PathBuf>> {
+    if !logs_dir.exists() {
+        return Ok(None);
+    }
+
+    let mut newest: Option<PathBuf> = None;
+    for entry in std::fs::read_dir(logs_dir)? {
+        let path = entry?.path();
+        let is_log = path.is_file() && path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX));
+
+        if is_log && newest.as_ref().map_or(true, |current| path.file_name() > current.file_name()) {
+            newest = Some(path);
+        }
+    }
+
+    Ok(newest)
+}
+
+/// Read the last `lines` lines of the newest log file in a directory
+fn tail_log_file_in(logs_dir: &Path, lines: usize) -> Result<Vec<String>> {
+    let Some(log_file) = newest_log_file_in(logs_dir)? else {
+        return Ok(Vec::new());
+    };
+
+    let bytes = std::fs::read(&log_file)
+        .with_context(|| format!("Failed to read log file: {}", log_file.display()))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let all_lines: Vec<&str> = content.lines().collect();
+    let start = all_lines.len().saturating_sub(lines);
+
+    Ok(all_lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Read the last `lines` lines of the current day's log file
+///
+/// # Returns
+/// The most recent log lines, oldest first (empty if no log file exists yet)
+pub fn tail_logs(lines: usize) -> Result<Vec<String>> {
+    tail_log_file_in(&get_logs_dir()?, lines)
+}
+
+/// Read complete lines appended to a log file since `offset`, advancing it past them
+///
+/// A trailing partial line is left for the next read. If the file shrank, reading restarts from the beginning.
+fn read_new_lines(path: &Path, offset: &mut u64) -> Result<Vec<String>> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+
+    if file.metadata()?.len() < *offset {
+        *offset = 0;
+    }
+
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    let Some(last_newline) = bytes.iter().rposition(|&b| b == b'\n') else {
+        return Ok(Vec::new());
+    };
+    *offset += last_newline as u64 + 1;
+
+    Ok(String::from_utf8_lossy(&bytes[..last_newline])
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Background follower that reports new log lines as they're written
+///
+/// Switches to the next file when the daily log rotates.
+#[derive(Default)]
+pub struct LogFollower {
+    /// Run flag of the current follow session; each session gets its own,
+    /// so a thread from a stopped session can't keep going after a restart
+    session: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl LogFollower {
+    /// Start following the current log file, calling `on_lines` with each batch of new lines
+    ///
+    /// # Returns
+    /// `false` if the follower was already running
+    pub fn start<F>(&self, on_lines: F) -> Result<bool>
+    where
+        F: Fn(Vec<String>) + Send + 'static,
+    {
+        self.start_in(get_logs_dir()?, on_lines)
+    }
+
+    fn start_in<F>(&self, logs_dir: PathBuf, on_lines: F) -> Result<bool>
+    where
+        F: Fn(Vec<String>) + Send + 'static,
+    {
+        let mut session = self.session.lock()
+            .map_err(|e| anyhow::anyhow!("Log follower lock error: {}", e))?;
+        if session.as_ref().is_some_and(|running| running.load(Ordering::SeqCst)) {
+            return Ok(false);
+        }
+
+        // Only lines written after following starts are reported
+        let mut current = newest_log_file_in(&logs_dir)?;
+        let mut offset = match &current {
+            Some(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            None => 0,
+        };
+
+        let running = Arc::new(AtomicBool::new(true));
+        *session = Some(Arc::clone(&running));
+        std::thread::spawn(move || {
+            // Warn once per run of failures rather than on every poll
+            let mut failing = false;
+            while running.load(Ordering::SeqCst) {
+                // Pick up the new file after a daily rotation
+                if let Ok(Some(newest)) = newest_log_file_in(&logs_dir) {
+                    if current.as_ref() != Some(&newest) {
+                        current = Some(newest);
+                        offset = 0;
+                    }
+                }
+
+                if let Some(path) = &current {
+                    match read_new_lines(path, &mut offset) {
+                        Ok(lines) => {
+                            if failing {
+                                info!("Resumed following log file: {}", path.display());
+                                failing = false;
+                            }
+                            if !lines.is_empty() {
+                                on_lines(lines);
+                            }
+                        }
+                        Err(e) => {
+                            if !failing {
+                                warn!("Failed to follow log file: {:#}", e);
+                                failing = true;
+                            }
+                        }
+                    }
+                }
+
+                std::thread::sleep(FOLLOW_POLL_INTERVAL);
+            }
+        });
+
+        Ok(true)
+    }
+
+    /// Stop following (the background thread exits on its next poll)
+    pub fn stop(&self) {
+        if let Some(running) = self.session.lock().ok().and_then(|mut session| session.take()) {
+            running.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether the follower is currently running
+    pub fn is_running(&self) -> bool {
+        self.session.lock()
+            .is_ok_and(|session| session.as_ref().is_some_and(|running| running.load(Ordering::SeqCst)))
+    }
+}
+
 /// Log a startup message with system information
 pub fn log_startup_info() {
     info!("=== DeltaRuntime Starting ===");
@@ -233,6 +409,7 @@ macro_rules! log_debug {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_get_logs_dir() {
@@ -246,4 +423,80 @@ mod tests {
         let level = get_log_level();
         assert!(level == "DEBUG" || level == "INFO");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tail_reads_newest_log_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs_dir = temp_dir.path();
+        std::fs::write(logs_dir.join("deltaruntime.log.2024-01-01"), "old 1\nold 2\n").unwrap();
+        std::fs::write(logs_dir.join("deltaruntime.log.2024-01-02"), "line 1\nline 2\nline 3\n").unwrap();
+        std::fs::write(logs_dir.join("unrelated.txt"), "not a log\n").unwrap();
+
+        assert_eq!(tail_log_file_in(logs_dir, 2).unwrap(), vec!["line 2", "line 3"]);
+        assert_eq!(tail_log_file_in(logs_dir, 10).unwrap().len(), 3);
+        assert!(tail_log_file_in(&logs_dir.join("missing"), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_new_lines_waits_for_complete_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("deltaruntime.log.2024-01-01");
+        std::fs::write(&log_file, "first\npart").unwrap();
+
+        let mut offset = 0;
+        assert_eq!(read_new_lines(&log_file, &mut offset).unwrap(), vec!["first"]);
+
+        // The partial line is reported once it's completed
+        std::fs::write(&log_file, "first\npartial\nsecond\n").unwrap();
+        assert_eq!(read_new_lines(&log_file, &mut offset).unwrap(), vec!["partial", "second"]);
+        assert!(read_new_lines(&log_file, &mut offset).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_follower_reports_appended_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("deltaruntime.log.2024-01-01");
+        std::fs::write(&log_file, "before\n").unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let follower = LogFollower::default();
+        assert!(follower.start_in(temp_dir.path().to_path_buf(), move |lines| {
+            sink.lock().unwrap().extend(lines);
+        }).unwrap());
+        assert!(!follower.start_in(temp_dir.path().to_path_buf(), |_| {}).unwrap());
+
+        std::fs::write(&log_file, "before\nafter\n").unwrap();
+        std::thread::sleep(FOLLOW_POLL_INTERVAL * 3);
+        follower.stop();
+
+        assert_eq!(*received.lock().unwrap(), vec!["after".to_string()]);
+        assert!(!follower.is_running());
+
+        // Restarting before the old thread's next poll doesn't leave two threads reporting lines
+        let sink = Arc::clone(&received);
+        assert!(follower.start_in(temp_dir.path().to_path_buf(), move |lines| {
+            sink.lock().unwrap().extend(lines);
+        }).unwrap());
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log_file).unwrap();
+        std::io::Write::write_all(&mut file, b"again\n").unwrap();
+        std::thread::sleep(FOLLOW_POLL_INTERVAL * 3);
+        follower.stop();
+
+        assert_eq!(*received.lock().unwrap(), vec!["after".to_string(), "again".to_string()]);
+    }
+
+    #[test]
+    fn test_follower_can_start_after_failing_to() {
+        let temp_dir = TempDir::new().unwrap();
+        let follower = LogFollower::default();
+        // Not a directory, so finding the newest log file fails
+        let not_a_dir = temp_dir.path().join("deltaruntime.log.2024-01-01");
+        std::fs::write(&not_a_dir, "").unwrap();
+
+        assert!(follower.start_in(not_a_dir, |_| {}).is_err());
+        assert!(!follower.is_running());
+        assert!(follower.start_in(temp_dir.path().to_path_buf(), |_| {}).unwrap());
+        follower.stop();
+    }
+}
@@ -92,6 +92,77 @@ pub fn normalize_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
     Ok(result)
 }
 
+/// Computes the relative path from `base` to `path`
+///
+/// Both paths are normalized first. Paths on different volumes (or relative
+/// inputs) have no relative form.
+///
+/// # Arguments
+/// * `path` - The absolute target path
+/// * `base` - The absolute directory the result is relative to
+///
+/// # Returns
+/// The relative path (e.g. `..\Games\GTA San Andreas`), or None if the paths don't share a root
+pub fn relative_path_from<P: AsRef<Path>, Q: AsRef<Path>>(path: P, base: Q) -> Option<PathBuf> {
+    let path = normalize_path(path).ok()?;
+    let base = normalize_path(base).ok()?;
+    
+    if !path.is_absolute() || !base.is_absolute() {
+        return None;
+    }
+    
+    let path_components: Vec<_> = path.components().collect();
+    let base_components: Vec<_> = base.components().collect();
+    
+    // Paths on different volumes have no relative form
+    if path_components.first() != base_components.first() {
+        return None;
+    }
+    
+    let common = path_components.iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    
+    let mut relative = PathBuf::new();
+    for _ in common..base_components.len() {
+        relative.push("..");
+    }
+    for component in &path_components[common..] {
+        relative.push(component.as_os_str());
+    }
+    
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    
+    Some(relative)
+}
+
+/// Checks if `path` is `root` or lies inside it
+///
+/// Both paths are normalized first; the comparison ignores case on Windows.
+///
+/// # Arguments
+/// * `path` - The path to check
+/// * `root` - The directory it may be inside
+///
+/// # Returns
+/// `true` if `path` equals or is nested under `root`, `false` otherwise
+pub fn is_within<P: AsRef<Path>, Q: AsRef<Path>>(path: P, root: Q) -> bool {
+    let (path, root) = match (normalize_path(path), normalize_path(root)) {
+        (Ok(path), Ok(root)) => (path, root),
+        _ => return false,
+    };
+    
+    if cfg!(windows) {
+        let lowercase = |p: &Path| PathBuf::from(p.to_string_lossy().to_lowercase());
+        lowercase(&path).starts_with(lowercase(&root))
+    } else {
+        path.starts_with(&root)
+    }
+}
+
 /// Detects if a path is on an NTFS volume
 ///
 /// This function checks if the given path is located on an NTFS file system,
@@ -253,6 +324,187 @@ pub fn get_free_space<P: AsRef<Path>>(path: P) -> Result<u64> {
     bail!("Could not determine free space for path: {}", path.display())
 }
 
+/// Checks if a path is a symlink, junction, or other reparse point
+///
+/// Uses `symlink_metadata` so the link itself is inspected rather than its target.
+///
+/// # Arguments
+/// * `path` - The path to check
+///
+/// # Returns
+/// `true` if the path is a link or reparse point, `false` otherwise (including when it doesn't exist)
+pub fn is_link<P: AsRef<Path>>(path: P) -> bool {
+    let metadata = match std::fs::symlink_metadata(path.as_ref()) {
+        Ok(metadata) => metadata,
+        Err(_) => return false,
+    };
+    
+    if metadata.file_type().is_symlink() {
+        return true;
+    }
+    
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_REPARSE_POINT;
+        
+        metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT.0 != 0
+    }
+    
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Checks if two paths are hardlinks to the same file
+///
+/// Compares volume serial and file index on Windows, device and inode elsewhere.
+///
+/// # Arguments
+/// * `path1` - First path
+/// * `path2` - Second path
+///
+/// # Returns
+/// `true` if both paths refer to the same file, `false` otherwise (including when either doesn't exist)
+#[cfg(windows)]
+pub fn are_files_hardlinked(path1: &Path, path2: &Path) -> bool {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+        OPEN_EXISTING, FILE_READ_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE,
+    };
+    use windows::core::PCWSTR;
+    use std::os::windows::ffi::OsStrExt;
+    
+    unsafe {
+        // Convert paths to wide strings
+        let path1_wide: Vec<u16> = path1.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let path2_wide: Vec<u16> = path2.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        
+        // Open both files
+        let handle1 = CreateFileW(
+            PCWSTR(path1_wide.as_ptr()),
+            FILE_READ_ATTRIBUTES.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            Default::default(),
+            None,
+        );
+        
+        let handle2 = CreateFileW(
+            PCWSTR(path2_wide.as_ptr()),
+            FILE_READ_ATTRIBUTES.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            Default::default(),
+            None,
+        );
+        
+        let (handle1, handle2) = match (handle1, handle2) {
+            (Ok(h1), Ok(h2)) => (h1, h2),
+            _ => return false,
+        };
+        
+        if handle1.is_invalid() || handle2.is_invalid() {
+            return false;
+        }
+        
+        // Get file information
+        let mut info1 = BY_HANDLE_FILE_INFORMATION::default();
+        let mut info2 = BY_HANDLE_FILE_INFORMATION::default();
+        
+        let success1 = GetFileInformationByHandle(handle1, &mut info1).is_ok();
+        let success2 = GetFileInformationByHandle(handle2, &mut info2).is_ok();
+        
+        if success1 && success2 {
+            // Compare volume serial and file index
+            info1.dwVolumeSerialNumber == info2.dwVolumeSerialNumber &&
+            info1.nFileIndexLow == info2.nFileIndexLow &&
+            info1.nFileIndexHigh == info2.nFileIndexHigh
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn are_files_hardlinked(path1: &Path, path2: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    
+    match (std::fs::metadata(path1), std::fs::metadata(path2)) {
+        (Ok(metadata1), Ok(metadata2)) => metadata1.dev() == metadata2.dev() && metadata1.ino() == metadata2.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn are_files_hardlinked(_path1: &Path, _path2: &Path) -> bool {
+    false
+}
+
+/// Compiles glob patterns for `matches_any_glob`
+///
+/// Compile a set once and reuse it for every path it's matched against.
+/// `\` in a pattern is treated as a path separator, like `/`.
+///
+/// # Arguments
+/// * `globs` - Glob patterns such as `*.log` or `data/settings/*.cfg`
+///
+/// # Returns
+/// The compiled patterns, or an error naming the first invalid one
+pub fn compile_globs(globs: &[String]) -> Result<Vec<glob::Pattern>> {
+    globs.iter()
+        .map(|pattern| glob::Pattern::new(&pattern.replace('\\', "/"))
+            .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", pattern, e)))
+        .collect()
+}
+
+/// Checks if a workspace-relative path matches any of the given glob patterns
+///
+/// Matching is case-insensitive and treats `\` and `/` as the same separator.
+///
+/// # Arguments
+/// * `rel_path` - Path relative to the workspace root
+/// * `patterns` - Patterns compiled with `compile_globs`
+///
+/// # Returns
+/// `true` if any pattern matches the path, `false` otherwise
+pub fn matches_any_glob(rel_path: &str, patterns: &[glob::Pattern]) -> bool {
+    let rel_path = rel_path.replace('\\', "/");
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+    
+    patterns.iter().any(|pattern| pattern.matches_with(&rel_path, options))
+}
+
+/// Converts a workspace-relative path into the string used as its blob index key
+///
+/// Every place that writes or looks up a reference goes through this, so the
+/// same file always produces the same key. Components are joined with `/` on
+/// every platform, matching the virtual paths the planner builds. Names are kept exactly as the file
+/// system reports them (no Unicode normalization), since NTFS stores them as
+/// given and both sides of a lookup come from the file system.
+///
+/// # Arguments
+/// * `rel_path` - Path relative to the workspace root
+///
+/// # Returns
+/// The key, or `None` if the path isn't valid Unicode; a lossy conversion
+/// would mangle such names and could map different files to the same key
+pub fn rel_path_key<P: AsRef<Path>>(rel_path: P) -> Option<String> {
+    let components = rel_path.as_ref()
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(components.join("/"))
+}
+
 /// Converts a file size in bytes to a human-readable string
 ///
 /// # Arguments
@@ -288,6 +540,37 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_matches_any_glob() {
+        let globs = compile_globs(&["*.log".to_string(), "data/settings/*.cfg".to_string()]).unwrap();
+        
+        assert!(matches_any_glob("modloader.log", &globs));
+        assert!(matches_any_glob("logs/Game.LOG", &globs));
+        assert!(matches_any_glob("data\\settings\\gta.cfg", &globs));
+        assert!(!matches_any_glob("data/handling.cfg", &globs));
+        assert!(!matches_any_glob("modloader.log", &[]));
+        assert!(compile_globs(&["data/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_rel_path_key() {
+        assert_eq!(rel_path_key(Path::new("données").join("café.txt")), Some("données/café.txt".to_string()));
+        assert_eq!(rel_path_key("模组/车辆.asi"), Some("模组/车辆.asi".to_string()));
+
+        // Composed and decomposed spellings are different files, so they stay different keys
+        let composed = rel_path_key("caf\u{e9}.txt").unwrap();
+        let decomposed = rel_path_key("cafe\u{301}.txt").unwrap();
+        assert_ne!(composed, decomposed);
+        assert_eq!(decomposed, "cafe\u{301}.txt");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let invalid = std::ffi::OsStr::from_bytes(b"mod\xff.asi");
+            assert_eq!(rel_path_key(invalid), None);
+        }
+    }
+
     #[test]
     fn test_normalize_path() {
         let path = PathBuf::from(r"C:\foo\.\bar\..\baz");
@@ -4,6 +4,24 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use anyhow::{Context, Result};
 use tracing::{info, warn, debug};
+use walkdir::WalkDir;
+
+use crate::blob_cache::{BlobCache, BlobPath};
+use crate::path_utils::rel_path_key;
+use crate::settings::ProfileSettingsOverrides;
+
+/// Characters that can't appear in profile or saves pool names
+const INVALID_NAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Where a profile keeps its save games
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum SavesMode {
+    /// Saves live in the profile's own saves directory
+    #[default]
+    Isolated,
+    /// Saves live in a named pool under `data_root/saves-pools/<name>`, shared with other profiles
+    Shared(String),
+}
 
 /// Profile metadata stored in the profile directory
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +36,15 @@ pub struct ProfileMetadata {
     pub description: Option<String>,
     /// Schema version for future migrations
     pub schema_version: u32,
+    /// Optional base installation used instead of the global base path
+    #[serde(default)]
+    pub base_path_override: Option<PathBuf>,
+    /// Whether saves are isolated to this profile or shared through a pool
+    #[serde(default)]
+    pub saves_mode: SavesMode,
+    /// Preferences this profile overrides
+    #[serde(default)]
+    pub overrides: ProfileSettingsOverrides,
 }
 
 impl ProfileMetadata {
@@ -30,6 +57,9 @@ impl ProfileMetadata {
             last_used: now,
             description: None,
             schema_version: 1,
+            base_path_override: None,
+            saves_mode: SavesMode::Isolated,
+            overrides: ProfileSettingsOverrides::default(),
         }
     }
 
@@ -62,7 +92,7 @@ impl Profile {
             return Err(anyhow::anyhow!("Profile name cannot be empty"));
         }
         
-        if name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        if name.contains(INVALID_NAME_CHARS) {
             return Err(anyhow::anyhow!("Profile name contains invalid characters"));
         }
 
@@ -161,7 +191,7 @@ impl Profile {
             return Err(anyhow::anyhow!("Profile name cannot be empty"));
         }
         
-        if new_name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        if new_name.contains(INVALID_NAME_CHARS) {
             return Err(anyhow::anyhow!("Profile name contains invalid characters"));
         }
 
@@ -210,6 +240,71 @@ impl Profile {
         self.metadata.touch();
         self.save_metadata()
     }
+
+    /// Get the base installation for this profile, honoring any per-profile override
+    pub fn effective_base_path(&self, default_base: &Path) -> PathBuf {
+        self.metadata.base_path_override
+            .clone()
+            .unwrap_or_else(|| default_base.to_path_buf())
+    }
+
+    /// Get the directory this profile's saves live in, creating a shared pool if it doesn't exist yet
+    pub fn effective_saves_dir(&self, data_root: &Path) -> Result<PathBuf> {
+        let pool_name = match &self.metadata.saves_mode {
+            SavesMode::Isolated => return Ok(self.saves_dir.clone()),
+            SavesMode::Shared(pool_name) => pool_name,
+        };
+
+        validate_pool_name(pool_name)?;
+        let pool_dir = data_root.join("saves-pools").join(pool_name);
+        fs::create_dir_all(&pool_dir)
+            .with_context(|| format!("Failed to create saves pool: {}", pool_dir.display()))?;
+        Ok(pool_dir)
+    }
+}
+
+/// Check that a saves pool name is usable as a directory name
+fn validate_pool_name(pool_name: &str) -> Result<()> {
+    if pool_name.trim().is_empty() {
+        return Err(anyhow::anyhow!("Saves pool name cannot be empty"));
+    }
+
+    if pool_name.contains(INVALID_NAME_CHARS) || pool_name == "." || pool_name == ".." {
+        return Err(anyhow::anyhow!("Saves pool name contains invalid characters"));
+    }
+
+    Ok(())
+}
+
+/// Check a profile name against a list filter (case-insensitive substring or wildcard pattern)
+fn profile_name_matches(name: &str, filter: Option<&str>) -> bool {
+    let filter = match filter.map(str::trim) {
+        Some(filter) if !filter.is_empty() => filter,
+        _ => return true,
+    };
+
+    if filter.contains(['*', '?', '[']) {
+        let options = glob::MatchOptions {
+            case_sensitive: false,
+            ..glob::MatchOptions::new()
+        };
+        if let Ok(pattern) = glob::Pattern::new(filter) {
+            return pattern.matches_with(name, options);
+        }
+    }
+
+    name.to_lowercase().contains(&filter.to_lowercase())
+}
+
+/// Counts from importing an existing modded install into a profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Files that differ from their base counterpart
+    pub overrides: usize,
+    /// Files that don't exist in base
+    pub new_files: usize,
+    /// Files identical to base, left to come from base
+    pub unchanged: usize,
 }
 
 /// Profile manager for CRUD operations
@@ -230,7 +325,15 @@ impl ProfileManager {
 
     /// List all profiles
     pub fn list_profiles(&self) -> Result<Vec<Profile>> {
-        info!("Listing profiles from: {}", self.profiles_root.display());
+        self.list_profiles_filtered(None)
+    }
+
+    /// List profiles whose name matches `filter`, most recently used first
+    ///
+    /// The filter is a case-insensitive substring, or a wildcard pattern when it contains `*`, `?` or `[`.
+    /// Directories are matched by name before their metadata is loaded, so non-matching profiles cost nothing.
+    pub fn list_profiles_filtered(&self, filter: Option<&str>) -> Result<Vec<Profile>> {
+        info!("Listing profiles from: {} (filter: {:?})", self.profiles_root.display(), filter);
 
         if !self.profiles_root.exists() {
             debug!("Profiles directory doesn't exist yet, returning empty list");
@@ -246,7 +349,11 @@ impl ProfileManager {
             let entry = entry.context("Failed to read directory entry")?;
             let path = entry.path();
             
-            if path.is_dir() {
+            let name_matches = path.file_name()
+                .map(|name| profile_name_matches(&name.to_string_lossy(), filter))
+                .unwrap_or(false);
+            
+            if path.is_dir() && name_matches {
                 match Profile::load(&path) {
                     Ok(profile) => {
                         debug!("Loaded profile: {}", profile.metadata.name);
@@ -290,6 +397,133 @@ impl ProfileManager {
         Ok(profile)
     }
 
+    /// Mark a profile as just used so it sorts first in the profile list
+    pub fn touch_profile(&self, name: &str) -> Result<Profile> {
+        let mut profile = self.get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+        profile.touch()?;
+        Ok(profile)
+    }
+
+    /// Set or clear the base path override for a profile
+    pub fn set_base_path_override(&self, name: &str, base_path: Option<PathBuf>) -> Result<Profile> {
+        let mut profile = self.get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+        if let Some(path) = &base_path {
+            if !path.is_dir() {
+                return Err(anyhow::anyhow!("Base path override is not a directory: {}", path.display()));
+            }
+        }
+
+        profile.metadata.base_path_override = base_path;
+        profile.save_metadata()?;
+        Ok(profile)
+    }
+
+    /// Set whether a profile's saves are isolated or shared through a pool
+    pub fn set_saves_mode(&self, name: &str, saves_mode: SavesMode) -> Result<Profile> {
+        let mut profile = self.get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+        if let SavesMode::Shared(pool_name) = &saves_mode {
+            validate_pool_name(pool_name)?;
+        }
+
+        profile.metadata.saves_mode = saves_mode;
+        profile.save_metadata()?;
+        Ok(profile)
+    }
+
+    /// Replace the preferences a profile overrides
+    pub fn set_settings_overrides(&self, name: &str, overrides: ProfileSettingsOverrides) -> Result<Profile> {
+        let mut profile = self.get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+        profile.metadata.overrides = overrides;
+        profile.save_metadata()?;
+        Ok(profile)
+    }
+
+    /// Create a profile capturing an existing modded install
+    ///
+    /// Every file that differs from `base_dir` (by content hash) or is missing from it is stored in the
+    /// blob cache and hardlinked into the new workspace, the same way the watcher normalizes files.
+    /// Files identical to base aren't duplicated. With `keep_copies` workspace files are real copies instead of hardlinks.
+    /// A failed import leaves the partially imported profile in place.
+    pub fn import_from_directory(
+        &self,
+        name: String,
+        modded_dir: &Path,
+        base_dir: &Path,
+        cache: &BlobCache,
+        keep_copies: bool,
+    ) -> Result<(Profile, ImportSummary)> {
+        if !modded_dir.is_dir() {
+            return Err(anyhow::anyhow!("Modded install is not a directory: {}", modded_dir.display()));
+        }
+
+        let profile = self.create_profile(name)?;
+        info!("Importing {} into profile '{}'", modded_dir.display(), profile.metadata.name);
+
+        let mut summary = ImportSummary::default();
+        for entry in WalkDir::new(modded_dir) {
+            let entry = entry.context("Failed to read modded install")?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let rel_path = entry.path().strip_prefix(modded_dir)
+                .context("Modded file outside the install directory")?;
+            let rel_key = rel_path_key(rel_path)
+                .ok_or_else(|| anyhow::anyhow!("File name is not valid Unicode: {}", rel_path.display()))?;
+            let base_file = base_dir.join(rel_path);
+            let modded_hash = BlobCache::hash_file(entry.path())
+                .with_context(|| format!("Failed to hash file: {}", entry.path().display()))?;
+
+            let has_base = base_file.is_file();
+            if has_base {
+                let base_hash = BlobCache::hash_file(&base_file)
+                    .with_context(|| format!("Failed to hash base file: {}", base_file.display()))?;
+                if base_hash == modded_hash {
+                    summary.unchanged += 1;
+                    continue;
+                }
+            }
+
+            // Store the blob once and hardlink it into the workspace, as normalization would
+            let cached_path = cache.get_blob_path(&modded_hash);
+            let blob = if cached_path.exists() {
+                BlobPath { hash: modded_hash, path: cached_path }
+            } else {
+                cache.ensure_blob_streaming(entry.path())
+                    .with_context(|| format!("Failed to store blob for: {}", rel_path.display()))?
+            };
+            let workspace_file = profile.workspace_dir.join(rel_path);
+            if keep_copies {
+                cache.copy_blob_to(&workspace_file, &blob)
+            } else {
+                cache.link_blob_to(&workspace_file, &blob)
+            }
+            .with_context(|| format!("Failed to place imported file: {}", rel_path.display()))?;
+            cache.add_ref_for_file(&blob, &profile.metadata.name, &rel_key, &workspace_file)
+                .with_context(|| format!("Failed to record blob reference for: {}", rel_path.display()))?;
+
+            if has_base {
+                summary.overrides += 1;
+            } else {
+                summary.new_files += 1;
+            }
+        }
+
+        info!(
+            "Imported profile '{}': {} overrides, {} new files, {} unchanged",
+            profile.metadata.name, summary.overrides, summary.new_files, summary.unchanged
+        );
+        Ok((profile, summary))
+    }
+
     /// Delete a profile
     pub fn delete_profile(&self, name: &str) -> Result<()> {
         let profile = self.get_profile(name)?
@@ -342,6 +576,178 @@ mod tests {
         assert_eq!(profiles[0].metadata.name, "renamed-profile");
     }
 
+    #[test]
+    fn test_list_profiles_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ProfileManager::new(temp_dir.path().join("profiles"));
+        for name in ["Vanilla", "SA-MP Server", "Graphics Overhaul", "graphics-lite"] {
+            manager.create_profile(name.to_string()).unwrap();
+        }
+
+        let names = |filter: Option<&str>| {
+            let mut names: Vec<String> = manager.list_profiles_filtered(filter).unwrap()
+                .into_iter()
+                .map(|profile| profile.metadata.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(None).len(), 4);
+        assert_eq!(names(Some("  ")).len(), 4);
+        assert_eq!(names(Some("GRAPHICS")), vec!["Graphics Overhaul", "graphics-lite"]);
+        assert_eq!(names(Some("*server")), vec!["SA-MP Server"]);
+        assert_eq!(names(Some("g?aphics-*")), vec!["graphics-lite"]);
+        assert!(names(Some("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_touch_profile_updates_last_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ProfileManager::new(temp_dir.path().join("profiles"));
+
+        let older = manager.create_profile("older".to_string()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        manager.create_profile("newer".to_string()).unwrap();
+        assert_eq!(manager.list_profiles().unwrap()[0].metadata.name, "newer");
+
+        // Opening the older profile persists a later timestamp and moves it to the front
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let touched = manager.touch_profile("older").unwrap();
+        assert!(touched.metadata.last_used > older.metadata.last_used);
+        let reloaded = manager.get_profile("older").unwrap().unwrap();
+        assert_eq!(reloaded.metadata.last_used, touched.metadata.last_used);
+        assert_eq!(manager.list_profiles().unwrap()[0].metadata.name, "older");
+
+        assert!(manager.touch_profile("missing").is_err());
+    }
+
+    #[test]
+    fn test_base_path_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let profiles_root = temp_dir.path().join("profiles");
+        let global_base = temp_dir.path().join("base");
+        let steam_base = temp_dir.path().join("steam_base");
+        fs::create_dir_all(&steam_base).unwrap();
+        let manager = ProfileManager::new(profiles_root);
+
+        let profile = manager.create_profile("steam".to_string()).unwrap();
+        assert_eq!(profile.effective_base_path(&global_base), global_base);
+
+        // Override is persisted and picked up on reload
+        manager.set_base_path_override("steam", Some(steam_base.clone())).unwrap();
+        let profile = manager.get_profile("steam").unwrap().unwrap();
+        assert_eq!(profile.effective_base_path(&global_base), steam_base);
+
+        // Missing directories are rejected
+        let missing = temp_dir.path().join("missing");
+        assert!(manager.set_base_path_override("steam", Some(missing)).is_err());
+
+        // Clearing the override falls back to the global base
+        manager.set_base_path_override("steam", None).unwrap();
+        let profile = manager.get_profile("steam").unwrap().unwrap();
+        assert_eq!(profile.effective_base_path(&global_base), global_base);
+    }
+
+    #[test]
+    fn test_shared_saves_pool() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        let manager = ProfileManager::new(data_root.join("profiles"));
+
+        let vanilla = manager.create_profile("vanilla".to_string()).unwrap();
+        manager.create_profile("modded".to_string()).unwrap();
+        assert_eq!(vanilla.metadata.saves_mode, SavesMode::Isolated);
+        assert_eq!(vanilla.effective_saves_dir(&data_root).unwrap(), vanilla.saves_dir);
+
+        // Profiles sharing a pool resolve to the same directory, created on demand
+        let shared = SavesMode::Shared("vanilla progress".to_string());
+        manager.set_saves_mode("vanilla", shared.clone()).unwrap();
+        manager.set_saves_mode("modded", shared).unwrap();
+        let pool_dir = data_root.join("saves-pools").join("vanilla progress");
+        for name in ["vanilla", "modded"] {
+            let profile = manager.get_profile(name).unwrap().unwrap();
+            assert_eq!(profile.effective_saves_dir(&data_root).unwrap(), pool_dir);
+        }
+        assert!(pool_dir.is_dir());
+
+        // Pool names must be plain directory names
+        assert!(manager.set_saves_mode("modded", SavesMode::Shared("".to_string())).is_err());
+        assert!(manager.set_saves_mode("modded", SavesMode::Shared("..".to_string())).is_err());
+        assert!(manager.set_saves_mode("modded", SavesMode::Shared("a/b".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_import_from_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let modded_dir = temp_dir.path().join("modded");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let manager = ProfileManager::new(temp_dir.path().join("profiles"));
+
+        for dir in [&base_dir, &modded_dir] {
+            fs::create_dir_all(dir.join("data")).unwrap();
+            fs::write(dir.join("gta_sa.exe"), "exe").unwrap();
+            fs::write(dir.join("data/handling.cfg"), "stock").unwrap();
+        }
+        fs::write(modded_dir.join("data/handling.cfg"), "tuned").unwrap();
+        fs::create_dir_all(modded_dir.join("modloader")).unwrap();
+        fs::write(modded_dir.join("modloader/mod.asi"), "mod").unwrap();
+
+        let (profile, summary) = manager.import_from_directory("imported".to_string(), &modded_dir, &base_dir, &cache, false).unwrap();
+        assert_eq!(summary.overrides, 1);
+        assert_eq!(summary.new_files, 1);
+        assert_eq!(summary.unchanged, 1);
+
+        // Only differing files land in the workspace, each backed by an indexed blob
+        assert!(!profile.workspace_dir.join("gta_sa.exe").exists());
+        assert_eq!(fs::read_to_string(profile.workspace_dir.join("data/handling.cfg")).unwrap(), "tuned");
+        let mod_path = Path::new("modloader").join("mod.asi");
+        let hash = cache.find_blob_hash_for_file("imported", &mod_path.to_string_lossy()).unwrap();
+        assert_eq!(hash, Some(blake3::hash(b"mod").to_hex().to_string()));
+
+        // Importing over an existing profile is refused
+        assert!(manager.import_from_directory("imported".to_string(), &modded_dir, &base_dir, &cache, false).is_err());
+    }
+
+    #[test]
+    fn test_import_reuses_existing_blobs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let modded_dir = temp_dir.path().join("modded");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let manager = ProfileManager::new(temp_dir.path().join("profiles"));
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::create_dir_all(modded_dir.join("modloader")).unwrap();
+        fs::write(modded_dir.join("modloader/big_mod.img"), "already cached").unwrap();
+
+        // The same content was already normalized by another profile
+        let existing = temp_dir.path().join("existing.img");
+        fs::write(&existing, "already cached").unwrap();
+        let blob = cache.ensure_blob(&existing).unwrap();
+        let count_blobs = || walkdir::WalkDir::new(cache.blobs_dir())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .count();
+        assert_eq!(count_blobs(), 1);
+
+        let (profile, summary) = manager.import_from_directory("imported".to_string(), &modded_dir, &base_dir, &cache, false).unwrap();
+        assert_eq!(summary.new_files, 1);
+        assert_eq!(count_blobs(), 1);
+
+        // The workspace file is the existing blob, not a fresh copy
+        let imported = profile.workspace_dir.join("modloader").join("big_mod.img");
+        #[cfg(windows)]
+        assert!(crate::path_utils::are_files_hardlinked(&imported, &blob.path));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(fs::metadata(&imported).unwrap().ino(), fs::metadata(&blob.path).unwrap().ino());
+        }
+        assert_eq!(fs::read_to_string(&imported).unwrap(), "already cached");
+    }
+
     #[test]
     fn test_invalid_profile_names() {
         let temp_dir = TempDir::new().unwrap();
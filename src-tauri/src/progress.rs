@@ -0,0 +1,118 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::settings::UserPreferences;
+
+/// How often long-running operations emit progress events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressCadence {
+    /// Emit every time this percentage of the total is done
+    Percent { percent: f64 },
+    /// Emit at most once per interval
+    Interval { interval_ms: u64 },
+}
+
+impl ProgressCadence {
+    /// The cadence the user's preferences ask for
+    pub fn from_preferences(preferences: &UserPreferences) -> Self {
+        match preferences.progress_mode.as_str() {
+            "interval" => Self::Interval { interval_ms: preferences.progress_interval_ms },
+            _ => Self::Percent { percent: preferences.progress_percent },
+        }
+    }
+}
+
+impl Default for ProgressCadence {
+    fn default() -> Self {
+        Self::Percent { percent: 1.0 }
+    }
+}
+
+/// Decides which processed-item counts emit progress, so the number of events
+/// stays about the same whether an operation covers a hundred files or a hundred thousand
+/// Shared by workers: each count is offered by exactly one caller
+pub struct ProgressThrottle {
+    total: usize,
+    /// Items between events in percent mode
+    step: usize,
+    /// Minimum time between events in interval mode
+    interval: Option<Duration>,
+    last_emit: Mutex<Option<Instant>>,
+}
+
+impl ProgressThrottle {
+    /// Pace progress for an operation over `total` items
+    pub fn new(cadence: ProgressCadence, total: usize) -> Self {
+        let (step, interval) = match cadence {
+            ProgressCadence::Percent { percent } => {
+                let step = (total as f64 * percent.clamp(0.0, 100.0) / 100.0).ceil() as usize;
+                (step.max(1), None)
+            }
+            ProgressCadence::Interval { interval_ms } => (1, Some(Duration::from_millis(interval_ms))),
+        };
+        Self {
+            total,
+            step,
+            interval,
+            last_emit: Mutex::new(None),
+        }
+    }
+
+    /// Whether progress should be emitted now that `done` items are finished
+    /// The last item always emits, so the final count is reported
+    pub fn should_emit(&self, done: usize) -> bool {
+        if done == self.total {
+            return true;
+        }
+        let Some(interval) = self.interval else {
+            return done % self.step == 0;
+        };
+
+        let Ok(mut last_emit) = self.last_emit.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        if last_emit.is_some_and(|last| now.duration_since(last) < interval) {
+            return false;
+        }
+        *last_emit = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(cadence: ProgressCadence, total: usize) -> usize {
+        let throttle = ProgressThrottle::new(cadence, total);
+        (1..=total).filter(|&done| throttle.should_emit(done)).count()
+    }
+
+    #[test]
+    fn test_event_count_is_bounded_at_any_scale() {
+        let cadence = ProgressCadence::default();
+
+        // Tiny plans still report every file, and the last one
+        assert_eq!(events(cadence, 5), 5);
+        assert_eq!(events(cadence, 300), 100);
+
+        // Huge plans don't flood the UI
+        let huge = events(cadence, 200_000);
+        assert!((100..=101).contains(&huge), "{} events", huge);
+
+        // Coarser steps emit fewer events
+        assert!(events(ProgressCadence::Percent { percent: 10.0 }, 200_000) <= 11);
+    }
+
+    #[test]
+    fn test_interval_cadence_limits_events_by_time() {
+        // Nothing can finish within an hour of the first event, so only the first and last emit
+        let cadence = ProgressCadence::Interval { interval_ms: 3_600_000 };
+        assert_eq!(events(cadence, 200_000), 2);
+        assert_eq!(events(cadence, 1), 1);
+
+        let throttle = ProgressThrottle::new(ProgressCadence::Interval { interval_ms: 0 }, 10);
+        assert!((1..=10).all(|done| throttle.should_emit(done)));
+    }
+}
@@ -1,18 +1,26 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use anyhow::{Context, Result, anyhow};
 use rayon::prelude::*;
 use tracing::{info, warn, error};
 
-use crate::runtime_planner::{RuntimePlan, RuntimePlanEntry, RuntimeSource, RuntimePlanner};
+use crate::runtime_planner::{RuntimePlan, RuntimePlanEntry, RuntimeSource, RuntimePlanner, PlanProgress, PlanProgressCallback, PLAN_FILE, PREVIOUS_PLAN_FILE};
 use crate::blob_cache::{BlobCache, BlobPath};
 use crate::settings::Settings;
+use crate::profiles::ProfileManager;
+use crate::path_utils::{safe_join, are_files_hardlinked};
+use crate::progress::{ProgressCadence, ProgressThrottle};
 use blake3::Hash;
 
+/// Plan entries whose presence and size are checked in a finished runtime, spread evenly over the plan
+const PLAN_SAMPLE_SIZE: usize = 32;
+
 /// Progress information for runtime building
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildProgress {
@@ -38,11 +46,41 @@ pub struct BuildProgress {
     pub completed: bool,
 }
 
+impl BuildProgress {
+    /// Create a progress update for a phase, with step numbering derived from the phase
+    pub fn new(phase: BuildPhase) -> Self {
+        Self {
+            current_step: phase.step(),
+            total_steps: BuildPhase::total(),
+            phase,
+            current_file: None,
+            files_processed: 0,
+            total_files: 0,
+            bytes_processed: 0,
+            total_bytes: 0,
+            error: None,
+            completed: false,
+        }
+    }
+
+    /// Create a final progress update for a build that failed during the given phase
+    pub fn failed(during: BuildPhase, error: String) -> Self {
+        Self {
+            phase: BuildPhase::Failed,
+            error: Some(error),
+            completed: true,
+            ..Self::new(during)
+        }
+    }
+}
+
 /// Phases of runtime building
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BuildPhase {
     /// Validating prerequisites
     Preflight,
+    /// Computing the runtime plan
+    Planning,
     /// Creating temporary runtime directory
     CreateTemp,
     /// Linking base game files
@@ -57,6 +95,33 @@ pub enum BuildPhase {
     Failed,
 }
 
+impl BuildPhase {
+    /// Working phases in the order a build runs through them
+    pub const ORDERED: [BuildPhase; 6] = [
+        BuildPhase::Preflight,
+        BuildPhase::Planning,
+        BuildPhase::CreateTemp,
+        BuildPhase::LinkBase,
+        BuildPhase::OverlayWorkspace,
+        BuildPhase::Finalize,
+    ];
+
+    /// Total number of steps reported in build progress
+    pub fn total() -> usize {
+        Self::ORDERED.len()
+    }
+
+    /// 1-based step number of this phase
+    /// Terminal phases (Complete/Failed) report the final step
+    pub fn step(&self) -> usize {
+        Self::ORDERED
+            .iter()
+            .position(|phase| phase == self)
+            .map(|index| index + 1)
+            .unwrap_or_else(Self::total)
+    }
+}
+
 /// Statistics from a runtime build
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildStats {
@@ -68,6 +133,12 @@ pub struct BuildStats {
     pub blob_files: usize,
     /// Total size in bytes
     pub total_bytes: u64,
+    /// Files hardlinked into the runtime
+    #[serde(default)]
+    pub files_linked: usize,
+    /// Files copied into the runtime (protected files and `copy_extensions` matches)
+    #[serde(default)]
+    pub files_copied: usize,
     /// Time taken for the build in milliseconds
     pub build_time_ms: u64,
     /// Average files per second
@@ -76,6 +147,217 @@ pub struct BuildStats {
     pub mb_per_second: f64,
 }
 
+/// File in the runtimes directory recording the stats of recent successful builds
+pub const BUILD_HISTORY_FILE: &str = "build_history.json";
+
+/// Number of recent builds kept in the build history
+const BUILD_HISTORY_LIMIT: usize = 20;
+
+/// Rates assumed when there is no build history yet (deliberately conservative)
+const DEFAULT_FILES_PER_SECOND: f64 = 200.0;
+const DEFAULT_MB_PER_SECOND: f64 = 50.0;
+
+/// Rough duration of a build, estimated from past build rates and the plan's size
+/// Every build links the full plan, so there is no separate incremental estimate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildEstimate {
+    /// Estimated build time in milliseconds
+    pub estimated_ms: u64,
+    /// Past builds the rates came from (0 means the default rates were used)
+    pub based_on_samples: usize,
+    /// Files in the plan
+    pub total_files: usize,
+    /// Bytes in the plan
+    pub total_bytes: u64,
+}
+
+impl BuildEstimate {
+    /// Estimate from past builds, using the slower of the file and byte rates
+    /// Rates are weighted by build size, so a few tiny builds don't skew them
+    pub fn from_history(history: &[BuildStats], total_files: usize, total_bytes: u64) -> Self {
+        let samples: Vec<&BuildStats> = history.iter().filter(|stats| stats.build_time_ms > 0).collect();
+        let (files_per_second, mb_per_second) = if samples.is_empty() {
+            (DEFAULT_FILES_PER_SECOND, DEFAULT_MB_PER_SECOND)
+        } else {
+            let seconds = samples.iter().map(|stats| stats.build_time_ms).sum::<u64>() as f64 / 1000.0;
+            let files = samples.iter().map(|stats| stats.total_files).sum::<usize>() as f64;
+            let megabytes = samples.iter().map(|stats| stats.total_bytes).sum::<u64>() as f64 / 1024.0 / 1024.0;
+            (files / seconds, megabytes / seconds)
+        };
+
+        let mut estimated_seconds: f64 = 0.0;
+        if files_per_second > 0.0 {
+            estimated_seconds = estimated_seconds.max(total_files as f64 / files_per_second);
+        }
+        if mb_per_second > 0.0 {
+            estimated_seconds = estimated_seconds.max(total_bytes as f64 / 1024.0 / 1024.0 / mb_per_second);
+        }
+
+        Self {
+            estimated_ms: (estimated_seconds * 1000.0).round() as u64,
+            based_on_samples: samples.len(),
+            total_files,
+            total_bytes,
+        }
+    }
+}
+
+/// Options controlling how a runtime build handles failures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildOptions {
+    /// Abort the build on the first file that fails to link (default)
+    pub fail_fast: bool,
+    /// When not failing fast, the most per-file errors tolerated before the build fails
+    pub max_file_errors: usize,
+    /// Skip linking when the existing runtime was built from an identical plan
+    #[serde(default)]
+    pub skip_if_fresh: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            fail_fast: true,
+            max_file_errors: 25,
+            skip_if_fresh: false,
+        }
+    }
+}
+
+/// Error a build stops with once its cancel flag is set
+#[derive(Debug)]
+pub struct BuildCancelled;
+
+impl fmt::Display for BuildCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Build was cancelled")
+    }
+}
+
+impl std::error::Error for BuildCancelled {}
+
+/// Broad category of a build error, for display and diagnostics
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum BuildErrorKind {
+    /// A source file or directory was missing
+    MissingFile,
+    /// Access to a file was denied (often a running game or antivirus holding it)
+    AccessDenied,
+    /// A hardlink was attempted across volumes
+    CrossVolume,
+    /// Any other I/O failure
+    Io,
+    /// More files failed than the build tolerates
+    TooManyFileErrors,
+    /// The build was cancelled before it finished
+    Cancelled,
+    /// Not an I/O failure (validation, planning, ...)
+    #[default]
+    Other,
+}
+
+impl BuildErrorKind {
+    /// Classify an error by the first I/O error in its chain
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if error.chain().any(|cause| cause.is::<BuildCancelled>()) {
+            return Self::Cancelled;
+        }
+
+        let io_error = match error.chain().find_map(|cause| cause.downcast_ref::<io::Error>()) {
+            Some(io_error) => io_error,
+            None => return Self::Other,
+        };
+
+        // ERROR_NOT_SAME_DEVICE on Windows, EXDEV elsewhere; wrapped errors keep the OS error further down the chain
+        let cross_device_code = if cfg!(windows) { 17 } else { 18 };
+        let crosses_devices = error.chain()
+            .filter_map(|cause| cause.downcast_ref::<io::Error>())
+            .any(|cause| cause.raw_os_error() == Some(cross_device_code));
+        if crosses_devices {
+            return Self::CrossVolume;
+        }
+
+        match io_error.kind() {
+            io::ErrorKind::NotFound => Self::MissingFile,
+            io::ErrorKind::PermissionDenied => Self::AccessDenied,
+            _ => Self::Io,
+        }
+    }
+}
+
+/// A single file that failed to link during a build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildFileError {
+    /// Relative path of the file in the runtime
+    pub rel_path: String,
+    /// Why the file failed
+    pub error: String,
+    /// Category of the failure
+    #[serde(default)]
+    pub kind: BuildErrorKind,
+}
+
+impl BuildFileError {
+    fn from_error(rel_path: &str, error: &anyhow::Error) -> Self {
+        Self {
+            rel_path: rel_path.to_string(),
+            error: format!("{:#}", error),
+            kind: BuildErrorKind::classify(error),
+        }
+    }
+}
+
+impl fmt::Display for BuildFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rel_path, self.error)
+    }
+}
+
+impl std::error::Error for BuildFileError {}
+
+/// Where and why a build failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildFailure {
+    /// Phase the build was in when it failed
+    pub phase: BuildPhase,
+    /// File being processed when the build failed, if any
+    pub rel_path: Option<String>,
+    /// Category of the failure
+    pub kind: BuildErrorKind,
+    /// Underlying error message
+    pub message: String,
+}
+
+impl BuildFailure {
+    /// Describe a failure in the given phase, picking up the failing file when a per-file operation caused it
+    pub fn from_error(phase: BuildPhase, error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<BuildFileError>() {
+            Some(file_error) => Self {
+                phase,
+                rel_path: Some(file_error.rel_path.clone()),
+                kind: file_error.kind,
+                message: file_error.error.clone(),
+            },
+            None => Self {
+                phase,
+                rel_path: None,
+                kind: BuildErrorKind::classify(error),
+                message: format!("{:#}", error),
+            },
+        }
+    }
+}
+
+impl fmt::Display for BuildFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed during {:?}", self.phase)?;
+        if let Some(rel_path) = &self.rel_path {
+            write!(f, " on {}", rel_path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
 /// Result of a runtime build operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildResult {
@@ -87,30 +369,213 @@ pub struct BuildResult {
     pub stats: Option<BuildStats>,
     /// Error message if build failed
     pub error: Option<String>,
+    /// Files that failed to link when the build tolerates per-file errors
+    #[serde(default)]
+    pub file_errors: Vec<BuildFileError>,
+    /// Phase, file and kind of the failure if the build failed
+    #[serde(default)]
+    pub failure: Option<BuildFailure>,
+    /// Whether the existing runtime was already up to date, so nothing was linked
+    #[serde(default)]
+    pub up_to_date: bool,
+    /// Whether the base installation changed since the last build, forcing a full rebuild
+    #[serde(default)]
+    pub base_changed: bool,
+}
+
+/// What cleaning up runtimes of deleted profiles removed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OrphanedRuntimeCleanup {
+    /// Runtime directories that were removed
+    pub removed: Vec<String>,
+    /// Files and directories removed across them
+    pub entries_removed: usize,
+    /// Total size of the removed files; blobs they were linked to stay in the cache, so less disk may be freed
+    pub bytes_reclaimed: u64,
+}
+
+/// What relinking a single file into a runtime changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileApplyResult {
+    /// Relative path from game root
+    pub rel_path: String,
+    /// Where the runtime took the file from before (None if it wasn't in the runtime)
+    pub previous_source: Option<RuntimeSource>,
+    /// Where the runtime takes the file from now (None if it was removed)
+    pub source: Option<RuntimeSource>,
+}
+
+/// Whether a built runtime is ready to launch, and what is wrong if it isn't
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LaunchCheck {
+    /// Whether no problems were found
+    pub ready: bool,
+    /// Path of the runtime that was checked
+    pub runtime_path: PathBuf,
+    /// Whether the runtime directory exists
+    pub runtime_exists: bool,
+    /// Whether the runtime contains the game executable
+    pub exe_present: bool,
+    /// Whether the executable matches its expected source (None if that couldn't be checked)
+    pub exe_matches: Option<bool>,
+    /// Problems preventing launch, in a form the UI can show directly
+    pub problems: Vec<String>,
+}
+
+/// Why a runtime file sourced from the cache no longer shares its data with its blob
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum LinkProblem {
+    /// The file was replaced by an independent copy (antivirus and backup tools do this)
+    Delinked,
+    /// The file is missing from the runtime
+    Missing,
+    /// The blob is missing from the cache, so the file can't be relinked
+    BlobMissing,
+}
+
+/// A runtime file that should be hardlinked to a cache blob but isn't
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkIssue {
+    /// Relative path from game root
+    pub rel_path: String,
+    /// Hash of the blob the file should be linked to
+    pub hash: String,
+    pub problem: LinkProblem,
 }
 
 /// Callback function type for progress updates
 pub type ProgressCallback = Arc<dyn Fn(BuildProgress) + Send + Sync>;
 
+/// Counts files finished by parallel workers and emits their progress through a single point,
+/// so reported counts never go backwards and `current_file` is a file that just finished
+struct ProgressReporter<'a> {
+    callback: &'a ProgressCallback,
+    total_files: usize,
+    total_bytes: u64,
+    files_processed: AtomicUsize,
+    files_copied: AtomicUsize,
+    bytes_processed: AtomicU64,
+    /// Paces events over both file phases
+    throttle: ProgressThrottle,
+    /// Highest file count emitted so far; held while emitting
+    last_emitted: Mutex<usize>,
+}
+
+impl<'a> ProgressReporter<'a> {
+    fn new(callback: &'a ProgressCallback, plan: &RuntimePlan, cadence: ProgressCadence) -> Self {
+        Self {
+            callback,
+            total_files: plan.total_files,
+            total_bytes: plan.total_size,
+            files_processed: AtomicUsize::new(0),
+            files_copied: AtomicUsize::new(0),
+            bytes_processed: AtomicU64::new(0),
+            throttle: ProgressThrottle::new(cadence, plan.total_files),
+            last_emitted: Mutex::new(0),
+        }
+    }
+
+    /// Progress for the start of a phase, carrying the counts so far
+    fn phase_progress(&self, phase: BuildPhase) -> BuildProgress {
+        BuildProgress {
+            files_processed: self.files_processed.load(Ordering::SeqCst),
+            total_files: self.total_files,
+            bytes_processed: self.bytes_processed.load(Ordering::SeqCst),
+            total_bytes: self.total_bytes,
+            ..BuildProgress::new(phase)
+        }
+    }
+
+    /// Count a finished file (copied or hardlinked), emitting progress when the throttle allows
+    fn file_done(&self, phase: BuildPhase, entry: &RuntimePlanEntry, copied: bool) {
+        if copied {
+            self.files_copied.fetch_add(1, Ordering::SeqCst);
+        }
+        let processed = self.files_processed.fetch_add(1, Ordering::SeqCst) + 1;
+        self.bytes_processed.fetch_add(entry.size, Ordering::SeqCst);
+        if !self.throttle.should_emit(processed) {
+            return;
+        }
+
+        let Ok(mut last_emitted) = self.last_emitted.lock() else {
+            return;
+        };
+        // Read the counts under the lock; a slower worker whose count was already passed stays quiet
+        let files_processed = self.files_processed.load(Ordering::SeqCst);
+        if files_processed <= *last_emitted {
+            return;
+        }
+        *last_emitted = files_processed;
+
+        (self.callback)(BuildProgress {
+            current_file: Some(entry.rel_path.clone()),
+            files_processed,
+            bytes_processed: self.bytes_processed.load(Ordering::SeqCst),
+            ..self.phase_progress(phase)
+        });
+    }
+}
+
 /// Runtime builder that creates hardlink-based game runtimes
 pub struct RuntimeBuilder {
     settings: Settings,
     blob_cache: BlobCache,
     planner: RuntimePlanner,
+    options: BuildOptions,
+    /// Checked between phases and files; setting it stops the build
+    cancel_flag: Option<Arc<AtomicBool>>,
 }
 
 impl RuntimeBuilder {
     /// Create a new runtime builder
     pub fn new(settings: Settings) -> Self {
-        let cache_dir = settings.data_root.join("cache");
-        let blob_cache = BlobCache::new(&cache_dir);
+        let blob_cache = settings.blob_cache();
         let planner = RuntimePlanner::new(settings.clone());
 
         Self {
             settings,
             blob_cache,
             planner,
+            options: BuildOptions::default(),
+            cancel_flag: None,
+        }
+    }
+
+    /// Set the options used for builds
+    pub fn with_options(mut self, options: BuildOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Stop the build (removing its temporary runtime) once `cancel_flag` is set
+    pub fn with_cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(anyhow::Error::new(BuildCancelled));
         }
+        Ok(())
+    }
+
+    /// Whether the profile's latest runtime was built from a plan identical to `plan` and still has its executable
+    /// Base files changed in place without changing size aren't detected here; builds check the base fingerprint for those
+    pub fn is_runtime_fresh(&self, plan: &RuntimePlan) -> Result<bool> {
+        let runtime_path = self.settings.data_root.join("runtimes").join(format!("{}-latest", plan.profile_name));
+        if !runtime_path.join(Settings::GAME_EXECUTABLE).is_file() {
+            return Ok(false);
+        }
+
+        Ok(match self.planner.load_plan(&plan.profile_name)? {
+            Some(saved_plan) => self.planner.diff_plans(&saved_plan, plan).is_empty(),
+            None => false,
+        })
     }
 
     /// Build a runtime for the specified profile
@@ -125,157 +590,158 @@ impl RuntimeBuilder {
         info!("Starting runtime build for profile: {}", profile_name);
 
         // Phase 1: Preflight checks
-        callback(BuildProgress {
-            phase: BuildPhase::Preflight,
-            current_step: 1,
-            total_steps: 5,
-            current_file: None,
-            files_processed: 0,
-            total_files: 0,
-            bytes_processed: 0,
-            total_bytes: 0,
-            error: None,
-            completed: false,
+        callback(BuildProgress::new(BuildPhase::Preflight));
+
+        let (base_path, base_changed) = match self.preflight_checks(profile_name) {
+            Ok(preflight) => preflight,
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Preflight, &e), Vec::new(), None, &callback)),
+        };
+
+        // Phase 2: Compute runtime plan
+        callback(BuildProgress::new(BuildPhase::Planning));
+
+        // Forward planner progress through the build's progress callback
+        let planning_callback = callback.clone();
+        let plan_progress: PlanProgressCallback = Arc::new(move |progress: PlanProgress| {
+            planning_callback(BuildProgress {
+                current_file: progress.current_file,
+                files_processed: progress.files_planned,
+                ..BuildProgress::new(BuildPhase::Planning)
+            });
         });
 
-        if let Err(e) = self.preflight_checks() {
-            let error_msg = format!("Preflight checks failed: {}", e);
-            error!("{}", error_msg);
+        let mut plan = match self.planner.compute_plan_with_progress(profile_name, Some(plan_progress)) {
+            Ok(plan) => plan,
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Planning, &e), Vec::new(), None, &callback)),
+        };
+        if let Err(e) = self.check_cancelled() {
+            return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Planning, &e), Vec::new(), None, &callback));
+        }
+
+        // No-op fast path: the existing runtime already matches the plan (and its base files weren't patched)
+        if self.options.skip_if_fresh && !base_changed && self.is_runtime_fresh(&plan).unwrap_or(false) {
+            info!("Runtime for profile '{}' is up to date, skipping build", profile_name);
             callback(BuildProgress {
-                phase: BuildPhase::Failed,
-                current_step: 1,
-                total_steps: 5,
-                current_file: None,
-                files_processed: 0,
-                total_files: 0,
-                bytes_processed: 0,
-                total_bytes: 0,
-                error: Some(error_msg.clone()),
+                files_processed: plan.total_files,
+                total_files: plan.total_files,
+                bytes_processed: plan.total_size,
+                total_bytes: plan.total_size,
                 completed: true,
+                ..BuildProgress::new(BuildPhase::Complete)
             });
+
             return Ok(BuildResult {
-                success: false,
-                runtime_path: None,
+                success: true,
+                runtime_path: Some(self.settings.data_root.join("runtimes").join(format!("{}-latest", profile_name))),
                 stats: None,
-                error: Some(error_msg),
+                error: None,
+                file_errors: Vec::new(),
+                failure: None,
+                up_to_date: true,
+                base_changed: false,
             });
         }
 
-        // Compute or load runtime plan
-        let plan = match self.planner.compute_plan(profile_name) {
-            Ok(plan) => plan,
-            Err(e) => {
-                let error_msg = format!("Failed to compute runtime plan: {}", e);
-                error!("{}", error_msg);
-                callback(BuildProgress {
-                    phase: BuildPhase::Failed,
-                    current_step: 1,
-                    total_steps: 5,
-                    current_file: None,
-                    files_processed: 0,
-                    total_files: 0,
-                    bytes_processed: 0,
-                    total_bytes: 0,
-                    error: Some(error_msg.clone()),
-                    completed: true,
-                });
-                return Ok(BuildResult {
-                    success: false,
-                    runtime_path: None,
-                    stats: None,
-                    error: Some(error_msg),
-                });
-            }
-        };
-
-        // Phase 2: Create temporary runtime directory
+        // Phase 3: Create temporary runtime directory
         callback(BuildProgress {
-            phase: BuildPhase::CreateTemp,
-            current_step: 2,
-            total_steps: 5,
-            current_file: None,
-            files_processed: 0,
             total_files: plan.total_files,
-            bytes_processed: 0,
             total_bytes: plan.total_size,
-            error: None,
-            completed: false,
+            ..BuildProgress::new(BuildPhase::CreateTemp)
         });
 
-        let temp_runtime_dir = self.create_temp_runtime_dir(profile_name)?;
+        let temp_runtime_dir = match self.create_temp_runtime_dir(profile_name) {
+            Ok(temp_runtime_dir) => temp_runtime_dir,
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::CreateTemp, &e), Vec::new(), None, &callback)),
+        };
         info!("Created temporary runtime directory: {}", temp_runtime_dir.display());
 
         // Build counters for progress tracking
-        let files_processed = Arc::new(AtomicUsize::new(0));
-        let bytes_processed = Arc::new(AtomicUsize::new(0));
+        let progress = ProgressReporter::new(&callback, &plan, ProgressCadence::from_preferences(&self.settings.preferences));
 
-        // Phase 3: Link base game files
-        callback(BuildProgress {
-            phase: BuildPhase::LinkBase,
-            current_step: 3,
-            total_steps: 5,
-            current_file: None,
-            files_processed: 0,
-            total_files: plan.total_files,
-            bytes_processed: 0,
-            total_bytes: plan.total_size,
-            error: None,
-            completed: false,
-        });
+        // Phase 4: Link base game files
+        callback(progress.phase_progress(BuildPhase::LinkBase));
 
         let base_entries: Vec<_> = plan.entries.iter()
             .filter(|entry| matches!(entry.source, RuntimeSource::Base))
             .collect();
 
-        self.link_base_files(&base_entries, &temp_runtime_dir, &files_processed, &bytes_processed, &callback, &plan)?;
+        let mut file_errors = match self.link_base_files(&base_entries, &base_path, &temp_runtime_dir, &progress) {
+            Ok(file_errors) => file_errors,
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::LinkBase, &e), Vec::new(), Some(&temp_runtime_dir), &callback)),
+        };
 
-        // Phase 4: Overlay workspace files
-        callback(BuildProgress {
-            phase: BuildPhase::OverlayWorkspace,
-            current_step: 4,
-            total_steps: 5,
-            current_file: None,
-            files_processed: files_processed.load(Ordering::Relaxed),
-            total_files: plan.total_files,
-            bytes_processed: bytes_processed.load(Ordering::Relaxed) as u64,
-            total_bytes: plan.total_size,
-            error: None,
-            completed: false,
-        });
+        // Phase 5: Overlay workspace files
+        callback(progress.phase_progress(BuildPhase::OverlayWorkspace));
 
         let blob_entries: Vec<_> = plan.entries.iter()
-            .filter(|entry| matches!(entry.source, RuntimeSource::Blob(_)))
+            .filter(|entry| matches!(entry.source, RuntimeSource::Blob(_) | RuntimeSource::Workspace | RuntimeSource::Shared))
             .collect();
 
-        self.overlay_workspace_files(&blob_entries, &temp_runtime_dir, &files_processed, &bytes_processed, &callback, &plan)?;
+        // Protected files are copied straight from the profile's workspace
+        let overlay_result = ProfileManager::new(self.settings.data_root.join("profiles"))
+            .get_profile(profile_name)
+            .and_then(|profile| profile.ok_or_else(|| anyhow!("Profile '{}' not found", profile_name)))
+            .and_then(|profile| self.overlay_workspace_files(&blob_entries, &profile.workspace_dir, &temp_runtime_dir, &progress));
 
-        // Phase 5: Finalize runtime
-        callback(BuildProgress {
-            phase: BuildPhase::Finalize,
-            current_step: 5,
-            total_steps: 5,
-            current_file: None,
-            files_processed: files_processed.load(Ordering::Relaxed),
-            total_files: plan.total_files,
-            bytes_processed: bytes_processed.load(Ordering::Relaxed) as u64,
-            total_bytes: plan.total_size,
-            error: None,
-            completed: false,
-        });
+        match overlay_result {
+            Ok(overlay_errors) => file_errors.extend(overlay_errors),
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::OverlayWorkspace, &e), file_errors, Some(&temp_runtime_dir), &callback)),
+        }
+
+        // Too many files failed for the runtime to be trusted
+        if file_errors.len() > self.options.max_file_errors {
+            let failure = BuildFailure {
+                phase: BuildPhase::OverlayWorkspace,
+                rel_path: None,
+                kind: BuildErrorKind::TooManyFileErrors,
+                message: format!("{} files failed to link (limit {})", file_errors.len(), self.options.max_file_errors),
+            };
+            return Ok(self.failed_build(failure, file_errors, Some(&temp_runtime_dir), &callback));
+        }
+
+        if !file_errors.is_empty() {
+            warn!("Runtime built with {} files that failed to link", file_errors.len());
+        }
 
-        let final_runtime_dir = self.finalize_runtime(profile_name, temp_runtime_dir)?;
+        // Last chance to cancel before the previous runtime is replaced
+        if let Err(e) = self.check_cancelled() {
+            return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Finalize, &e), file_errors, Some(&temp_runtime_dir), &callback));
+        }
+
+        // Phase 6: Finalize runtime
+        callback(progress.phase_progress(BuildPhase::Finalize));
+
+        // Only a runtime that actually matches its plan replaces the previous one
+        if let Err(e) = self.verify_runtime_matches_plan(&temp_runtime_dir, &plan, &file_errors) {
+            return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Finalize, &e), file_errors, Some(&temp_runtime_dir), &callback));
+        }
+
+        let final_runtime_dir = match self.finalize_runtime(profile_name, temp_runtime_dir.clone()) {
+            Ok(final_runtime_dir) => final_runtime_dir,
+            Err(e) => return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Finalize, &e), file_errors, Some(&temp_runtime_dir), &callback)),
+        };
         
+        // The runtime now links the current base files
+        if let Err(e) = self.planner.record_base_fingerprint(&mut plan, &base_path) {
+            warn!("Failed to record base fingerprint: {}", e);
+        }
+
         // Save the runtime plan to the final directory
-        self.planner.save_plan(&plan)?;
+        if let Err(e) = self.planner.save_plan(&plan) {
+            return Ok(self.failed_build(BuildFailure::from_error(BuildPhase::Finalize, &e), file_errors, None, &callback));
+        }
 
         let build_time = start_time.elapsed().unwrap_or_default();
         let build_time_ms = build_time.as_millis() as u64;
+        let files_copied = progress.files_copied.load(Ordering::SeqCst);
         
         let stats = BuildStats {
             total_files: plan.total_files,
             base_files: plan.base_files,
             blob_files: plan.blob_files,
             total_bytes: plan.total_size,
+            files_linked: progress.files_processed.load(Ordering::SeqCst).saturating_sub(files_copied),
+            files_copied,
             build_time_ms,
             files_per_second: if build_time_ms > 0 {
                 (plan.total_files as f64 * 1000.0) / build_time_ms as f64
@@ -297,18 +763,19 @@ impl RuntimeBuilder {
             stats.mb_per_second
         );
 
+        // The history only feeds estimates, so failing to record it doesn't fail the build
+        if let Err(e) = self.record_build_stats(&stats) {
+            warn!("Failed to record build history: {}", e);
+        }
+
         // Final progress update
         callback(BuildProgress {
-            phase: BuildPhase::Complete,
-            current_step: 5,
-            total_steps: 5,
-            current_file: None,
             files_processed: plan.total_files,
             total_files: plan.total_files,
             bytes_processed: plan.total_size,
             total_bytes: plan.total_size,
-            error: None,
             completed: true,
+            ..BuildProgress::new(BuildPhase::Complete)
         });
 
         Ok(BuildResult {
@@ -316,20 +783,67 @@ impl RuntimeBuilder {
             runtime_path: Some(final_runtime_dir),
             stats: Some(stats),
             error: None,
+            file_errors,
+            failure: None,
+            up_to_date: false,
+            base_changed,
         })
     }
 
+    /// Report a failed build: log it, remove the temporary runtime and emit the final progress update
+    fn failed_build(
+        &self,
+        failure: BuildFailure,
+        file_errors: Vec<BuildFileError>,
+        temp_runtime_dir: Option<&Path>,
+        callback: &ProgressCallback,
+    ) -> BuildResult {
+        let error_msg = failure.to_string();
+        error!("{}", error_msg);
+
+        if let Some(temp_runtime_dir) = temp_runtime_dir.filter(|dir| dir.exists()) {
+            if let Err(e) = fs::remove_dir_all(temp_runtime_dir) {
+                warn!("Failed to remove temporary runtime directory {}: {}", temp_runtime_dir.display(), e);
+            }
+        }
+
+        callback(BuildProgress {
+            current_file: failure.rel_path.clone(),
+            ..BuildProgress::failed(failure.phase.clone(), error_msg.clone())
+        });
+
+        BuildResult {
+            success: false,
+            runtime_path: None,
+            stats: None,
+            error: Some(error_msg),
+            file_errors,
+            failure: Some(failure),
+            up_to_date: false,
+            base_changed: false,
+        }
+    }
+
     /// Perform preflight checks before building
-    fn preflight_checks(&self) -> Result<()> {
+    /// Returns the base installation to build from (the profile's override if it has one),
+    /// and whether it changed since the last build so the runtime must be fully rebuilt
+    fn preflight_checks(&self, profile_name: &str) -> Result<(PathBuf, bool)> {
         info!("Performing preflight checks");
 
+        // Resolve and validate the profile's base path
+        let base_path = self.planner.base_path_for_profile(profile_name)?;
+
         // Check that base path exists
-        if !self.settings.base_path.exists() {
-            return Err(anyhow!("Base game path does not exist: {}", self.settings.base_path.display()));
+        if !base_path.exists() {
+            return Err(anyhow!("Base game path does not exist: {}", base_path.display()));
         }
 
+        // Read access is all the build needs from the base
+        fs::read_dir(&base_path)
+            .with_context(|| format!("Base game path is not readable: {}", base_path.display()))?;
+
         // Check that cache directory exists
-        let cache_dir = self.settings.data_root.join("cache");
+        let cache_dir = self.settings.get_cache_directory();
         if !cache_dir.exists() {
             return Err(anyhow!("Cache directory does not exist: {}", cache_dir.display()));
         }
@@ -337,11 +851,24 @@ impl RuntimeBuilder {
         // Note: Volume compatibility is ensured during settings validation
         // All paths (base, cache, profiles) are guaranteed to be on the same NTFS volume
 
+        // A patched or updated base leaves earlier runtimes linked to stale files
+        let base_changed = match self.planner.check_base_fingerprint(profile_name, &base_path) {
+            Ok(check) => check.changed,
+            Err(e) => {
+                warn!("Failed to check base fingerprint: {}", e);
+                false
+            }
+        };
+        if base_changed {
+            warn!("Base installation changed since the last build; rebuilding the full runtime");
+        }
+
         info!("Preflight checks passed");
-        Ok(())
+        Ok((base_path, base_changed))
     }
 
     /// Create a temporary runtime directory
+    /// Always under `runtimes`, not the temp directory: finalizing renames it into place, which must stay on one volume
     fn create_temp_runtime_dir(&self, profile_name: &str) -> Result<PathBuf> {
         let runtimes_dir = self.settings.data_root.join("runtimes");
         fs::create_dir_all(&runtimes_dir)
@@ -360,19 +887,19 @@ impl RuntimeBuilder {
     }
 
     /// Link base game files to the runtime directory
+    /// Only needs read access to the base: hardlinks can be created from read-only files (and read-only
+    /// mounts of the same NTFS volume), and nothing is ever written into the base
     fn link_base_files(
         &self,
         entries: &[&RuntimePlanEntry],
+        base_path: &Path,
         runtime_dir: &Path,
-        files_processed: &Arc<AtomicUsize>,
-        bytes_processed: &Arc<AtomicUsize>,
-        callback: &ProgressCallback,
-        plan: &RuntimePlan,
-    ) -> Result<()> {
+        progress: &ProgressReporter,
+    ) -> Result<Vec<BuildFileError>> {
         info!("Linking {} base game files", entries.len());
 
-        entries.par_iter().try_for_each(|entry| -> Result<()> {
-            let source_path = self.settings.base_path.join(&entry.rel_path);
+        let file_errors = self.for_each_entry(entries, |entry| {
+            let source_path = base_path.join(&entry.rel_path);
             let dest_path = runtime_dir.join(&entry.rel_path);
 
             // Create parent directory if it doesn't exist
@@ -381,101 +908,138 @@ impl RuntimeBuilder {
                     .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
             }
 
-            // Create hardlink using standard library
-            std::fs::hard_link(&source_path, &dest_path)
-                .with_context(|| format!("Failed to create hardlink: {} -> {}", source_path.display(), dest_path.display()))?;
-
-            // Update progress counters
-            let processed = files_processed.fetch_add(1, Ordering::Relaxed) + 1;
-            bytes_processed.fetch_add(entry.size as usize, Ordering::Relaxed);
-
-            // Send progress update every 100 files
-            if processed % 100 == 0 {
-                callback(BuildProgress {
-                    phase: BuildPhase::LinkBase,
-                    current_step: 3,
-                    total_steps: 5,
-                    current_file: Some(entry.rel_path.clone()),
-                    files_processed: processed,
-                    total_files: plan.total_files,
-                    bytes_processed: bytes_processed.load(Ordering::Relaxed) as u64,
-                    total_bytes: plan.total_size,
-                    error: None,
-                    completed: false,
-                });
+            // Create hardlink using standard library, or an independent copy for copy extensions
+            let copied = self.is_copy_extension(&entry.rel_path);
+            if copied {
+                fs::copy(&source_path, &dest_path)
+                    .with_context(|| format!("Failed to copy base file: {} -> {}", source_path.display(), dest_path.display()))?;
+            } else {
+                std::fs::hard_link(&source_path, &dest_path)
+                    .with_context(|| format!("Failed to create hardlink: {} -> {}", source_path.display(), dest_path.display()))?;
             }
 
+            // Progress is emitted at the configured cadence
+            progress.file_done(BuildPhase::LinkBase, entry, copied);
+
             Ok(())
         })?;
 
         info!("Base file linking completed");
-        Ok(())
+        Ok(file_errors)
     }
 
     /// Overlay workspace files from blob cache to the runtime directory
+    /// Protected files have no blob and are copied from the workspace instead;
+    /// shared workspace files are linked straight from the shared workspace, like base files
     fn overlay_workspace_files(
         &self,
         entries: &[&RuntimePlanEntry],
+        workspace_dir: &Path,
         runtime_dir: &Path,
-        files_processed: &Arc<AtomicUsize>,
-        bytes_processed: &Arc<AtomicUsize>,
-        callback: &ProgressCallback,
-        plan: &RuntimePlan,
-    ) -> Result<()> {
+        progress: &ProgressReporter,
+    ) -> Result<Vec<BuildFileError>> {
         info!("Overlaying {} workspace files", entries.len());
 
-        entries.par_iter().try_for_each(|entry| -> Result<()> {
-            if let RuntimeSource::Blob(hash_str) = &entry.source {
-                let blob_path = self.blob_cache.get_blob_path_from_hash(hash_str)?;
-                let dest_path = runtime_dir.join(&entry.rel_path);
+        let file_errors = self.for_each_entry(entries, |entry| {
+            let dest_path = runtime_dir.join(&entry.rel_path);
 
-                // Create parent directory if it doesn't exist
-                if let Some(parent) = dest_path.parent() {
-                    fs::create_dir_all(parent)
-                        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-                }
+            // Create parent directory if it doesn't exist
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+            }
 
-                // If this is an override, remove the base file first
-                if entry.is_override && dest_path.exists() {
-                    fs::remove_file(&dest_path)
-                        .with_context(|| format!("Failed to remove base file for override: {}", dest_path.display()))?;
-                }
+            // If this is an override, remove the base file first
+            if entry.is_override && dest_path.exists() {
+                fs::remove_file(&dest_path)
+                    .with_context(|| format!("Failed to remove base file for override: {}", dest_path.display()))?;
+            }
 
-                // Create hardlink from blob cache to runtime using the existing BlobCache method
-                let blob_path = BlobPath {
-                    hash: Hash::from_hex(hash_str)
-                        .map_err(|e| anyhow::anyhow!("Invalid hash: {}", e))?,
-                    path: blob_path,
-                };
-                self.blob_cache.link_blob_to(&dest_path, &blob_path)
-                    .with_context(|| format!("Failed to create hardlink from blob: {} -> {}", blob_path.path.display(), dest_path.display()))?;
-
-                // Update progress counters
-                let processed = files_processed.fetch_add(1, Ordering::Relaxed) + 1;
-                bytes_processed.fetch_add(entry.size as usize, Ordering::Relaxed);
-
-                // Send progress update every 50 files
-                if processed % 50 == 0 {
-                    callback(BuildProgress {
-                        phase: BuildPhase::OverlayWorkspace,
-                        current_step: 4,
-                        total_steps: 5,
-                        current_file: Some(entry.rel_path.clone()),
-                        files_processed: processed,
-                        total_files: plan.total_files,
-                        bytes_processed: bytes_processed.load(Ordering::Relaxed) as u64,
-                        total_bytes: plan.total_size,
-                        error: None,
-                        completed: false,
-                    });
+            let copied = match &entry.source {
+                RuntimeSource::Blob(hash_str) => {
+                    // Create hardlink from blob cache to runtime using the existing BlobCache method
+                    let blob_path = BlobPath {
+                        hash: Hash::from_hex(hash_str)
+                            .map_err(|e| anyhow::anyhow!("Invalid hash: {}", e))?,
+                        path: self.blob_cache.get_blob_path_from_hash(hash_str)?,
+                    };
+                    if self.is_copy_extension(&entry.rel_path) {
+                        self.blob_cache.copy_blob_to(&dest_path, &blob_path)
+                            .with_context(|| format!("Failed to copy blob: {} -> {}", blob_path.path.display(), dest_path.display()))?;
+                        true
+                    } else {
+                        self.blob_cache.link_blob_to(&dest_path, &blob_path)
+                            .with_context(|| format!("Failed to create hardlink from blob: {} -> {}", blob_path.path.display(), dest_path.display()))?;
+                        false
+                    }
                 }
-            }
+                RuntimeSource::Workspace => {
+                    // Copy so runtime writes never reach the workspace file
+                    let source_path = workspace_dir.join(&entry.rel_path);
+                    fs::copy(&source_path, &dest_path)
+                        .with_context(|| format!("Failed to copy protected file: {} -> {}", source_path.display(), dest_path.display()))?;
+                    true
+                }
+                RuntimeSource::Shared => {
+                    let source_path = self.settings.get_shared_workspace_directory().join(&entry.rel_path);
+                    let copied = self.is_copy_extension(&entry.rel_path);
+                    if copied {
+                        fs::copy(&source_path, &dest_path)
+                            .with_context(|| format!("Failed to copy shared file: {} -> {}", source_path.display(), dest_path.display()))?;
+                    } else {
+                        fs::hard_link(&source_path, &dest_path)
+                            .with_context(|| format!("Failed to create hardlink: {} -> {}", source_path.display(), dest_path.display()))?;
+                    }
+                    copied
+                }
+                RuntimeSource::Base => return Ok(()),
+            };
+
+            // Progress is emitted at the configured cadence
+            progress.file_done(BuildPhase::OverlayWorkspace, entry, copied);
 
             Ok(())
         })?;
 
         info!("Workspace overlay completed");
-        Ok(())
+        Ok(file_errors)
+    }
+
+    /// Whether a runtime file's extension is configured to be copied instead of hardlinked
+    fn is_copy_extension(&self, rel_path: &str) -> bool {
+        self.settings.preferences.is_copy_extension(rel_path)
+    }
+
+    /// Run a per-file operation over plan entries in parallel
+    /// Fails fast on the first error, or collects per-file errors when the build tolerates them
+    fn for_each_entry<F>(&self, entries: &[&RuntimePlanEntry], operation: F) -> Result<Vec<BuildFileError>>
+    where
+        F: Fn(&RuntimePlanEntry) -> Result<()> + Sync,
+    {
+        if self.options.fail_fast {
+            // Keep the failing file with the error so the build result can report it
+            entries.par_iter().try_for_each(|entry| {
+                self.check_cancelled()?;
+                operation(entry).map_err(|e| anyhow::Error::new(BuildFileError::from_error(&entry.rel_path, &e)))
+            })?;
+            return Ok(Vec::new());
+        }
+
+        let file_errors = Mutex::new(Vec::new());
+        entries.par_iter().for_each(|entry| {
+            if self.is_cancelled() {
+                return;
+            }
+            if let Err(e) = operation(entry) {
+                warn!("Failed to link {}: {:#}", entry.rel_path, e);
+                if let Ok(mut file_errors) = file_errors.lock() {
+                    file_errors.push(BuildFileError::from_error(&entry.rel_path, &e));
+                }
+            }
+        });
+        self.check_cancelled()?;
+
+        Ok(file_errors.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()))
     }
 
     /// Finalize the runtime by atomically renaming from temp to final
@@ -483,6 +1047,14 @@ impl RuntimeBuilder {
         let runtimes_dir = self.settings.data_root.join("runtimes");
         let final_dir = runtimes_dir.join(format!("{}-latest", profile_name));
 
+        // Carry the outgoing plan over as the previous plan, so the new build can be diffed against it
+        let outgoing_plan = final_dir.join(PLAN_FILE);
+        if outgoing_plan.exists() {
+            if let Err(e) = fs::rename(&outgoing_plan, temp_dir.join(PREVIOUS_PLAN_FILE)) {
+                warn!("Failed to keep previous runtime plan {}: {}", outgoing_plan.display(), e);
+            }
+        }
+
         // Remove existing runtime if it exists
         if final_dir.exists() {
             fs::remove_dir_all(&final_dir)
@@ -497,6 +1069,295 @@ impl RuntimeBuilder {
         Ok(final_dir)
     }
 
+    /// Check that a runtime about to be finalized agrees with its plan: the file count matches (less files that
+    /// failed to link) and a sample of entries is present with the planned size. Catches silently partial builds
+    fn verify_runtime_matches_plan(&self, runtime_dir: &Path, plan: &RuntimePlan, file_errors: &[BuildFileError]) -> Result<()> {
+        let failed: std::collections::HashSet<&str> = file_errors.iter().map(|error| error.rel_path.as_str()).collect();
+        let expected_files = plan.total_files.saturating_sub(failed.len());
+
+        let runtime_files = walkdir::WalkDir::new(runtime_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .count();
+        if runtime_files != expected_files {
+            return Err(anyhow!(
+                "The runtime has {} files but its plan expects {}; the build is incomplete",
+                runtime_files,
+                expected_files
+            ));
+        }
+
+        let step = (plan.entries.len() / PLAN_SAMPLE_SIZE).max(1);
+        for entry in plan.entries.iter().step_by(step).filter(|entry| !failed.contains(entry.rel_path.as_str())) {
+            let runtime_file = safe_join(runtime_dir, &entry.rel_path)?;
+            match fs::metadata(&runtime_file) {
+                Ok(metadata) if metadata.len() == entry.size => {}
+                Ok(metadata) => return Err(anyhow!(
+                    "Runtime file {} is {} bytes but its plan expects {}",
+                    entry.rel_path,
+                    metadata.len(),
+                    entry.size
+                )),
+                Err(e) => return Err(anyhow!("Runtime file {} from the plan is missing: {}", entry.rel_path, e)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the profile's latest runtime exists and contains an intact game executable
+    /// The executable is compared (size, then hash) against the source the saved plan used for it, or the base install
+    pub fn verify_launchable(&self, profile_name: &str) -> Result<LaunchCheck> {
+        let runtime_path = self.settings.data_root.join("runtimes").join(format!("{}-latest", profile_name));
+        let mut check = LaunchCheck {
+            runtime_path: runtime_path.clone(),
+            runtime_exists: runtime_path.is_dir(),
+            ..LaunchCheck::default()
+        };
+
+        if !check.runtime_exists {
+            check.problems.push("The runtime has not been built yet".to_string());
+            return Ok(check);
+        }
+
+        let exe_path = runtime_path.join(Settings::GAME_EXECUTABLE);
+        check.exe_present = exe_path.is_file();
+        if !check.exe_present {
+            check.problems.push(format!("The runtime is missing the game executable ({})", Settings::GAME_EXECUTABLE));
+            return Ok(check);
+        }
+
+        // Where the executable should have come from: an override recorded in the plan, or the base install
+        let planned_source = self.planner.load_plan(profile_name)?
+            .and_then(|plan| plan.entries.into_iter().find(|entry| entry.rel_path == Settings::GAME_EXECUTABLE))
+            .map(|entry| entry.source);
+        let expected_path = match planned_source {
+            Some(RuntimeSource::Blob(hash_str)) => self.blob_cache.get_blob_path_from_hash(&hash_str)?,
+            Some(RuntimeSource::Workspace) => ProfileManager::new(self.settings.data_root.join("profiles"))
+                .get_profile(profile_name)?
+                .ok_or_else(|| anyhow!("Profile '{}' not found", profile_name))?
+                .workspace_dir
+                .join(Settings::GAME_EXECUTABLE),
+            Some(RuntimeSource::Shared) => self.settings.get_shared_workspace_directory().join(Settings::GAME_EXECUTABLE),
+            Some(RuntimeSource::Base) | None => self.planner.base_path_for_profile(profile_name)?.join(Settings::GAME_EXECUTABLE),
+        };
+
+        if expected_path.is_file() {
+            let matches = fs::metadata(&exe_path)?.len() == fs::metadata(&expected_path)?.len()
+                && BlobCache::hash_file(&exe_path)? == BlobCache::hash_file(&expected_path)?;
+            check.exe_matches = Some(matches);
+            if !matches {
+                check.problems.push(format!(
+                    "The runtime's game executable doesn't match its source ({}); rebuild the runtime",
+                    expected_path.display()
+                ));
+            }
+        } else {
+            warn!("Can't verify game executable, expected source is missing: {}", expected_path.display());
+        }
+
+        check.ready = check.problems.is_empty();
+        Ok(check)
+    }
+
+    /// Check that every runtime file the saved plan sources from the cache is still hardlinked to its blob
+    /// Files with a copy extension are copied on purpose and aren't checked
+    pub fn verify_runtime_links(&self, profile_name: &str) -> Result<Vec<LinkIssue>> {
+        let runtime_path = self.settings.data_root.join("runtimes").join(format!("{}-latest", profile_name));
+        if !runtime_path.is_dir() {
+            return Err(anyhow!("Profile '{}' has no built runtime to verify; build it first", profile_name));
+        }
+        let saved_plan = self.planner.load_plan(profile_name)?
+            .ok_or_else(|| anyhow!("Profile '{}' has no saved runtime plan; rebuild it first", profile_name))?;
+
+        let mut issues = Vec::new();
+        for entry in saved_plan.entries {
+            let RuntimeSource::Blob(hash_str) = &entry.source else {
+                continue;
+            };
+            if self.is_copy_extension(&entry.rel_path) {
+                continue;
+            }
+
+            let blob_path = self.blob_cache.get_blob_path_from_hash(hash_str)?;
+            let runtime_file = safe_join(&runtime_path, &entry.rel_path)?;
+            let problem = if !blob_path.is_file() {
+                LinkProblem::BlobMissing
+            } else if !runtime_file.is_file() {
+                LinkProblem::Missing
+            } else if !are_files_hardlinked(&blob_path, &runtime_file) {
+                LinkProblem::Delinked
+            } else {
+                continue;
+            };
+            issues.push(LinkIssue {
+                rel_path: entry.rel_path,
+                hash: hash_str.clone(),
+                problem,
+            });
+        }
+
+        if !issues.is_empty() {
+            warn!("{} runtime files of profile {} are no longer linked to the cache", issues.len(), profile_name);
+        }
+        Ok(issues)
+    }
+
+    /// Relink runtime files that lost their hardlink to the cache, returning the number relinked
+    /// Files whose blob is missing can't be relinked and need a rebuild
+    pub fn repair_runtime_links(&self, profile_name: &str) -> Result<usize> {
+        let runtime_path = self.settings.data_root.join("runtimes").join(format!("{}-latest", profile_name));
+        let mut relinked = 0;
+        for issue in self.verify_runtime_links(profile_name)? {
+            if issue.problem == LinkProblem::BlobMissing {
+                warn!("Can't relink {}, its blob is missing from the cache", issue.rel_path);
+                continue;
+            }
+            let blob = BlobPath {
+                hash: Hash::from_hex(&issue.hash).map_err(|e| anyhow!("Invalid hash: {}", e))?,
+                path: self.blob_cache.get_blob_path_from_hash(&issue.hash)?,
+            };
+            self.blob_cache.link_blob_to(safe_join(&runtime_path, &issue.rel_path)?, &blob)
+                .with_context(|| format!("Failed to relink: {}", issue.rel_path))?;
+            relinked += 1;
+        }
+
+        info!("Relinked {} runtime files of profile {}", relinked, profile_name);
+        Ok(relinked)
+    }
+
+    /// Relink a single file into the profile's existing runtime and update its saved plan entry
+    /// The fast path after editing one file: nothing else in the runtime is touched or diffed.
+    /// Workspace files must be normalized first, so the runtime links the blob the edit produced
+    pub fn apply_file_to_runtime(&self, profile_name: &str, rel_path: &str) -> Result<FileApplyResult> {
+        let rel_path = rel_path.replace('\\', "/").trim_start_matches('/').to_string();
+        info!("Applying {} to the runtime of profile: {}", rel_path, profile_name);
+
+        let runtime_path = self.settings.data_root.join("runtimes").join(format!("{}-latest", profile_name));
+        if !runtime_path.join(Settings::GAME_EXECUTABLE).is_file() {
+            return Err(anyhow!("Profile '{}' has no built runtime to update; build it first", profile_name));
+        }
+        let saved_plan = self.planner.load_plan(profile_name)?
+            .ok_or_else(|| anyhow!("Profile '{}' has no saved runtime plan; rebuild it first", profile_name))?;
+        let previous_source = saved_plan.entries.into_iter()
+            .find(|entry| entry.rel_path == rel_path)
+            .map(|entry| entry.source);
+
+        let entry = self.planner.plan_entry(profile_name, &rel_path)?;
+        let dest_path = safe_join(&runtime_path, &rel_path)?;
+        match &entry {
+            Some(entry) => self.place_single_entry(profile_name, entry, &dest_path)?,
+            None => {
+                if dest_path.is_file() {
+                    fs::remove_file(&dest_path)
+                        .with_context(|| format!("Failed to remove runtime file: {}", dest_path.display()))?;
+                }
+            }
+        }
+
+        let source = entry.as_ref().map(|entry| entry.source.clone());
+        self.planner.update_plan_entry(profile_name, &rel_path, entry)?;
+        info!("Applied {} to runtime: {:?} -> {:?}", rel_path, previous_source, source);
+
+        Ok(FileApplyResult {
+            rel_path,
+            previous_source,
+            source,
+        })
+    }
+
+    /// Replace one runtime file with its planned source, through a temp file and rename so the old file stays until the new one is ready
+    fn place_single_entry(&self, profile_name: &str, entry: &RuntimePlanEntry, dest_path: &Path) -> Result<()> {
+        let copied = self.is_copy_extension(&entry.rel_path);
+        let source_path = match &entry.source {
+            RuntimeSource::Blob(hash_str) => {
+                let hash = Hash::from_hex(hash_str).map_err(|e| anyhow!("Invalid hash: {}", e))?;
+                let blob = BlobPath {
+                    hash,
+                    path: self.blob_cache.get_blob_path_from_hash(hash_str)?,
+                };
+                if !blob.path.is_file() {
+                    return Err(anyhow!("Blob for {} is missing from the cache", entry.rel_path));
+                }
+
+                // The index must describe the file as it is now, or the runtime would get stale content
+                let workspace_file = self.planner.workspace_path_for_profile(profile_name)?.join(&entry.rel_path);
+                if BlobCache::hash_file(&workspace_file)? != hash {
+                    return Err(anyhow!("{} has changed since it was normalized; wait for the watcher or normalize the workspace first", entry.rel_path));
+                }
+
+                if copied {
+                    self.blob_cache.copy_blob_to(dest_path, &blob)
+                } else {
+                    self.blob_cache.link_blob_to(dest_path, &blob)
+                }
+                .with_context(|| format!("Failed to place blob for: {}", entry.rel_path))?;
+                return Ok(());
+            }
+            RuntimeSource::Base => self.planner.base_path_for_profile(profile_name)?.join(&entry.rel_path),
+            RuntimeSource::Shared => self.settings.get_shared_workspace_directory().join(&entry.rel_path),
+            RuntimeSource::Workspace => self.planner.workspace_path_for_profile(profile_name)?.join(&entry.rel_path),
+        };
+
+        // Protected workspace files are always copied, like in a full build
+        let copied = copied || entry.source == RuntimeSource::Workspace;
+        let parent = dest_path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        let temp_path = parent.join(format!(".tmp_{}", uuid::Uuid::new_v4()));
+        let placed = if copied {
+            fs::copy(&source_path, &temp_path).map(|_| ())
+        } else {
+            fs::hard_link(&source_path, &temp_path)
+        };
+        if let Err(e) = placed.and_then(|_| fs::rename(&temp_path, dest_path)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(anyhow!("Failed to place {} into the runtime: {}", entry.rel_path, e));
+        }
+        Ok(())
+    }
+
+    /// Load the stats of recent successful builds, oldest first
+    pub fn load_build_history(&self) -> Result<Vec<BuildStats>> {
+        let history_file = self.settings.data_root.join("runtimes").join(BUILD_HISTORY_FILE);
+        if !history_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&history_file)
+            .with_context(|| format!("Failed to read build history: {}", history_file.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse build history: {}", history_file.display()))
+    }
+
+    /// Append a build's stats to the history, keeping only the most recent builds
+    fn record_build_stats(&self, stats: &BuildStats) -> Result<()> {
+        let mut history = self.load_build_history().unwrap_or_default();
+        history.push(stats.clone());
+        if history.len() > BUILD_HISTORY_LIMIT {
+            history.drain(..history.len() - BUILD_HISTORY_LIMIT);
+        }
+
+        let history_file = self.settings.data_root.join("runtimes").join(BUILD_HISTORY_FILE);
+        let content = serde_json::to_string_pretty(&history)
+            .context("Failed to serialize build history")?;
+        fs::write(&history_file, content)
+            .with_context(|| format!("Failed to write build history: {}", history_file.display()))
+    }
+
+    /// Estimate how long building a profile would take, from its computed plan and the build history
+    /// Only the plan is computed; nothing is linked
+    pub fn estimate_build_time(&self, profile_name: &str) -> Result<BuildEstimate> {
+        let plan = self.planner.compute_plan(profile_name)?;
+        let history = self.load_build_history().unwrap_or_else(|e| {
+            warn!("Ignoring unreadable build history: {}", e);
+            Vec::new()
+        });
+
+        Ok(BuildEstimate::from_history(&history, plan.total_files, plan.total_size))
+    }
+
     /// Clean up old temporary runtime directories
     pub fn cleanup_temp_runtimes(&self) -> Result<()> {
         let runtimes_dir = self.settings.data_root.join("runtimes");
@@ -525,4 +1386,679 @@ impl RuntimeBuilder {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// List `{profile}-latest` runtime directories whose profile no longer exists in `profiles_root`
+    pub fn find_orphaned_runtimes(&self, profiles_root: &Path) -> Result<Vec<PathBuf>> {
+        let runtimes_dir = self.settings.data_root.join("runtimes");
+        if !runtimes_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let manager = ProfileManager::new(profiles_root.to_path_buf());
+        let mut orphaned = Vec::new();
+        for entry in fs::read_dir(&runtimes_dir).context("Failed to read runtimes directory")? {
+            let path = entry.context("Failed to read directory entry")?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(profile_name) = path.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_suffix("-latest")) else {
+                continue;
+            };
+            if manager.get_profile(profile_name)?.is_none() {
+                orphaned.push(path);
+            }
+        }
+
+        orphaned.sort();
+        Ok(orphaned)
+    }
+
+    /// Remove runtime directories left behind by deleted profiles
+    /// Unlike `cleanup_temp_runtimes` this only touches finished runtimes without a profile
+    pub fn clean_orphaned_runtimes(&self, profiles_root: &Path) -> Result<OrphanedRuntimeCleanup> {
+        let mut cleanup = OrphanedRuntimeCleanup::default();
+
+        for path in self.find_orphaned_runtimes(profiles_root)? {
+            let (entries, bytes) = walkdir::WalkDir::new(&path)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .fold((0usize, 0u64), |(entries, bytes), entry| {
+                    let size = if entry.file_type().is_file() {
+                        entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    (entries + 1, bytes + size)
+                });
+
+            info!("Removing orphaned runtime: {}", path.display());
+            if let Err(e) = fs::remove_dir_all(&path) {
+                warn!("Failed to remove orphaned runtime {}: {}", path.display(), e);
+                continue;
+            }
+            cleanup.removed.push(path.to_string_lossy().to_string());
+            cleanup.entries_removed += entries;
+            cleanup.bytes_reclaimed += bytes;
+        }
+
+        Ok(cleanup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_link_failures_classify_by_os_error() {
+        let cross_device_code = if cfg!(windows) { 17 } else { 18 };
+        let link_error = |os_error: io::Error| -> anyhow::Error {
+            let error = crate::blob_cache::hardlink_error(Path::new("cache/blob"), Path::new("runtime/.tmp"), os_error);
+            anyhow::Error::from(error).context("Failed to link file: gta3.img")
+        };
+
+        let cross_volume = link_error(io::Error::from_raw_os_error(cross_device_code));
+        assert_eq!(BuildErrorKind::classify(&cross_volume), BuildErrorKind::CrossVolume);
+        assert!(cross_volume.chain().any(|cause| cause.to_string().contains("same NTFS volume")));
+
+        let missing = link_error(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(BuildErrorKind::classify(&missing), BuildErrorKind::MissingFile);
+    }
+
+    #[test]
+    fn test_build_phase_steps_are_monotonic_and_bounded() {
+        let steps: Vec<usize> = BuildPhase::ORDERED.iter().map(|phase| phase.step()).collect();
+
+        assert_eq!(steps.first(), Some(&1));
+        assert_eq!(steps.last(), Some(&BuildPhase::total()));
+        assert!(steps.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+        assert_eq!(BuildPhase::Complete.step(), BuildPhase::total());
+        assert_eq!(BuildPhase::Failed.step(), BuildPhase::total());
+    }
+
+    fn plan_entry(rel_path: &str) -> RuntimePlanEntry {
+        RuntimePlanEntry {
+            rel_path: rel_path.to_string(),
+            source: RuntimeSource::Base,
+            size: 0,
+            has_base: true,
+            is_override: false,
+        }
+    }
+
+    #[test]
+    fn test_file_errors_fail_fast_or_collect() {
+        let entries = [plan_entry("gta_sa.exe"), plan_entry("data/locked.dat"), plan_entry("data/gta.dat")];
+        let entry_refs: Vec<_> = entries.iter().collect();
+        let operation = |entry: &RuntimePlanEntry| -> Result<()> {
+            if entry.rel_path.contains("locked") {
+                Err(anyhow!("file is locked"))
+            } else {
+                Ok(())
+            }
+        };
+
+        // Strict mode aborts on the first failure
+        let strict = RuntimeBuilder::new(Settings::new());
+        assert!(strict.for_each_entry(&entry_refs, operation).is_err());
+
+        // Tolerant mode keeps going and reports what failed
+        let tolerant = RuntimeBuilder::new(Settings::new())
+            .with_options(BuildOptions { fail_fast: false, ..BuildOptions::default() });
+        let file_errors = tolerant.for_each_entry(&entry_refs, operation).unwrap();
+        assert_eq!(file_errors.len(), 1);
+        assert_eq!(file_errors[0].rel_path, "data/locked.dat");
+        assert!(file_errors[0].error.contains("locked"));
+    }
+
+    #[test]
+    fn test_failure_captures_phase_file_and_kind() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let entries = [plan_entry("gta_sa.exe"), plan_entry("data/handling.cfg")];
+        let entry_refs: Vec<_> = entries.iter().collect();
+        let operation = |entry: &RuntimePlanEntry| -> Result<()> {
+            if entry.rel_path == "data/handling.cfg" {
+                fs::hard_link(temp_dir.path().join("missing.cfg"), temp_dir.path().join("handling.cfg"))
+                    .context("Failed to create hardlink")?;
+            }
+            Ok(())
+        };
+
+        let builder = RuntimeBuilder::new(Settings::new());
+        let error = builder.for_each_entry(&entry_refs, operation).unwrap_err();
+        let failure = BuildFailure::from_error(BuildPhase::OverlayWorkspace, &error);
+
+        assert_eq!(failure.phase, BuildPhase::OverlayWorkspace);
+        assert_eq!(failure.rel_path.as_deref(), Some("data/handling.cfg"));
+        assert_eq!(failure.kind, BuildErrorKind::MissingFile);
+        assert!(failure.to_string().starts_with("Failed during OverlayWorkspace on data/handling.cfg: Failed to create hardlink"));
+    }
+
+    #[test]
+    fn test_failed_build_result_reports_phase() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        let settings = Settings::for_wizard(temp_dir.path().join("missing-base"), data_root.clone());
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let result = RuntimeBuilder::new(settings).build_runtime("test", None).unwrap();
+
+        assert!(!result.success);
+        let failure = result.failure.unwrap();
+        assert_eq!(failure.phase, BuildPhase::Preflight);
+        assert!(failure.rel_path.is_none());
+        assert!(result.error.unwrap().starts_with("Failed during Preflight"));
+    }
+
+    #[test]
+    fn test_verify_launchable() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let builder = RuntimeBuilder::new(Settings::for_wizard(base_dir, data_root.clone()));
+
+        let check = builder.verify_launchable("test").unwrap();
+        assert!(!check.ready && !check.runtime_exists);
+
+        // A runtime missing its executable
+        let runtime = data_root.join("runtimes").join("test-latest");
+        fs::create_dir_all(runtime.join("data")).unwrap();
+        let check = builder.verify_launchable("test").unwrap();
+        assert!(!check.ready && check.runtime_exists && !check.exe_present);
+        assert_eq!(check.problems.len(), 1);
+
+        // A botched executable
+        fs::write(runtime.join(Settings::GAME_EXECUTABLE), "gamf").unwrap();
+        let check = builder.verify_launchable("test").unwrap();
+        assert!(!check.ready && check.exe_present);
+        assert_eq!(check.exe_matches, Some(false));
+
+        fs::write(runtime.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let check = builder.verify_launchable("test").unwrap();
+        assert!(check.ready && check.problems.is_empty());
+        assert_eq!(check.exe_matches, Some(true));
+    }
+
+    #[test]
+    fn test_rebuild_keeps_previous_plan_for_diffing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let builder = RuntimeBuilder::new(settings.clone());
+        assert!(builder.build_runtime("test", None).unwrap().success);
+        let planner = RuntimePlanner::new(settings.clone());
+        assert!(planner.load_previous_plan("test").unwrap().is_none());
+        assert!(planner.diff_against_previous("test").unwrap().is_none());
+
+        // Add a mod and build again
+        let mod_file = profile.workspace_dir.join("mod.asi");
+        fs::write(&mod_file, "mod").unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(&mod_file).unwrap();
+        cache.add_ref(&blob, "test", "mod.asi").unwrap();
+        assert!(builder.build_runtime("test", None).unwrap().success);
+
+        let previous = planner.load_previous_plan("test").unwrap().unwrap();
+        assert_eq!(previous.total_files, 1);
+        let diff = planner.diff_against_previous("test").unwrap().unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].rel_path, "mod.asi");
+        assert!(diff.removed.is_empty() && diff.changed.is_empty());
+
+        // Both builds were recorded for estimates
+        assert_eq!(builder.load_build_history().unwrap().len(), 2);
+        let estimate = builder.estimate_build_time("test").unwrap();
+        assert_eq!(estimate.total_files, 2);
+    }
+
+    #[test]
+    fn test_runtime_missing_planned_files_fails_the_build() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "dat").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let builder = RuntimeBuilder::new(settings);
+        assert!(builder.build_runtime("test", None).unwrap().success);
+        let previous_plan = builder.planner.load_plan("test").unwrap().unwrap();
+
+        // A file disappears from the temp runtime of the next build right before it's finalized
+        let runtimes_dir = data_root.join("runtimes");
+        let sabotage: ProgressCallback = Arc::new(move |progress: BuildProgress| {
+            if progress.phase != BuildPhase::Finalize {
+                return;
+            }
+            for entry in fs::read_dir(&runtimes_dir).unwrap().flatten() {
+                if entry.file_name().to_string_lossy().ends_with("-tmp") {
+                    let _ = fs::remove_file(entry.path().join("data/gta.dat"));
+                }
+            }
+        });
+
+        let result = builder.build_runtime("test", Some(sabotage)).unwrap();
+        assert!(!result.success);
+        let failure = result.failure.unwrap();
+        assert_eq!(failure.phase, BuildPhase::Finalize);
+        assert!(failure.message.contains("incomplete"), "{}", failure.message);
+
+        // The mismatched runtime never replaced the previous one, which keeps its plan
+        let latest = data_root.join("runtimes/test-latest");
+        assert!(latest.join("data/gta.dat").exists());
+        assert_eq!(builder.planner.load_plan("test").unwrap().unwrap().generated_at, previous_plan.generated_at);
+        assert!(!fs::read_dir(data_root.join("runtimes")).unwrap().flatten().any(|entry| entry.file_name().to_string_lossy().ends_with("-tmp")));
+        assert!(builder.build_runtime("test", None).unwrap().success);
+    }
+
+    #[test]
+    fn test_orphaned_runtimes_are_found_and_removed() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        let profiles_root = data_root.join("profiles");
+        let settings = Settings::for_wizard(temp_dir.path().join("base"), data_root.clone());
+        ProfileManager::new(profiles_root.clone())
+            .create_profile("kept".to_string())
+            .unwrap();
+
+        let runtimes_dir = data_root.join("runtimes");
+        fs::create_dir_all(runtimes_dir.join("kept-latest")).unwrap();
+        fs::create_dir_all(runtimes_dir.join("gone-latest/data")).unwrap();
+        fs::write(runtimes_dir.join("gone-latest/data/gta.dat"), "dat").unwrap();
+        fs::create_dir_all(runtimes_dir.join("gone-123-tmp")).unwrap();
+
+        let builder = RuntimeBuilder::new(settings);
+        let orphaned = builder.find_orphaned_runtimes(&profiles_root).unwrap();
+        assert_eq!(orphaned, vec![runtimes_dir.join("gone-latest")]);
+
+        let cleanup = builder.clean_orphaned_runtimes(&profiles_root).unwrap();
+        assert_eq!(cleanup.removed.len(), 1);
+        assert_eq!(cleanup.entries_removed, 2);
+        assert_eq!(cleanup.bytes_reclaimed, 3);
+        assert!(!runtimes_dir.join("gone-latest").exists());
+
+        // The live profile's runtime and temp dirs are left alone
+        assert!(runtimes_dir.join("kept-latest").exists());
+        assert!(runtimes_dir.join("gone-123-tmp").exists());
+        assert!(builder.find_orphaned_runtimes(&profiles_root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_skip_if_fresh_and_cancel() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let builder = RuntimeBuilder::new(settings.clone())
+            .with_options(BuildOptions { skip_if_fresh: true, ..BuildOptions::default() });
+        let first = builder.build_runtime("test", None).unwrap();
+        assert!(first.success && !first.up_to_date);
+
+        // Nothing changed, so the second build is a no-op
+        let second = builder.build_runtime("test", None).unwrap();
+        assert!(second.success && second.up_to_date);
+        assert_eq!(builder.load_build_history().unwrap().len(), 1);
+
+        // A new base file makes the runtime stale again
+        fs::write(base_dir.join("gta3.img"), "img").unwrap();
+        let cancel_flag = Arc::new(AtomicBool::new(true));
+        let cancelled = RuntimeBuilder::new(settings.clone())
+            .with_options(BuildOptions { skip_if_fresh: true, ..BuildOptions::default() })
+            .with_cancel_flag(cancel_flag.clone())
+            .build_runtime("test", None)
+            .unwrap();
+        assert!(!cancelled.success);
+        assert_eq!(cancelled.failure.unwrap().kind, BuildErrorKind::Cancelled);
+        assert!(!data_root.join("runtimes").join("test-latest").join("gta3.img").exists());
+
+        cancel_flag.store(false, Ordering::SeqCst);
+        let rebuilt = builder.build_runtime("test", None).unwrap();
+        assert!(rebuilt.success && !rebuilt.up_to_date);
+        assert!(data_root.join("runtimes").join("test-latest").join("gta3.img").exists());
+
+        // Patching a base file in place keeps the plan identical, but still forces a rebuild
+        fs::write(base_dir.join("gta3.img"), "IMG").unwrap();
+        let patched = builder.build_runtime("test", None).unwrap();
+        assert!(patched.success && !patched.up_to_date && patched.base_changed);
+        assert!(builder.build_runtime("test", None).unwrap().up_to_date);
+    }
+
+    #[test]
+    fn test_base_changes_are_tracked_per_profile() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        // Data root inside the base, so every build writes into the tree being fingerprinted
+        let data_root = base_dir.join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("gta3.img"), "img").unwrap();
+        let settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profiles = ProfileManager::new(data_root.join("profiles"));
+        for name in ["one", "two"] {
+            profiles.create_profile(name.to_string()).unwrap();
+        }
+
+        let builder = RuntimeBuilder::new(settings)
+            .with_options(BuildOptions { skip_if_fresh: true, ..BuildOptions::default() });
+        for name in ["one", "two"] {
+            assert!(builder.build_runtime(name, None).unwrap().success);
+            let again = builder.build_runtime(name, None).unwrap();
+            assert!(again.up_to_date && !again.base_changed);
+        }
+
+        // Rebuilding one profile after a patch doesn't make the other's runtime look current
+        fs::write(base_dir.join("gta3.img"), "IMG").unwrap();
+        assert!(builder.build_runtime("one", None).unwrap().base_changed);
+        let two = builder.build_runtime("two", None).unwrap();
+        assert!(two.success && !two.up_to_date && two.base_changed);
+        assert!(builder.build_runtime("two", None).unwrap().up_to_date);
+    }
+
+    #[test]
+    fn test_parallel_progress_never_goes_backwards() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("models")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        for i in 0..2000 {
+            fs::write(base_dir.join("models").join(format!("model{}.dff", i)), "model").unwrap();
+        }
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let callback: ProgressCallback = Arc::new(move |progress: BuildProgress| {
+            recorded.lock().unwrap().push(progress);
+        });
+        assert!(RuntimeBuilder::new(settings).build_runtime("test", Some(callback)).unwrap().success);
+
+        // Planning reports files planned; counting linked files starts with the LinkBase phase
+        let events = events.lock().unwrap();
+        let linking: Vec<_> = events.iter()
+            .skip_while(|progress| progress.phase != BuildPhase::LinkBase)
+            .collect();
+        assert!(linking.iter().filter(|progress| progress.current_file.is_some()).count() >= 10);
+        for pair in linking.windows(2) {
+            assert!(pair[1].files_processed >= pair[0].files_processed);
+            assert!(pair[1].bytes_processed >= pair[0].bytes_processed);
+        }
+        assert_eq!(linking.last().unwrap().files_processed, 2001);
+    }
+
+    #[test]
+    fn test_copy_extensions_are_copied_not_linked() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("models")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("models/gta3.img"), "archive").unwrap();
+        let mut settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        settings.preferences.copy_extensions = vec![".IMG".to_string()];
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        // One workspace mod with a copy extension, one without
+        let cache = BlobCache::new(settings.get_cache_directory());
+        for rel_path in ["mods/cars.img", "mods/cars.asi"] {
+            let mod_file = profile.workspace_dir.join(rel_path);
+            fs::create_dir_all(mod_file.parent().unwrap()).unwrap();
+            fs::write(&mod_file, rel_path).unwrap();
+            let blob = cache.ensure_blob(&mod_file).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+
+        let result = RuntimeBuilder::new(settings).build_runtime("test", None).unwrap();
+        assert!(result.success);
+        let stats = result.stats.unwrap();
+        assert_eq!((stats.files_copied, stats.files_linked), (2, 2));
+
+        let runtime_dir = result.runtime_path.unwrap();
+        assert!(!are_files_hardlinked(&base_dir.join("models/gta3.img"), &runtime_dir.join("models/gta3.img")));
+        assert!(are_files_hardlinked(&base_dir.join(Settings::GAME_EXECUTABLE), &runtime_dir.join(Settings::GAME_EXECUTABLE)));
+        let img_blob = cache.get_blob_path(&BlobCache::hash_file(profile.workspace_dir.join("mods/cars.img")).unwrap());
+        assert!(!are_files_hardlinked(&img_blob, &runtime_dir.join("mods/cars.img")));
+        let asi_blob = cache.get_blob_path(&BlobCache::hash_file(profile.workspace_dir.join("mods/cars.asi")).unwrap());
+        assert!(are_files_hardlinked(&asi_blob, &runtime_dir.join("mods/cars.asi")));
+        assert_eq!(fs::read_to_string(runtime_dir.join("mods/cars.img")).unwrap(), "mods/cars.img");
+    }
+
+    #[test]
+    fn test_delinked_runtime_files_are_detected_and_repaired() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        for rel_path in ["mods/cars.asi", "mods/bikes.asi"] {
+            let mod_file = profile.workspace_dir.join(rel_path);
+            fs::create_dir_all(mod_file.parent().unwrap()).unwrap();
+            fs::write(&mod_file, rel_path).unwrap();
+            let blob = cache.ensure_blob(&mod_file).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+
+        let builder = RuntimeBuilder::new(settings);
+        let runtime_dir = builder.build_runtime("test", None).unwrap().runtime_path.unwrap();
+        assert!(builder.verify_runtime_links("test").unwrap().is_empty());
+
+        // Replace a linked file with a copy of the same content, the way a backup restore would
+        let runtime_file = runtime_dir.join("mods/cars.asi");
+        fs::remove_file(&runtime_file).unwrap();
+        fs::write(&runtime_file, "mods/cars.asi").unwrap();
+
+        let issues = builder.verify_runtime_links("test").unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rel_path, "mods/cars.asi");
+        assert_eq!(issues[0].problem, LinkProblem::Delinked);
+
+        assert_eq!(builder.repair_runtime_links("test").unwrap(), 1);
+        assert!(builder.verify_runtime_links("test").unwrap().is_empty());
+        let blob = cache.get_blob_path_from_hash(&issues[0].hash).unwrap();
+        assert!(are_files_hardlinked(&blob, &runtime_file));
+    }
+
+    #[test]
+    fn test_apply_single_file_to_runtime() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        let settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let normalize = |rel_path: &str, content: &str| {
+            let file = profile.workspace_dir.join(rel_path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            let _ = fs::remove_file(&file);
+            fs::write(&file, content).unwrap();
+            let blob = cache.ensure_blob(&file).unwrap();
+            cache.remove_existing_ref("test", rel_path).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+            blob
+        };
+        normalize("data/handling.cfg", "tuned");
+        normalize("mod.asi", "plugin");
+
+        let builder = RuntimeBuilder::new(settings);
+        // Nothing to update before the runtime is built
+        assert!(builder.apply_file_to_runtime("test", "data/handling.cfg").is_err());
+        let runtime_dir = builder.build_runtime("test", None).unwrap().runtime_path.unwrap();
+
+        let retuned = normalize("data/handling.cfg", "retuned");
+        let applied = builder.apply_file_to_runtime("test", "data\\handling.cfg").unwrap();
+        assert_eq!(applied.source, Some(RuntimeSource::Blob(retuned.hash.to_hex().to_string())));
+        assert_ne!(applied.previous_source, applied.source);
+
+        // Only the edited file changed in the runtime and the saved plan
+        assert_eq!(fs::read_to_string(runtime_dir.join("data/handling.cfg")).unwrap(), "retuned");
+        assert!(are_files_hardlinked(&retuned.path, &runtime_dir.join("data/handling.cfg")));
+        assert!(are_files_hardlinked(&base_dir.join(Settings::GAME_EXECUTABLE), &runtime_dir.join(Settings::GAME_EXECUTABLE)));
+        let diff = builder.planner.diff_against_previous("test").unwrap().unwrap();
+        assert_eq!((diff.added.len(), diff.removed.len(), diff.changed.len()), (0, 0, 1));
+        assert!(builder.is_runtime_fresh(&builder.planner.compute_plan("test").unwrap()).unwrap());
+
+        // An edit the watcher hasn't normalized yet is refused
+        fs::remove_file(profile.workspace_dir.join("mod.asi")).unwrap();
+        fs::write(profile.workspace_dir.join("mod.asi"), "unsaved").unwrap();
+        assert!(builder.apply_file_to_runtime("test", "mod.asi").is_err());
+        assert_eq!(fs::read_to_string(runtime_dir.join("mod.asi")).unwrap(), "plugin");
+
+        // Deleting the override reveals the base file again
+        fs::remove_file(profile.workspace_dir.join("data/handling.cfg")).unwrap();
+        cache.remove_existing_ref("test", "data/handling.cfg").unwrap();
+        let reverted = builder.apply_file_to_runtime("test", "data/handling.cfg").unwrap();
+        assert_eq!(reverted.source, Some(RuntimeSource::Base));
+        assert!(are_files_hardlinked(&base_dir.join("data/handling.cfg"), &runtime_dir.join("data/handling.cfg")));
+    }
+
+    #[test]
+    fn test_shared_workspace_files_are_linked_into_runtime() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let shared_dir = settings.get_shared_workspace_directory();
+        fs::create_dir_all(shared_dir.join("data")).unwrap();
+        fs::write(shared_dir.join("data/handling.cfg"), "shared").unwrap();
+        fs::write(shared_dir.join("common.asi"), "plugin").unwrap();
+
+        let result = RuntimeBuilder::new(settings).build_runtime("test", None).unwrap();
+        assert!(result.success);
+        let runtime_dir = result.runtime_path.unwrap();
+        assert_eq!(fs::read_to_string(runtime_dir.join("data/handling.cfg")).unwrap(), "shared");
+        assert!(are_files_hardlinked(&shared_dir.join("common.asi"), &runtime_dir.join("common.asi")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_from_read_only_base() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "dat").unwrap();
+        let settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        // Remove write permission from everything in the base
+        let set_mode = |file_mode: u32, dir_mode: u32| {
+            for path in [base_dir.join(Settings::GAME_EXECUTABLE), base_dir.join("data/gta.dat")] {
+                fs::set_permissions(&path, fs::Permissions::from_mode(file_mode)).unwrap();
+            }
+            for dir in [base_dir.join("data"), base_dir.clone()] {
+                fs::set_permissions(&dir, fs::Permissions::from_mode(dir_mode)).unwrap();
+            }
+        };
+        set_mode(0o444, 0o555);
+
+        let result = RuntimeBuilder::new(settings.clone()).build_runtime("test", None).unwrap();
+        let runtime_dir = data_root.join("runtimes").join("test-latest");
+        let built = result.success && fs::read_to_string(runtime_dir.join("data/gta.dat")).ok().as_deref() == Some("dat");
+
+        // Rebuilding replaces the previous runtime, whose files are hardlinks to read-only base files
+        let rebuilt = RuntimeBuilder::new(settings).build_runtime("test", None).unwrap().success;
+        set_mode(0o644, 0o755);
+        assert!(built);
+        assert!(rebuilt);
+    }
+
+    #[test]
+    fn test_build_estimate_from_history() {
+        let stats = |total_files: usize, total_bytes: u64, build_time_ms: u64| BuildStats {
+            total_files,
+            base_files: total_files,
+            blob_files: 0,
+            total_bytes,
+            files_linked: total_files,
+            files_copied: 0,
+            build_time_ms,
+            files_per_second: 0.0,
+            mb_per_second: 0.0,
+        };
+
+        // No history falls back to the default rates
+        let estimate = BuildEstimate::from_history(&[], 400, 0);
+        assert_eq!(estimate.based_on_samples, 0);
+        assert_eq!(estimate.estimated_ms, 2000);
+
+        // 1000 files/sec and 100 MB/sec; the byte rate is the bottleneck here
+        let history = [stats(1000, 100 * 1024 * 1024, 1000), stats(3000, 300 * 1024 * 1024, 3000), stats(5, 5, 0)];
+        let estimate = BuildEstimate::from_history(&history, 2000, 500 * 1024 * 1024);
+        assert_eq!(estimate.based_on_samples, 2);
+        assert_eq!(estimate.estimated_ms, 5000);
+    }
+
+    #[test]
+    fn test_failed_progress_keeps_failing_step() {
+        let progress = BuildProgress::failed(BuildPhase::Planning, "boom".to_string());
+
+        assert_eq!(progress.phase, BuildPhase::Failed);
+        assert_eq!(progress.current_step, BuildPhase::Planning.step());
+        assert_eq!(progress.total_steps, BuildPhase::total());
+        assert_eq!(progress.error.as_deref(), Some("boom"));
+        assert!(progress.completed);
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result, anyhow};
+use tracing::{info, warn};
+
+use crate::blob_cache::BlobCache;
+use crate::path_utils::{compile_globs, matches_any_glob};
+use crate::profiles::ProfileManager;
+use crate::runtime_builder::RuntimeBuilder;
+use crate::settings::Settings;
+
+/// How often a running game is polled for exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Folder under the user's documents the game keeps its saves in
+pub const GAME_USER_FILES_DIR: &str = "GTA San Andreas User Files";
+
+/// Folder under the data root holding the user's own saves while a profile's saves are in the game's folder
+pub const ORIGINAL_SAVES_BACKUP_DIR: &str = "saves-backup";
+
+/// File in the backup folder marking that a profile's saves are in the game's folder and the user's aren't back yet
+const SAVES_IN_USE_MARKER: &str = "in-use";
+
+/// Save slot files the game writes into its user files folder; its settings and other files there are left alone
+const SAVE_FILE_GLOB: &str = "GTASAsf*.b";
+
+/// Options for launching a runtime
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LaunchOptions {
+    /// Extra command line arguments passed to the game
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Garbage collect unreferenced blobs once the game exits
+    #[serde(default)]
+    pub gc_on_exit: bool,
+    /// Folder the game reads and writes saves in (defaults to `GAME_USER_FILES_DIR` in the documents folder)
+    #[serde(default)]
+    pub game_saves_dir: Option<PathBuf>,
+}
+
+/// A game process started from a profile's runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchHandle {
+    /// Profile whose runtime is running
+    pub profile_name: String,
+    /// Process id of the game
+    pub pid: u32,
+    /// Runtime directory the game was started from
+    pub runtime_path: PathBuf,
+    /// When the game was started (RFC 3339)
+    pub started_at: String,
+}
+
+/// How a launched game ended, after the post-play hooks ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameExit {
+    /// Profile whose runtime was running
+    pub profile_name: String,
+    /// Exit code of the game (None if it was killed or the status couldn't be read)
+    pub exit_code: Option<i32>,
+    /// Whether the game was stopped with `terminate`
+    pub terminated: bool,
+    /// Blobs removed by the post-play garbage collection
+    pub blobs_collected: usize,
+    /// Save files copied back into the profile's saves directory (or its shared pool)
+    pub saves_copied: usize,
+}
+
+/// Callback run once a launched game has exited
+pub type ExitCallback = Box<dyn FnOnce(GameExit) + Send>;
+
+/// A tracked game process
+struct RunningGame {
+    child: Child,
+    handle: LaunchHandle,
+    terminated: bool,
+}
+
+/// Where a launched profile's saves were put for the game, so they can be copied back once it exits
+struct SavesLink {
+    profile_saves: PathBuf,
+    game_saves: PathBuf,
+    backup_dir: PathBuf,
+}
+
+impl SavesLink {
+    /// Put the user's own saves back in the game's folder, replacing the profile's
+    fn restore_original(&self) -> Result<usize> {
+        let restored = copy_save_files(&self.backup_dir, &self.game_saves)
+            .context("Failed to restore the original saves")?;
+        fs::remove_file(self.backup_dir.join(SAVES_IN_USE_MARKER))
+            .context("Failed to clear the saves in use marker")?;
+        Ok(restored)
+    }
+}
+
+/// Launches runtimes and tracks their game processes, at most one per profile
+/// Clones share the same set of running games
+#[derive(Clone, Default)]
+pub struct RuntimeLauncher {
+    running: Arc<Mutex<HashMap<String, RunningGame>>>,
+    /// Profiles with a runtime build in progress, which can't be launched
+    builds: Arc<Mutex<HashSet<String>>>,
+}
+
+impl RuntimeLauncher {
+    /// Create a launcher with no running games
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a profile as building, refusing if its game is running or another build holds it
+    /// Checked under the launcher's lock, so the game can't be launched between the check and the mark
+    pub fn begin_build(&self, profile_name: &str) -> Result<()> {
+        let mut running = self.lock()?;
+        if Self::still_running(&mut running, profile_name) {
+            return Err(anyhow!("Profile '{}' is running; stop the game first", profile_name));
+        }
+
+        let mut building = self.builds.lock().map_err(|e| anyhow!("Build lock error: {}", e))?;
+        if !building.insert(profile_name.to_string()) {
+            return Err(anyhow!("A build is already in progress for profile '{}'", profile_name));
+        }
+        Ok(())
+    }
+
+    /// Release a profile marked with `begin_build`
+    pub fn end_build(&self, profile_name: &str) {
+        if let Ok(mut building) = self.builds.lock() {
+            building.remove(profile_name);
+        }
+    }
+
+    /// Launch a profile's latest runtime, after checking it's ready to launch
+    /// A monitor thread waits for the game to exit, runs the post-play hooks, then calls `on_exit`
+    pub fn launch(
+        &self,
+        settings: &Settings,
+        profile_name: &str,
+        options: LaunchOptions,
+        on_exit: Option<ExitCallback>,
+    ) -> Result<LaunchHandle> {
+        // Held until the game is tracked, so the same profile can't be launched twice or start building meanwhile
+        let mut running = self.lock()?;
+        if Self::still_running(&mut running, profile_name) {
+            return Err(anyhow!("Profile '{}' is already running", profile_name));
+        }
+        let building = self.builds.lock().map_err(|e| anyhow!("Build lock error: {}", e))?.contains(profile_name);
+        if building {
+            return Err(anyhow!("A build is in progress for profile '{}'", profile_name));
+        }
+
+        let check = RuntimeBuilder::new(settings.clone()).verify_launchable(profile_name)?;
+        if !check.ready {
+            return Err(anyhow!("Runtime is not ready to launch: {}", check.problems.join("; ")));
+        }
+
+        // Every profile's game uses the same saves folder
+        let other_running: Vec<String> = running.keys().filter(|name| *name != profile_name).cloned().collect();
+        if let Some(other) = other_running.into_iter().find(|name| Self::still_running(&mut running, name)) {
+            return Err(anyhow!("Profile '{}' is running and using the game's saves folder; stop it first", other));
+        }
+        let saves = Self::install_saves(settings, profile_name, &options)?;
+
+        let exe_path = check.runtime_path.join(Settings::GAME_EXECUTABLE);
+        let child = Command::new(&exe_path)
+            .current_dir(&check.runtime_path)
+            .args(&options.args)
+            .spawn()
+            .inspect_err(|_| {
+                // The game never ran, so the user's own saves go straight back
+                if let Some(saves) = &saves {
+                    if let Err(e) = saves.restore_original() {
+                        warn!("Failed to restore saves after profile '{}' failed to start: {:#}", profile_name, e);
+                    }
+                }
+            })
+            .with_context(|| format!("Failed to start game: {}", exe_path.display()))?;
+
+        let handle = LaunchHandle {
+            profile_name: profile_name.to_string(),
+            pid: child.id(),
+            runtime_path: check.runtime_path,
+            started_at: chrono::Utc::now().to_rfc3339(),
+        };
+        info!("Launched runtime for profile '{}' (pid {})", profile_name, handle.pid);
+
+        running.insert(profile_name.to_string(), RunningGame {
+            child,
+            handle: handle.clone(),
+            terminated: false,
+        });
+        drop(running);
+
+        let launcher = self.clone();
+        let profile_name = profile_name.to_string();
+        let pid = handle.pid;
+        let cache = settings.blob_cache();
+        thread::spawn(move || launcher.monitor(profile_name, pid, options, cache, saves, on_exit));
+
+        Ok(handle)
+    }
+
+    /// Whether the profile's game is currently running
+    pub fn is_running(&self, profile_name: &str) -> bool {
+        match self.lock() {
+            Ok(mut running) => Self::still_running(&mut running, profile_name),
+            Err(_) => false,
+        }
+    }
+
+    /// Handles of all games currently running
+    pub fn running_games(&self) -> Vec<LaunchHandle> {
+        match self.lock() {
+            Ok(running) => running.values().map(|game| game.handle.clone()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Kill the profile's game; the monitor thread still runs the post-play hooks
+    /// Returns false if the profile wasn't running
+    pub fn terminate(&self, profile_name: &str) -> Result<bool> {
+        let mut running = self.lock()?;
+        let Some(game) = running.get_mut(profile_name) else {
+            return Ok(false);
+        };
+
+        game.terminated = true;
+        game.child.kill()
+            .with_context(|| format!("Failed to stop game for profile '{}'", profile_name))?;
+        info!("Terminated runtime for profile '{}' (pid {})", profile_name, game.handle.pid);
+        Ok(true)
+    }
+
+    /// Wait for a game to exit, stop tracking it and run the post-play hooks
+    fn monitor(
+        &self,
+        profile_name: String,
+        pid: u32,
+        options: LaunchOptions,
+        cache: BlobCache,
+        saves: Option<SavesLink>,
+        on_exit: Option<ExitCallback>,
+    ) {
+        let mut exit = loop {
+            thread::sleep(EXIT_POLL_INTERVAL);
+
+            let Ok(mut running) = self.running.lock() else {
+                return;
+            };
+            // Stop if the game is no longer tracked or the profile was relaunched in the meantime
+            let status = match running.get_mut(&profile_name) {
+                Some(game) if game.handle.pid == pid => game.child.try_wait(),
+                _ => return,
+            };
+            if let Ok(None) = status {
+                continue;
+            }
+
+            // The game has exited (or can no longer be waited on), so stop tracking it
+            let Some(game) = running.remove(&profile_name) else {
+                return;
+            };
+            if let Err(e) = &status {
+                warn!("Lost track of game for profile '{}': {}", profile_name, e);
+            }
+            break GameExit {
+                profile_name: profile_name.clone(),
+                exit_code: status.ok().flatten().and_then(|status| status.code()),
+                terminated: game.terminated,
+                blobs_collected: 0,
+                saves_copied: 0,
+            };
+        };
+        info!("Runtime for profile '{}' exited with code {:?}", profile_name, exit.exit_code);
+
+        // The user's own saves only go back once the profile's are safe, so a failed copy loses nothing
+        if let Some(saves) = saves {
+            match copy_save_files(&saves.game_saves, &saves.profile_saves) {
+                Ok(copied) => {
+                    exit.saves_copied = copied;
+                    match saves.restore_original() {
+                        Ok(restored) => info!("Restored {} original save files after profile '{}'", restored, profile_name),
+                        Err(e) => warn!("Failed to restore the original saves after profile '{}': {:#}", profile_name, e),
+                    }
+                }
+                Err(e) => warn!("Failed to copy saves back for profile '{}': {}", profile_name, e),
+            }
+        }
+
+        if options.gc_on_exit {
+            match cache.garbage_collect_all() {
+                Ok(collected) => exit.blobs_collected = collected,
+                Err(e) => warn!("Post-play garbage collection failed: {}", e),
+            }
+        }
+
+        if let Some(on_exit) = on_exit {
+            on_exit(exit);
+        }
+    }
+
+    /// Put the profile's saves (its own or its shared pool's) in the folder the game reads them from
+    /// The user's own saves there are backed up under the data root until the game exits and they're restored
+    fn install_saves(settings: &Settings, profile_name: &str, options: &LaunchOptions) -> Result<Option<SavesLink>> {
+        let game_saves = match &options.game_saves_dir {
+            Some(game_saves) => game_saves.clone(),
+            None => match dirs::document_dir() {
+                Some(documents) => documents.join(GAME_USER_FILES_DIR),
+                None => {
+                    warn!("No documents folder found, launching profile '{}' without its saves", profile_name);
+                    return Ok(None);
+                }
+            },
+        };
+        let profile = ProfileManager::new(settings.data_root.join("profiles")).get_profile(profile_name)?
+            .ok_or_else(|| anyhow!("Profile '{}' not found", profile_name))?;
+        let profile_saves = profile.effective_saves_dir(&settings.data_root)?;
+
+        // Taken fresh each launch, since the user may have saved outside DeltaRuntime since the last one.
+        // Skipped if the last launch never restored them: the game's folder then holds a profile's saves
+        let backup_dir = settings.data_root.join(ORIGINAL_SAVES_BACKUP_DIR);
+        let marker = backup_dir.join(SAVES_IN_USE_MARKER);
+        if marker.exists() {
+            warn!("Original saves from an earlier launch were never restored; keeping the backup in {}", backup_dir.display());
+        } else {
+            let backed_up = copy_save_files(&game_saves, &backup_dir)
+                .context("Failed to back up the original saves")?;
+            fs::write(&marker, profile_name)
+                .context("Failed to mark the saves as in use")?;
+            info!("Backed up {} original save files to {}", backed_up, backup_dir.display());
+        }
+
+        let saves = SavesLink { profile_saves, game_saves, backup_dir };
+        let installed = match copy_save_files(&saves.profile_saves, &saves.game_saves) {
+            Ok(installed) => installed,
+            Err(e) => {
+                if let Err(restore_error) = saves.restore_original() {
+                    warn!("Failed to restore the original saves: {:#}", restore_error);
+                }
+                return Err(e.context(format!("Failed to put saves in place for profile '{}'", profile_name)));
+            }
+        };
+        info!("Put {} save files from {} in place for profile '{}'", installed, saves.profile_saves.display(), profile_name);
+        Ok(Some(saves))
+    }
+
+    /// Whether a tracked game hasn't exited yet (exited games are left for their monitor to clean up)
+    fn still_running(running: &mut HashMap<String, RunningGame>, profile_name: &str) -> bool {
+        running.get_mut(profile_name)
+            .is_some_and(|game| matches!(game.child.try_wait(), Ok(None)))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, RunningGame>>> {
+        self.running.lock().map_err(|e| anyhow!("Launcher lock error: {}", e))
+    }
+}
+
+/// Replace the save files in `dest` with those in `src` (none if `src` doesn't exist), returning how many were copied
+fn copy_save_files(src: &Path, dest: &Path) -> Result<usize> {
+    let save_globs = compile_globs(&[SAVE_FILE_GLOB.to_string()])?;
+    let save_files = |dir: &Path| -> Result<Vec<PathBuf>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).with_context(|| format!("Failed to read saves: {}", dir.display()))? {
+            let path = entry?.path();
+            let is_save = path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| matches_any_glob(name, &save_globs));
+            if is_save && path.is_file() {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    };
+
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create saves directory: {}", dest.display()))?;
+    for stale in save_files(dest)? {
+        fs::remove_file(&stale)
+            .with_context(|| format!("Failed to remove save: {}", stale.display()))?;
+    }
+    let sources = save_files(src)?;
+    for source in &sources {
+        let target = dest.join(source.file_name().unwrap_or_default());
+        fs::copy(source, &target)
+            .with_context(|| format!("Failed to copy save {} to {}", source.display(), target.display()))?;
+    }
+    Ok(sources.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_track_and_terminate() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::mpsc;
+        use crate::profiles::ProfileManager;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+
+        // The game's saves folder already holds the user's own save and settings
+        let game_saves = temp_dir.path().join("User Files");
+        fs::create_dir_all(&game_saves).unwrap();
+        fs::write(game_saves.join("GTASAsf1.b"), "original").unwrap();
+        fs::write(game_saves.join("gta_sa.set"), "settings").unwrap();
+
+        // A stand-in game that saves into a new slot, then runs until it's stopped
+        let exe = base_dir.join(Settings::GAME_EXECUTABLE);
+        fs::write(&exe, format!("#!/bin/sh\necho progress > '{}'\nsleep 30\n", game_saves.join("GTASAsf2.b").display())).unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        fs::create_dir_all(settings.get_cache_directory()).unwrap();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        fs::write(profile.saves_dir.join("GTASAsf1.b"), "profile").unwrap();
+        let options = LaunchOptions { game_saves_dir: Some(game_saves.clone()), ..LaunchOptions::default() };
+
+        // Nothing to launch before the runtime is built
+        let launcher = RuntimeLauncher::new();
+        assert!(launcher.launch(&settings, "test", options.clone(), None).is_err());
+        assert!(RuntimeBuilder::new(settings.clone()).build_runtime("test", None).unwrap().success);
+
+        // A profile that's building can't be launched
+        launcher.begin_build("test").unwrap();
+        assert!(launcher.launch(&settings, "test", options.clone(), None).is_err());
+        launcher.end_build("test");
+
+        let (exit_tx, exit_rx) = mpsc::channel();
+        let handle = launcher.launch(&settings, "test", options.clone(), Some(Box::new(move |exit| {
+            exit_tx.send(exit).unwrap();
+        }))).unwrap();
+        assert!(handle.pid > 0);
+        assert!(launcher.is_running("test"));
+        assert_eq!(launcher.running_games().len(), 1);
+        assert!(launcher.launch(&settings, "test", options, None).is_err());
+        assert!(launcher.begin_build("test").is_err());
+
+        // The profile's saves replaced the user's, which were backed up
+        assert_eq!(fs::read_to_string(game_saves.join("GTASAsf1.b")).unwrap(), "profile");
+        assert_eq!(fs::read_to_string(game_saves.join("gta_sa.set")).unwrap(), "settings");
+        assert_eq!(fs::read_to_string(data_root.join(ORIGINAL_SAVES_BACKUP_DIR).join("GTASAsf1.b")).unwrap(), "original");
+
+        // Let the game save before stopping it
+        for _ in 0..100 {
+            if game_saves.join("GTASAsf2.b").exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert!(launcher.terminate("test").unwrap());
+        let exit = exit_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(exit.terminated);
+        assert!(!launcher.is_running("test"));
+        assert!(!launcher.terminate("test").unwrap());
+
+        // The new save was copied back into the profile, and the user's own saves are back in the game's folder
+        assert_eq!(exit.saves_copied, 2);
+        assert_eq!(fs::read_to_string(profile.saves_dir.join("GTASAsf2.b")).unwrap().trim(), "progress");
+        assert_eq!(fs::read_to_string(game_saves.join("GTASAsf1.b")).unwrap(), "original");
+        assert!(!game_saves.join("GTASAsf2.b").exists());
+        assert_eq!(fs::read_to_string(game_saves.join("gta_sa.set")).unwrap(), "settings");
+        assert!(!data_root.join(ORIGINAL_SAVES_BACKUP_DIR).join(SAVES_IN_USE_MARKER).exists());
+        launcher.begin_build("test").unwrap();
+    }
+}
@@ -1,14 +1,29 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use anyhow::{Context, Result};
 use tracing::{info, debug, warn};
 
-use crate::virtual_fs::{VirtualFileSystem, VirtualNodeSource};
+use crate::virtual_fs::{PruneReport, VirtualFileSystem, VirtualNodeSource};
 use crate::blob_cache::BlobCache;
 use crate::settings::Settings;
-use crate::profiles::ProfileManager;
+use crate::profiles::{Profile, ProfileManager};
+use crate::progress::{ProgressCadence, ProgressThrottle};
+use crate::path_utils::{compile_globs, get_drive_letter, is_within, matches_any_glob, rel_path_key, safe_join};
+
+/// File name of the saved plan inside a profile's runtime directory
+pub const PLAN_FILE: &str = "runtime_plan.json";
+
+/// File name the previously saved plan is kept under, for diffing a build against the one before it
+pub const PREVIOUS_PLAN_FILE: &str = "runtime_plan.prev.json";
+
+/// File in a profile's runtime directory recording the size and mtime of every base file the runtime was built from
+pub const BASE_MANIFEST_FILE: &str = "base_manifest.json";
+
+/// Size and modification time of each base file, keyed by relative path
+type BaseManifest = BTreeMap<String, BaseFileStamp>;
 
 /// Source of a file in the runtime plan
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,6 +32,10 @@ pub enum RuntimeSource {
     Base,
     /// File comes from a blob in the cache (identified by hash)
     Blob(String), // Hash as hex string for JSON serialization
+    /// File is copied straight from the workspace (protected files that are never normalized)
+    Workspace,
+    /// File comes from the shared workspace, linked like a base file
+    Shared,
 }
 
 /// A single entry in the runtime plan
@@ -49,10 +68,77 @@ pub struct RuntimePlan {
     pub base_files: usize,
     /// Number of files from blob cache (overrides/new files)
     pub blob_files: usize,
+    /// Number of protected files copied from the workspace
+    #[serde(default)]
+    pub workspace_files: usize,
+    /// Number of base files left out by the excluded base globs
+    #[serde(default)]
+    pub excluded_files: usize,
+    /// Number of files from the shared workspace
+    #[serde(default)]
+    pub shared_files: usize,
+    /// Fingerprint of the base installation the runtime was built from, recorded when the plan is saved by a build
+    #[serde(default)]
+    pub base_fingerprint: Option<BaseFingerprint>,
     /// The actual plan entries
     pub entries: Vec<RuntimePlanEntry>,
 }
 
+/// Why a single file comes from where it does in the runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanExplanation {
+    /// Relative path from game root
+    pub rel_path: String,
+    /// Where the runtime takes the file from (None if neither base nor workspace has it)
+    pub source: Option<RuntimeSource>,
+    /// Whether this file exists in the base installation
+    pub has_base: bool,
+    /// Whether this file exists in the workspace
+    pub has_workspace: bool,
+    /// Whether this file exists in the shared workspace
+    #[serde(default)]
+    pub has_shared: bool,
+    /// Whether the workspace (or shared) file overrides a lower layer's file
+    pub is_override: bool,
+    /// Whether the file matches a protected glob (copied instead of linked from a blob)
+    pub is_protected: bool,
+    /// Whether the workspace file was found in the blob index (false means its hash was computed on the fly)
+    pub in_index: bool,
+    /// Whether the base file is left out by the excluded base globs
+    #[serde(default)]
+    pub is_excluded: bool,
+}
+
+/// Progress information for plan computation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanProgress {
+    /// Files planned so far
+    pub files_planned: usize,
+    /// Current file being planned
+    pub current_file: Option<String>,
+}
+
+/// Callback function type for planner progress updates
+pub type PlanProgressCallback = Arc<dyn Fn(PlanProgress) + Send + Sync>;
+
+/// Running totals of the files planned so far
+#[derive(Debug, Default)]
+struct PlanCounts {
+    total_size: u64,
+    base_files: usize,
+    blob_files: usize,
+    shared_files: usize,
+}
+
+/// What stays the same for every file of a plan's traversal of the virtual tree
+struct PlanTraversal<'a> {
+    base_path: &'a Path,
+    profile_name: &'a str,
+    protected_globs: &'a [glob::Pattern],
+    callback: &'a PlanProgressCallback,
+    throttle: &'a ProgressThrottle,
+}
+
 /// Runtime plan computer and manager
 pub struct RuntimePlanner {
     settings: Settings,
@@ -62,8 +148,7 @@ pub struct RuntimePlanner {
 impl RuntimePlanner {
     /// Create a new runtime planner
     pub fn new(settings: Settings) -> Self {
-        let cache_dir = settings.data_root.join("cache");
-        let blob_cache = BlobCache::new(cache_dir);
+        let blob_cache = settings.blob_cache();
         
         Self {
             settings,
@@ -73,6 +158,17 @@ impl RuntimePlanner {
 
     /// Compute a runtime plan for a given profile
     pub fn compute_plan(&self, profile_name: &str) -> Result<RuntimePlan> {
+        self.compute_plan_with_progress(profile_name, None)
+    }
+
+    /// Compute a runtime plan for a given profile, reporting progress as files are planned
+    pub fn compute_plan_with_progress(
+        &self,
+        profile_name: &str,
+        progress_callback: Option<PlanProgressCallback>,
+    ) -> Result<RuntimePlan> {
+        let callback = progress_callback.unwrap_or_else(|| Arc::new(|_| {}));
+
         info!("Computing runtime plan for profile: {}", profile_name);
 
         // Get profile manager and validate profile exists
@@ -81,55 +177,520 @@ impl RuntimePlanner {
         let profile = profile_manager.get_profile(profile_name)?
             .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
 
-        // Create virtual file system
-        let vfs = VirtualFileSystem::new(
-            self.settings.base_path.clone(),
-            profile.workspace_dir.clone(),
-        );
+        // Create virtual file system over the profile's base installation
+        let base_path = self.resolve_base_path(&profile)?;
+        let vfs = self.profile_vfs(&base_path, &profile)?;
+        let protected_globs = self.protected_globs()?;
 
         // Get the complete virtual file tree
         let root_node = vfs.get_virtual_tree(None)
             .context("Failed to get virtual file tree")?;
 
         let mut entries = Vec::new();
-        let mut total_size = 0u64;
-        let mut base_files = 0;
-        let mut blob_files = 0;
+        let mut counts = PlanCounts::default();
+        let throttle = ProgressThrottle::new(
+            ProgressCadence::from_preferences(&self.settings.preferences),
+            Self::count_files(&root_node),
+        );
+        let traversal = PlanTraversal {
+            base_path: &base_path,
+            profile_name,
+            protected_globs: &protected_globs,
+            callback: &callback,
+            throttle: &throttle,
+        };
 
         // Recursively traverse the virtual tree and build plan entries
-        self.traverse_and_plan(&root_node, "", &mut entries, &mut total_size, &mut base_files, &mut blob_files, profile_name)?;
+        self.traverse_and_plan(&root_node, "", &traversal, &mut entries, &mut counts)?;
+
+        callback(PlanProgress {
+            files_planned: entries.len(),
+            current_file: None,
+        });
+
+        // Fail now rather than deep in the build if blobs vanished from the cache
+        self.verify_blobs_exist(&entries)?;
+
+        // Stable order so saved plans and plan diffs are comparable across runs
+        entries.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+        let workspace_files = entries.iter()
+            .filter(|entry| entry.source == RuntimeSource::Workspace)
+            .count();
 
         let plan = RuntimePlan {
             profile_name: profile_name.to_string(),
             generated_at: chrono::Utc::now().to_rfc3339(),
             total_files: entries.len(),
-            total_size,
-            base_files,
-            blob_files,
+            total_size: counts.total_size,
+            base_files: counts.base_files,
+            blob_files: counts.blob_files,
+            workspace_files,
+            excluded_files: vfs.excluded_base_file_count(),
+            shared_files: counts.shared_files,
+            base_fingerprint: None,
             entries,
         };
 
         info!(
-            "Runtime plan computed: {} files ({} base, {} blob), {} bytes total",
+            "Runtime plan computed: {} files ({} base, {} shared, {} blob, {} protected, {} excluded), {} bytes total",
             plan.total_files,
             plan.base_files, 
+            plan.shared_files,
             plan.blob_files,
+            plan.workspace_files,
+            plan.excluded_files,
             plan.total_size
         );
 
         Ok(plan)
     }
 
+    /// Categorize every base file by what the profile's runtime does with it: include it, override it or exclude it
+    /// Sizes are the base files' own, so overridden bytes are what the overrides replace
+    pub fn base_coverage(&self, profile_name: &str) -> Result<BaseCoverage> {
+        let plan = self.compute_plan(profile_name)?;
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile = ProfileManager::new(profiles_root).get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+        let vfs = self.profile_vfs(&self.resolve_base_path(&profile)?, &profile)?;
+
+        let sources: HashMap<&str, &RuntimeSource> = plan.entries.iter()
+            .map(|entry| (entry.rel_path.as_str(), &entry.source))
+            .collect();
+        let mut coverage = BaseCoverage::default();
+        for (rel_path, size) in vfs.base_files() {
+            coverage.total_files += 1;
+            coverage.total_bytes += size;
+            match sources.get(rel_path.as_str()) {
+                Some(RuntimeSource::Base) => {
+                    coverage.included_files += 1;
+                    coverage.included_bytes += size;
+                }
+                Some(_) => {
+                    coverage.overridden.push(rel_path);
+                    coverage.overridden_bytes += size;
+                }
+                None => {
+                    coverage.excluded.push(rel_path);
+                    coverage.excluded_bytes += size;
+                }
+            }
+        }
+        coverage.overridden.sort();
+        coverage.excluded.sort();
+
+        info!(
+            "Base coverage for profile {}: {} included, {} overridden, {} excluded of {} base files",
+            profile_name, coverage.included_files, coverage.overridden.len(), coverage.excluded.len(), coverage.total_files
+        );
+        Ok(coverage)
+    }
+
+    /// Check that every blob the plan links from exists in the cache
+    fn verify_blobs_exist(&self, entries: &[RuntimePlanEntry]) -> Result<()> {
+        let blob_entries: Vec<(&str, String)> = entries.iter()
+            .filter_map(|entry| match &entry.source {
+                RuntimeSource::Blob(hash_str) => Some((entry.rel_path.as_str(), hash_str.clone())),
+                _ => None,
+            })
+            .collect();
+        let hashes: Vec<String> = blob_entries.iter().map(|(_, hash_str)| hash_str.clone()).collect();
+        let presence = self.blob_cache.blobs_exist(&hashes)
+            .context("Failed to check blobs exist")?;
+
+        let mut missing = Vec::new();
+        for ((rel_path, _), blob) in blob_entries.iter().zip(&presence) {
+            if blob.malformed {
+                return Err(anyhow::anyhow!("Invalid blob hash for {}: {}", rel_path, blob.hash));
+            }
+            if !blob.exists {
+                missing.push(*rel_path);
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        const SHOWN: usize = 10;
+        let mut listed = missing.iter().take(SHOWN).copied().collect::<Vec<_>>().join(", ");
+        if missing.len() > SHOWN {
+            listed.push_str(&format!(" (and {} more)", missing.len() - SHOWN));
+        }
+
+        Err(anyhow::anyhow!(
+            "{} overlay file(s) are missing from the blob cache: {}. Run a cache audit and re-add these files to the workspace to restore their blobs",
+            missing.len(),
+            listed
+        ))
+    }
+
+    /// Get the base installation a profile builds from
+    /// Uses the profile's override when present, validating it exists and shares the data root's volume
+    pub fn resolve_base_path(&self, profile: &Profile) -> Result<PathBuf> {
+        let override_path = match &profile.metadata.base_path_override {
+            Some(path) => path,
+            None => return Ok(self.settings.base_path.clone()),
+        };
+
+        if !override_path.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Base path override for profile '{}' does not exist or is not a directory: {}",
+                profile.metadata.name,
+                override_path.display()
+            ));
+        }
+
+        // Hardlinks require the base and the data root to be on the same volume
+        if let (Ok(Some(base_drive)), Ok(Some(data_drive))) =
+            (get_drive_letter(override_path), get_drive_letter(&self.settings.data_root))
+        {
+            if base_drive != data_drive {
+                return Err(anyhow::anyhow!(
+                    "Base path override for profile '{}' must be on the same drive as the data root for hardlinks. Base: {}, Data: {}",
+                    profile.metadata.name,
+                    base_drive,
+                    data_drive
+                ));
+            }
+        }
+
+        Ok(override_path.clone())
+    }
+
+    /// Virtual file system over a profile's layers, set up the way plans see them
+    fn profile_vfs(&self, base_path: &Path, profile: &Profile) -> Result<VirtualFileSystem> {
+        let mut vfs = VirtualFileSystem::new(base_path.to_path_buf(), profile.workspace_dir.clone());
+        vfs.set_shared_path(self.settings.get_shared_workspace_directory());
+        vfs.set_follow_links(self.settings.preferences.follow_links);
+        vfs.set_data_root(self.settings.data_root.clone());
+        vfs.set_excluded_base_globs(&self.settings.preferences.excluded_base_globs)?;
+        Ok(vfs)
+    }
+
+    /// The protected globs, compiled once for all the files a call plans
+    fn protected_globs(&self) -> Result<Vec<glob::Pattern>> {
+        compile_globs(&self.settings.preferences.protected_globs).context("Invalid protected glob")
+    }
+
+    /// Plan a single file the way `compute_plan` would, without walking the whole tree
+    /// Returns None if no layer provides the file (or only an excluded base file does)
+    pub fn plan_entry(&self, profile_name: &str, rel_path: &str) -> Result<Option<RuntimePlanEntry>> {
+        let rel_path = rel_path.replace('\\', "/").trim_start_matches('/').to_string();
+
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile_manager = ProfileManager::new(profiles_root);
+        let profile = profile_manager.get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+
+        let base_path = self.resolve_base_path(&profile)?;
+        // Rejects paths escaping the game root before any layer is looked at
+        safe_join(&base_path, &rel_path)?;
+        let vfs = self.profile_vfs(&base_path, &profile)?;
+        let physical_path = match vfs.resolve_physical(&rel_path) {
+            Ok(physical_path) => physical_path,
+            Err(_) => return Ok(None),
+        };
+        if physical_path.is_dir() {
+            return Err(anyhow::anyhow!("Path is a directory, not a file: {}", rel_path));
+        }
+        let Ok(node) = vfs.get_node(&rel_path) else {
+            return Ok(None);
+        };
+
+        let (source, has_base, is_override) = self.plan_source(&node.source, &rel_path, &base_path, profile_name, &self.protected_globs()?)?;
+        Ok(Some(RuntimePlanEntry {
+            rel_path,
+            source,
+            size: node.size.unwrap_or(0),
+            has_base,
+            is_override,
+        }))
+    }
+
+    /// Replace one entry of a profile's saved plan (removing it for None) and save the plan
+    /// Totals are recounted from the entries; the plan being replaced is kept as the previous plan
+    pub fn update_plan_entry(&self, profile_name: &str, rel_path: &str, entry: Option<RuntimePlanEntry>) -> Result<RuntimePlan> {
+        let mut plan = self.load_plan(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("No saved runtime plan for profile '{}'", profile_name))?;
+
+        plan.entries.retain(|existing| existing.rel_path != rel_path);
+        if let Some(entry) = entry {
+            let position = plan.entries.partition_point(|existing| existing.rel_path < entry.rel_path);
+            plan.entries.insert(position, entry);
+        }
+
+        plan.generated_at = chrono::Utc::now().to_rfc3339();
+        plan.total_files = plan.entries.len();
+        plan.total_size = plan.entries.iter().map(|entry| entry.size).sum();
+        plan.base_files = plan.entries.iter().filter(|entry| entry.source == RuntimeSource::Base).count();
+        plan.blob_files = plan.entries.iter().filter(|entry| matches!(entry.source, RuntimeSource::Blob(_))).count();
+        plan.shared_files = plan.entries.iter().filter(|entry| entry.source == RuntimeSource::Shared).count();
+        plan.workspace_files = plan.entries.iter().filter(|entry| entry.source == RuntimeSource::Workspace).count();
+
+        self.save_plan(&plan)?;
+        Ok(plan)
+    }
+
+    /// Explain where the runtime will take a single file from, without computing the whole plan
+    pub fn explain(&self, profile_name: &str, rel_path: &str) -> Result<PlanExplanation> {
+        let rel_path = rel_path.replace('\\', "/").trim_start_matches('/').to_string();
+        info!("Explaining runtime source for {}/{}", profile_name, rel_path);
+
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile_manager = ProfileManager::new(profiles_root);
+        let profile = profile_manager.get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+
+        let base_path = self.resolve_base_path(&profile)?;
+        let base_file = safe_join(&base_path, &rel_path)?;
+        let workspace_file = safe_join(&profile.workspace_dir, &rel_path)?;
+        let shared_file = safe_join(self.settings.get_shared_workspace_directory(), &rel_path)?;
+        if base_file.is_dir() || workspace_file.is_dir() || shared_file.is_dir() {
+            return Err(anyhow::anyhow!("Path is a directory, not a file: {}", rel_path));
+        }
+
+        let protected_globs = self.protected_globs()?;
+        let mut explanation = PlanExplanation {
+            is_protected: matches_any_glob(&rel_path, &protected_globs),
+            rel_path,
+            source: None,
+            has_base: base_file.is_file(),
+            has_workspace: workspace_file.is_file(),
+            has_shared: shared_file.is_file(),
+            is_override: false,
+            in_index: false,
+            is_excluded: false,
+        };
+
+        if !explanation.has_base && !explanation.has_workspace && !explanation.has_shared {
+            return Ok(explanation);
+        }
+
+        // Resolve the node the same way the full plan does
+        let vfs = self.profile_vfs(&base_path, &profile)?;
+        explanation.is_excluded = explanation.has_base && vfs.is_excluded_base(&explanation.rel_path);
+        if explanation.is_excluded && !explanation.has_workspace && !explanation.has_shared {
+            return Ok(explanation);
+        }
+        let node = vfs.get_node(&explanation.rel_path)?;
+
+        if explanation.has_workspace && !explanation.is_protected {
+            explanation.in_index = self.blob_cache
+                .find_blob_hash_for_file(profile_name, &explanation.rel_path)?
+                .is_some();
+        }
+
+        let (source, _, is_override) = self.plan_source(&node.source, &explanation.rel_path, &base_path, profile_name, &protected_globs)?;
+        explanation.source = Some(source);
+        explanation.is_override = is_override;
+
+        Ok(explanation)
+    }
+
+    /// Get the workspace directory of a profile by name
+    pub fn workspace_path_for_profile(&self, profile_name: &str) -> Result<PathBuf> {
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile_manager = ProfileManager::new(profiles_root);
+        let profile = profile_manager.get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+        Ok(profile.workspace_dir)
+    }
+
+    /// Get the base installation for a profile by name
+    pub fn base_path_for_profile(&self, profile_name: &str) -> Result<PathBuf> {
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile_manager = ProfileManager::new(profiles_root);
+        let profile = profile_manager.get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+        self.resolve_base_path(&profile)
+    }
+
+    /// Take the fingerprint of a base installation
+    pub fn fingerprint_base(&self, base_path: &Path) -> Result<BaseFingerprint> {
+        self.scan_base(base_path).map(|(fingerprint, _)| fingerprint)
+    }
+
+    /// Fingerprint a base installation, also listing each file's size and mtime
+    /// Covers the same files the virtual file system takes from base: the data root and excluded base globs are skipped
+    fn scan_base(&self, base_path: &Path) -> Result<(BaseFingerprint, BaseManifest)> {
+        let mut manifest = BaseManifest::new();
+        let mut hasher = blake3::Hasher::new();
+        let mut file_count = 0;
+        let mut total_size = 0;
+
+        let excluded_globs = compile_globs(&self.settings.preferences.excluded_base_globs)
+            .context("Invalid excluded base glob")?;
+        let walker = walkdir::WalkDir::new(base_path).sort_by_file_name().into_iter().filter_entry(|entry| {
+            if is_within(entry.path(), &self.settings.data_root) {
+                return false;
+            }
+            // Skipping an excluded directory skips everything inside it, as in the virtual file system
+            match entry.path().strip_prefix(base_path).ok().and_then(rel_path_key) {
+                Some(rel_path) if !rel_path.is_empty() => !matches_any_glob(&rel_path, &excluded_globs),
+                _ => true,
+            }
+        });
+        for entry in walker {
+            let entry = entry.with_context(|| format!("Failed to scan base installation: {}", base_path.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = entry.metadata()
+                .with_context(|| format!("Failed to read metadata: {}", entry.path().display()))?;
+            let modified = metadata.modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |since_epoch| since_epoch.as_nanos());
+            let rel_path = entry.path().strip_prefix(base_path).ok().and_then(rel_path_key)
+                .unwrap_or_else(|| entry.path().to_string_lossy().into_owned());
+
+            // Separate fields with NUL, which can't appear in paths
+            for field in [&rel_path, &metadata.len().to_string(), &modified.to_string()] {
+                hasher.update(field.as_bytes());
+                hasher.update(&[0]);
+            }
+            manifest.insert(rel_path, BaseFileStamp { size: metadata.len(), modified_ns: modified as u64 });
+            file_count += 1;
+            total_size += metadata.len();
+        }
+
+        let fingerprint = BaseFingerprint {
+            digest: hasher.finalize().to_hex().to_string(),
+            file_count,
+            total_size,
+            computed_at: chrono::Utc::now().to_rfc3339(),
+        };
+        Ok((fingerprint, manifest))
+    }
+
+    /// Fingerprint a base installation and record it in a plan about to be saved for a build from that base
+    /// Each profile's runtime keeps its own fingerprint, so rebuilding one profile doesn't vouch for the others
+    pub fn record_base_fingerprint(&self, plan: &mut RuntimePlan, base_path: &Path) -> Result<BaseFingerprint> {
+        let (fingerprint, manifest) = self.scan_base(base_path)?;
+        plan.base_fingerprint = Some(fingerprint.clone());
+
+        let runtime_dir = self.settings.data_root.join("runtimes").join(format!("{}-latest", plan.profile_name));
+        fs::create_dir_all(&runtime_dir)
+            .context("Failed to create profile runtime directory")?;
+        let manifest_file = runtime_dir.join(BASE_MANIFEST_FILE);
+        let json = serde_json::to_string(&manifest)
+            .context("Failed to serialize base manifest")?;
+        fs::write(&manifest_file, json)
+            .with_context(|| format!("Failed to write base manifest: {}", manifest_file.display()))?;
+
+        debug!("Recorded base fingerprint for {} from {}: {}", plan.profile_name, base_path.display(), fingerprint.digest);
+        Ok(fingerprint)
+    }
+
+    /// Base file listing recorded when a profile's runtime was last built
+    fn load_base_manifest(&self, profile_name: &str) -> Result<Option<BaseManifest>> {
+        let manifest_file = self.settings.data_root
+            .join("runtimes")
+            .join(format!("{}-latest", profile_name))
+            .join(BASE_MANIFEST_FILE);
+        if !manifest_file.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&manifest_file)
+            .with_context(|| format!("Failed to read base manifest: {}", manifest_file.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse base manifest: {}", manifest_file.display()))
+    }
+
+    /// Base files added, removed or modified since a profile's runtime was last built from them, sorted
+    /// None if no file listing was recorded for the profile
+    pub fn changed_base_files(&self, profile_name: &str, base_path: &Path) -> Result<Option<Vec<String>>> {
+        let Some(recorded) = self.load_base_manifest(profile_name)? else {
+            return Ok(None);
+        };
+        let (_, current) = self.scan_base(base_path)?;
+
+        let mut changed: Vec<String> = current.iter()
+            .filter(|(rel_path, stamp)| recorded.get(*rel_path) != Some(stamp))
+            .map(|(rel_path, _)| rel_path.clone())
+            .chain(recorded.keys().filter(|rel_path| !current.contains_key(*rel_path)).cloned())
+            .collect();
+        changed.sort();
+        Ok(Some(changed))
+    }
+
+    /// For every base file changed since a profile's runtime was last built, the profiles overriding it,
+    /// either in their own workspace or through the shared workspace layered into every profile
+    /// Only files overridden by at least one profile are listed, since those overrides may no longer fit the update
+    pub fn profiles_affected_by_base_changes(&self) -> Result<Vec<BaseFileImpact>> {
+        let profiles = ProfileManager::new(self.settings.data_root.join("profiles")).list_profiles()?;
+        let index = self.blob_cache.load_index()?;
+        let shared_dir = self.settings.get_shared_workspace_directory();
+
+        let mut impacts: BTreeMap<(PathBuf, String), Vec<String>> = BTreeMap::new();
+        for profile in &profiles {
+            let profile_name = &profile.metadata.name;
+            let base_path = match self.resolve_base_path(profile) {
+                Ok(base_path) => base_path,
+                Err(e) => {
+                    warn!("Skipping profile {} when checking base changes: {}", profile_name, e);
+                    continue;
+                }
+            };
+            let Some(changed) = self.changed_base_files(profile_name, &base_path)? else {
+                warn!("No base file listing recorded for profile {}, can't tell which files changed", profile_name);
+                continue;
+            };
+            for rel_path in changed {
+                let overridden = self.blob_cache.profiles_overriding_in(&index, &rel_path).contains(profile_name)
+                    || safe_join(&shared_dir, &rel_path).is_ok_and(|shared_file| shared_file.is_file());
+                if overridden {
+                    impacts.entry((base_path.clone(), rel_path)).or_default().push(profile_name.clone());
+                }
+            }
+        }
+
+        Ok(impacts.into_iter()
+            .map(|((base_path, rel_path), mut profiles)| {
+                profiles.sort();
+                BaseFileImpact { base_path, rel_path, profiles }
+            })
+            .collect())
+    }
+
+    /// Compare a profile's base installation against the fingerprint recorded when its runtime was last built
+    /// A profile without a recorded fingerprint doesn't count as changed
+    pub fn check_base_fingerprint(&self, profile_name: &str, base_path: &Path) -> Result<BaseChangeCheck> {
+        let recorded = self.load_plan(profile_name)?.and_then(|plan| plan.base_fingerprint);
+        let current = self.fingerprint_base(base_path)?;
+        let changed = recorded.as_ref().is_some_and(|recorded| recorded.digest != current.digest);
+        if changed {
+            warn!("Base installation changed since it was last recorded: {}", base_path.display());
+        }
+
+        Ok(BaseChangeCheck {
+            base_path: base_path.to_path_buf(),
+            recorded,
+            current,
+            changed,
+        })
+    }
+
+    /// Number of files in a virtual tree, so planning progress can be paced against it
+    fn count_files(node: &crate::virtual_fs::VirtualNode) -> usize {
+        match &node.children {
+            Some(children) if node.is_directory => children.iter().map(Self::count_files).sum(),
+            _ if node.is_directory => 0,
+            _ => 1,
+        }
+    }
+
     /// Recursively traverse virtual tree and create plan entries
     fn traverse_and_plan(
         &self,
         node: &crate::virtual_fs::VirtualNode,
         current_path: &str,
+        traversal: &PlanTraversal,
         entries: &mut Vec<RuntimePlanEntry>,
-        total_size: &mut u64,
-        base_files: &mut usize,
-        blob_files: &mut usize,
-        profile_name: &str,
+        counts: &mut PlanCounts,
     ) -> Result<()> {
         if node.is_directory {
             // For directories, traverse children
@@ -150,7 +711,7 @@ impl RuntimePlanner {
                         format!("{}/{}", current_path, node.name) // Nested directories
                     };
                     
-                    self.traverse_and_plan(child, &child_path, entries, total_size, base_files, blob_files, profile_name)?;
+                    self.traverse_and_plan(child, &child_path, traversal, entries, counts)?;
                 }
             } else {
                 debug!("Directory {} has no children", node.name);
@@ -167,26 +728,22 @@ impl RuntimePlanner {
                    node.name, current_path, rel_path);
 
             let size = node.size.unwrap_or(0);
-            *total_size += size;
+            counts.total_size += size;
 
-            let (source, has_base, is_override) = match node.source {
-                VirtualNodeSource::Base => {
-                    *base_files += 1;
-                    (RuntimeSource::Base, true, false)
-                }
-                VirtualNodeSource::Workspace => {
-                    *blob_files += 1;
-                    // For workspace-only files, look up the blob hash from index
-                    let hash = self.get_blob_hash_for_file(profile_name, &rel_path)?;
-                    (RuntimeSource::Blob(hash), false, false)
-                }
-                VirtualNodeSource::Override => {
-                    *blob_files += 1;
-                    // For override files, look up the blob hash from index
-                    let hash = self.get_blob_hash_for_file(profile_name, &rel_path)?;
-                    (RuntimeSource::Blob(hash), true, true)
-                }
-            };
+            let (source, has_base, is_override) = self.plan_source(&node.source, &rel_path, traversal.base_path, traversal.profile_name, traversal.protected_globs)?;
+            match source {
+                RuntimeSource::Base => counts.base_files += 1,
+                RuntimeSource::Blob(_) => counts.blob_files += 1,
+                RuntimeSource::Shared => counts.shared_files += 1,
+                RuntimeSource::Workspace => {}
+            }
+
+            if traversal.throttle.should_emit(entries.len() + 1) {
+                (traversal.callback)(PlanProgress {
+                    files_planned: entries.len() + 1,
+                    current_file: Some(rel_path.clone()),
+                });
+            }
 
             entries.push(RuntimePlanEntry {
                 rel_path,
@@ -200,6 +757,74 @@ impl RuntimePlanner {
         Ok(())
     }
 
+    /// Decide where the runtime takes a file from, returning (source, has_base, is_override)
+    fn plan_source(
+        &self,
+        node_source: &VirtualNodeSource,
+        rel_path: &str,
+        base_path: &Path,
+        profile_name: &str,
+        protected_globs: &[glob::Pattern],
+    ) -> Result<(RuntimeSource, bool, bool)> {
+        let is_protected = matches_any_glob(rel_path, protected_globs);
+
+        Ok(match node_source {
+            VirtualNodeSource::Base => (RuntimeSource::Base, true, false),
+            // Shared files aren't normalized into the cache, so they're linked straight from the shared workspace
+            VirtualNodeSource::Shared => {
+                let has_base = base_path.join(rel_path).exists();
+                (RuntimeSource::Shared, has_base, has_base)
+            }
+            // Protected files are never normalized, so they have no blob to link
+            VirtualNodeSource::Workspace if is_protected => (RuntimeSource::Workspace, false, false),
+            VirtualNodeSource::Override if is_protected => (RuntimeSource::Workspace, true, true),
+            VirtualNodeSource::Workspace => {
+                // For workspace-only files, look up the blob hash from index
+                let hash = self.get_blob_hash_for_file(profile_name, rel_path)?;
+                (RuntimeSource::Blob(hash), false, false)
+            }
+            VirtualNodeSource::Override => {
+                // For override files, look up the blob hash from index
+                let hash = self.get_blob_hash_for_file(profile_name, rel_path)?;
+                if self.settings.preferences.skip_identical_overrides && self.is_identical_override(&hash, rel_path, base_path)? {
+                    debug!("Override identical to base, planning it as base: {}", rel_path);
+                    (RuntimeSource::Base, true, false)
+                } else {
+                    (RuntimeSource::Blob(hash), true, true)
+                }
+            }
+        })
+    }
+
+    /// Whether an override's blob holds exactly the base file's content, so the override changes nothing
+    /// Overrides of shared files never count, since dropping them would reveal the shared file instead
+    fn is_identical_override(&self, hash_str: &str, rel_path: &str, base_path: &Path) -> Result<bool> {
+        if self.settings.get_shared_workspace_directory().join(rel_path).exists() {
+            return Ok(false);
+        }
+
+        // Sizes first, so only same-sized files are hashed
+        let base_file = base_path.join(rel_path);
+        let blob_path = self.blob_cache.get_blob_path_from_hash(hash_str)?;
+        match (fs::metadata(&base_file), fs::metadata(&blob_path)) {
+            (Ok(base), Ok(blob)) if base.is_file() && base.len() == blob.len() => {
+                Ok(self.get_file_hash(&base_file)? == hash_str)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Delete workspace overrides whose content is identical to their base file, with their blob references
+    /// These are typically files copied to the workspace and never edited
+    pub fn remove_identical_overrides(&self, profile_name: &str) -> Result<PruneReport> {
+        let profiles_root = self.settings.data_root.join("profiles");
+        let profile = ProfileManager::new(profiles_root).get_profile(profile_name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile_name))?;
+        let vfs = self.profile_vfs(&self.resolve_base_path(&profile)?, &profile)?;
+
+        vfs.prune_redundant_overrides(profile_name, &self.blob_cache)
+    }
+
     /// Get the blob hash for a file from the index (efficient lookup)
     /// Falls back to computing hash if not found in index
     fn get_blob_hash_for_file(&self, profile_name: &str, rel_path: &str) -> Result<String> {
@@ -248,10 +873,17 @@ impl RuntimePlanner {
         fs::create_dir_all(&profile_runtime_dir)
             .context("Failed to create profile runtime directory")?;
 
-        let plan_file = profile_runtime_dir.join("runtime_plan.json");
+        let plan_file = profile_runtime_dir.join(PLAN_FILE);
         let plan_json = serde_json::to_string_pretty(plan)
             .context("Failed to serialize runtime plan")?;
 
+        // Keep the plan being replaced so this one can be diffed against it later
+        if plan_file.exists() {
+            let previous_file = profile_runtime_dir.join(PREVIOUS_PLAN_FILE);
+            fs::rename(&plan_file, &previous_file)
+                .with_context(|| format!("Failed to keep previous runtime plan as: {}", previous_file.display()))?;
+        }
+
         fs::write(&plan_file, plan_json)
             .with_context(|| format!("Failed to write runtime plan to: {}", plan_file.display()))?;
 
@@ -261,10 +893,81 @@ impl RuntimePlanner {
 
     /// Load a runtime plan from disk
     pub fn load_plan(&self, profile_name: &str) -> Result<Option<RuntimePlan>> {
+        self.load_plan_file(profile_name, PLAN_FILE)
+    }
+
+    /// Fingerprint of a profile's effective runtime content, for telling whether profiles are identical
+    /// or whether one changed. Computed from a fresh plan, so only protected workspace files are hashed
+    pub fn profile_digest(&self, profile_name: &str) -> Result<String> {
+        let plan = self.compute_plan(profile_name)?;
+        Ok(Self::plan_digest(&plan))
+    }
+
+    /// Deterministic BLAKE3 hash over a plan's entries (path, source, blob hash and size), in path order
+    /// Independent of when the plan was generated and of the profile's name
+    pub fn plan_digest(plan: &RuntimePlan) -> String {
+        let mut entries: Vec<&RuntimePlanEntry> = plan.entries.iter().collect();
+        entries.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+        let mut hasher = blake3::Hasher::new();
+        for entry in entries {
+            let (source, blob_hash) = match &entry.source {
+                RuntimeSource::Base => ("base", ""),
+                RuntimeSource::Blob(hash) => ("blob", hash.as_str()),
+                RuntimeSource::Workspace => ("workspace", ""),
+                RuntimeSource::Shared => ("shared", ""),
+            };
+            // Separate fields with NUL, which can't appear in paths
+            for field in [entry.rel_path.as_str(), source, blob_hash, &entry.size.to_string()] {
+                hasher.update(field.as_bytes());
+                hasher.update(&[0]);
+            }
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Most bytes a single runtime build copies across all profiles' saved plans, i.e. the most free space a build needs
+    /// Hardlinked files take no space, so only protected workspace files and copy extension matches count.
+    /// Profiles without a readable plan are skipped
+    pub fn largest_build_copy_size(&self) -> Result<u64> {
+        let profiles = ProfileManager::new(self.settings.data_root.join("profiles")).list_profiles()?;
+        let mut largest = 0;
+        for profile in profiles {
+            let preferences = self.settings.with_overrides(&profile.metadata.overrides).preferences;
+            match self.load_plan(&profile.metadata.name) {
+                Ok(Some(plan)) => {
+                    let copied: u64 = plan.entries.iter()
+                        .filter(|entry| entry.source == RuntimeSource::Workspace || preferences.is_copy_extension(&entry.rel_path))
+                        .map(|entry| entry.size)
+                        .sum();
+                    largest = largest.max(copied);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Skipping unreadable plan for profile {}: {}", profile.metadata.name, e),
+            }
+        }
+        Ok(largest)
+    }
+
+    /// Load the plan that was saved before the current one
+    pub fn load_previous_plan(&self, profile_name: &str) -> Result<Option<RuntimePlan>> {
+        self.load_plan_file(profile_name, PREVIOUS_PLAN_FILE)
+    }
+
+    /// Diff the current plan against the previous one, i.e. what the last build changed
+    /// Returns None until two plans have been saved
+    pub fn diff_against_previous(&self, profile_name: &str) -> Result<Option<RuntimePlanDiff>> {
+        match (self.load_previous_plan(profile_name)?, self.load_plan(profile_name)?) {
+            (Some(previous), Some(current)) => Ok(Some(self.diff_plans(&previous, &current))),
+            _ => Ok(None),
+        }
+    }
+
+    fn load_plan_file(&self, profile_name: &str, file_name: &str) -> Result<Option<RuntimePlan>> {
         let plan_file = self.settings.data_root
             .join("runtimes")
             .join(format!("{}-latest", profile_name))
-            .join("runtime_plan.json");
+            .join(file_name);
 
         if !plan_file.exists() {
             return Ok(None);
@@ -327,8 +1030,65 @@ impl RuntimePlanner {
     }
 }
 
+/// Fingerprint of a base installation: a hash over its file list, sizes and modification times
+/// Cheap to take (no file contents are read), and changes whenever the game is patched or updated
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BaseFingerprint {
+    pub digest: String,
+    pub file_count: usize,
+    pub total_size: u64,
+    /// When the fingerprint was taken (RFC 3339)
+    pub computed_at: String,
+}
+
+/// Size and modification time of a base file when its installation's fingerprint was recorded
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BaseFileStamp {
+    pub size: u64,
+    /// Nanoseconds since the Unix epoch
+    pub modified_ns: u64,
+}
+
+/// How much of the base installation a profile's runtime takes from base
+/// There are no deletion overlays, so base files only leave a runtime through the exclusion globs
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BaseCoverage {
+    pub total_files: usize,
+    pub total_bytes: u64,
+    /// Base files the runtime links as they are (including overrides planned as base for being identical)
+    pub included_files: usize,
+    pub included_bytes: u64,
+    /// Base files replaced by the profile's workspace or the shared workspace, sorted
+    pub overridden: Vec<String>,
+    pub overridden_bytes: u64,
+    /// Base files hidden by the exclusion globs and not provided by another layer, sorted
+    pub excluded: Vec<String>,
+    pub excluded_bytes: u64,
+}
+
+/// A changed base file and the profiles overriding it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseFileImpact {
+    pub base_path: PathBuf,
+    /// Relative path from game root
+    pub rel_path: String,
+    /// Profiles using this base whose workspace or the shared workspace overrides the file, sorted
+    pub profiles: Vec<String>,
+}
+
+/// A base installation compared against the fingerprint recorded for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseChangeCheck {
+    pub base_path: PathBuf,
+    /// Fingerprint recorded when the profile's runtime was last built (None if never recorded)
+    pub recorded: Option<BaseFingerprint>,
+    pub current: BaseFingerprint,
+    /// Whether the base changed since it was recorded, which leaves built runtimes linked to stale files
+    pub changed: bool,
+}
+
 /// Represents the differences between two runtime plans
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimePlanDiff {
     pub added: Vec<RuntimePlanEntry>,
     pub removed: Vec<RuntimePlanEntry>,
@@ -345,4 +1105,437 @@ impl RuntimePlanDiff {
     pub fn touched_count(&self) -> usize {
         self.added.len() + self.removed.len() + self.changed.len()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_explain_single_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "base").unwrap();
+
+        let mut settings = Settings::for_wizard(base_dir, data_root.clone());
+        settings.preferences.protected_globs = vec!["data/*.set".to_string()];
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let workspace = &profile.workspace_dir;
+        fs::create_dir_all(workspace.join("data")).unwrap();
+        fs::write(workspace.join("data/handling.cfg"), "override").unwrap();
+        fs::write(workspace.join("data/new.cfg"), "new").unwrap();
+        fs::write(workspace.join("data/gta.set"), "protected").unwrap();
+
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(workspace.join("data/handling.cfg")).unwrap();
+        cache.add_ref(&blob, "test", "data/handling.cfg").unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+
+        let base = planner.explain("test", "data/gta.dat").unwrap();
+        assert_eq!(base.source, Some(RuntimeSource::Base));
+        assert!(base.has_base && !base.has_workspace && !base.is_override);
+
+        // Backslash paths resolve the same way
+        let override_file = planner.explain("test", "data\\handling.cfg").unwrap();
+        assert_eq!(override_file.source, Some(RuntimeSource::Blob(blob.hash.to_hex().to_string())));
+        assert!(override_file.is_override && override_file.in_index);
+
+        // Files missing from the index still get a hash, but are flagged
+        let unindexed = planner.explain("test", "data/new.cfg").unwrap();
+        assert!(matches!(unindexed.source, Some(RuntimeSource::Blob(_))));
+        assert!(!unindexed.has_base && !unindexed.in_index);
+
+        let protected = planner.explain("test", "data/gta.set").unwrap();
+        assert_eq!(protected.source, Some(RuntimeSource::Workspace));
+        assert!(protected.is_protected);
+
+        assert_eq!(planner.explain("test", "data/missing.cfg").unwrap().source, None);
+        assert!(planner.explain("test", "data").is_err());
+    }
+
+    #[test]
+    fn test_base_coverage_categorizes_base_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("movies")).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+        fs::write(base_dir.join("handling.cfg"), "base").unwrap();
+        fs::write(base_dir.join("shared.cfg"), "base").unwrap();
+        fs::write(base_dir.join("movies/intro.mpg"), "intro!").unwrap();
+        fs::write(base_dir.join("movies/logo.mpg"), "logo").unwrap();
+
+        let mut settings = Settings::for_wizard(base_dir, data_root.clone());
+        settings.preferences.excluded_base_globs = vec!["movies".to_string()];
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let shared_dir = settings.get_shared_workspace_directory();
+        fs::create_dir_all(&shared_dir).unwrap();
+        fs::write(shared_dir.join("shared.cfg"), "shared").unwrap();
+
+        // An override of a base file, a replacement for one excluded file and a workspace-only file
+        fs::create_dir_all(profile.workspace_dir.join("movies")).unwrap();
+        for (rel_path, content) in [("handling.cfg", "edit"), ("movies/logo.mpg", "mine"), ("new.asi", "new")] {
+            fs::write(profile.workspace_dir.join(rel_path), content).unwrap();
+            let blob = cache.ensure_blob(profile.workspace_dir.join(rel_path)).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+
+        let coverage = RuntimePlanner::new(settings).base_coverage("test").unwrap();
+        assert_eq!((coverage.total_files, coverage.total_bytes), (5, 22));
+        assert_eq!((coverage.included_files, coverage.included_bytes), (1, 4));
+        assert_eq!(coverage.overridden, vec!["handling.cfg", "movies/logo.mpg", "shared.cfg"]);
+        assert_eq!(coverage.overridden_bytes, 12);
+        assert_eq!(coverage.excluded, vec!["movies/intro.mpg"]);
+        assert_eq!(coverage.excluded_bytes, 6);
+    }
+
+    #[test]
+    fn test_identical_overrides_plan_as_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join("copied.cfg"), "base").unwrap();
+        fs::write(base_dir.join("edited.cfg"), "base").unwrap();
+
+        let mut settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        let workspace = &profile.workspace_dir;
+        let cache = BlobCache::new(settings.get_cache_directory());
+
+        // Copied to the workspace but never edited
+        VirtualFileSystem::new(base_dir, workspace.clone())
+            .copy_to_workspace("test", "copied.cfg", &cache)
+            .unwrap();
+        let copied = cache.ensure_blob(workspace.join("copied.cfg")).unwrap();
+        fs::write(workspace.join("edited.cfg"), "edit").unwrap();
+        let edited = cache.ensure_blob(workspace.join("edited.cfg")).unwrap();
+        cache.add_ref(&edited, "test", "edited.cfg").unwrap();
+
+        let source = |planner: &RuntimePlanner, rel_path: &str| planner.plan_entry("test", rel_path).unwrap().unwrap().source;
+
+        // Off by default: every override is a blob
+        let planner = RuntimePlanner::new(settings.clone());
+        assert!(matches!(source(&planner, "copied.cfg"), RuntimeSource::Blob(_)));
+
+        settings.preferences.skip_identical_overrides = true;
+        let planner = RuntimePlanner::new(settings);
+        assert_eq!(source(&planner, "copied.cfg"), RuntimeSource::Base);
+        assert!(matches!(source(&planner, "edited.cfg"), RuntimeSource::Blob(_)));
+
+        // Cleaning drops only the unedited copy, with its reference
+        assert_eq!(planner.remove_identical_overrides("test").unwrap().pruned, vec!["copied.cfg".to_string()]);
+        assert!(!workspace.join("copied.cfg").exists());
+        assert!(workspace.join("edited.cfg").exists());
+        assert!(cache.get_refs(&copied).unwrap().is_empty());
+        assert_eq!(cache.get_refs(&edited).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_plan_sources_across_shared_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join("base.cfg"), "base").unwrap();
+        fs::write(base_dir.join("shared.cfg"), "base").unwrap();
+        fs::write(base_dir.join("profile.cfg"), "base").unwrap();
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        let shared_dir = settings.get_shared_workspace_directory();
+        fs::create_dir_all(&shared_dir).unwrap();
+        fs::write(shared_dir.join("shared.cfg"), "shared").unwrap();
+        fs::write(shared_dir.join("profile.cfg"), "shared").unwrap();
+        fs::write(shared_dir.join("common.asi"), "shared").unwrap();
+
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        fs::write(profile.workspace_dir.join("profile.cfg"), "profile").unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(profile.workspace_dir.join("profile.cfg")).unwrap();
+        cache.add_ref(&blob, "test", "profile.cfg").unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let plan = planner.compute_plan("test").unwrap();
+        let source_of = |rel_path: &str| plan.entries.iter().find(|entry| entry.rel_path == rel_path).unwrap();
+
+        assert_eq!(source_of("base.cfg").source, RuntimeSource::Base);
+        assert_eq!(source_of("shared.cfg").source, RuntimeSource::Shared);
+        assert!(source_of("shared.cfg").is_override);
+        assert_eq!(source_of("common.asi").source, RuntimeSource::Shared);
+        assert!(!source_of("common.asi").has_base);
+        assert_eq!(source_of("profile.cfg").source, RuntimeSource::Blob(blob.hash.to_hex().to_string()));
+        assert_eq!((plan.base_files, plan.shared_files, plan.blob_files), (1, 2, 1));
+
+        let explanation = planner.explain("test", "shared.cfg").unwrap();
+        assert!(explanation.has_shared && explanation.has_base && explanation.is_override);
+    }
+
+    #[test]
+    fn test_plan_entries_are_sorted_and_stable() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        for dir in ["models", "data/maps", "audio"] {
+            fs::create_dir_all(base_dir.join(dir)).unwrap();
+        }
+        for file in ["gta_sa.exe", "models/gta3.img", "data/maps/LA.ide", "data/gta.dat", "audio/sfx.dat", "Audio.txt"] {
+            fs::write(base_dir.join(file), file).unwrap();
+        }
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let first = planner.compute_plan("test").unwrap();
+        let second = planner.compute_plan("test").unwrap();
+
+        let paths = |plan: &RuntimePlan| plan.entries.iter().map(|entry| entry.rel_path.clone()).collect::<Vec<_>>();
+        let mut sorted = paths(&first);
+        sorted.sort();
+        assert_eq!(paths(&first), sorted);
+        assert_eq!(paths(&first), paths(&second));
+        assert_eq!(first.total_files, 6);
+    }
+
+    #[test]
+    fn test_profile_digest_tracks_content_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        let profiles = ProfileManager::new(data_root.join("profiles"));
+        let profile = profiles.create_profile("test".to_string()).unwrap();
+        profiles.create_profile("copy".to_string()).unwrap();
+        let mod_file = profile.workspace_dir.join("mod.asi");
+        fs::write(&mod_file, "v1").unwrap();
+
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(&mod_file).unwrap();
+        cache.add_ref(&blob, "test", "mod.asi").unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let digest = planner.profile_digest("test").unwrap();
+        assert_eq!(planner.profile_digest("test").unwrap(), digest);
+        assert_ne!(planner.profile_digest("copy").unwrap(), digest);
+
+        // Editing the file points its reference at a new blob
+        fs::write(&mod_file, "v2").unwrap();
+        let edited = cache.ensure_blob(&mod_file).unwrap();
+        cache.remove_existing_ref("test", "mod.asi").unwrap();
+        cache.add_ref(&edited, "test", "mod.asi").unwrap();
+        assert_ne!(planner.profile_digest("test").unwrap(), digest);
+    }
+
+    #[test]
+    fn test_base_changes_are_detected() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(&data_root).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "dat").unwrap();
+
+        let mut settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        settings.preferences.excluded_base_globs = vec!["movies".to_string()];
+        ProfileManager::new(data_root.join("profiles")).create_profile("test".to_string()).unwrap();
+        let planner = RuntimePlanner::new(settings);
+
+        // Nothing recorded yet, so nothing to compare against
+        let check = planner.check_base_fingerprint("test", &base_dir).unwrap();
+        assert!(check.recorded.is_none() && !check.changed);
+
+        let mut plan = planner.compute_plan("test").unwrap();
+        let recorded = planner.record_base_fingerprint(&mut plan, &base_dir).unwrap();
+        planner.save_plan(&plan).unwrap();
+        assert_eq!(recorded.file_count, 2);
+        assert!(!planner.check_base_fingerprint("test", &base_dir).unwrap().changed);
+
+        // Excluded files don't reach runtimes, so changing them isn't a base change
+        fs::create_dir_all(base_dir.join("movies")).unwrap();
+        fs::write(base_dir.join("movies/intro.mpg"), "intro").unwrap();
+        assert!(!planner.check_base_fingerprint("test", &base_dir).unwrap().changed);
+
+        // A game update patches a file in place
+        fs::write(base_dir.join("data/gta.dat"), "patched dat").unwrap();
+        let check = planner.check_base_fingerprint("test", &base_dir).unwrap();
+        assert!(check.changed);
+        assert_eq!(check.recorded, Some(recorded));
+
+        // Recording again (as a rebuild does) accepts the new base
+        let mut plan = planner.compute_plan("test").unwrap();
+        planner.record_base_fingerprint(&mut plan, &base_dir).unwrap();
+        planner.save_plan(&plan).unwrap();
+        assert!(!planner.check_base_fingerprint("test", &base_dir).unwrap().changed);
+    }
+
+    #[test]
+    fn test_profiles_affected_by_base_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "dat").unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "handling").unwrap();
+
+        let settings = Settings::for_wizard(base_dir.clone(), data_root.clone());
+        let cache = settings.blob_cache();
+        let profiles = ProfileManager::new(data_root.join("profiles"));
+        for name in ["modded", "tuned", "vanilla"] {
+            profiles.create_profile(name.to_string()).unwrap();
+        }
+        for (name, rel_path) in [("modded", "data/gta.dat"), ("tuned", "data/gta.dat"), ("tuned", "data/handling.cfg")] {
+            let profile = profiles.get_profile(name).unwrap().unwrap();
+            let file = profile.workspace_dir.join(rel_path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, format!("{} {}", name, rel_path)).unwrap();
+            let blob = cache.ensure_blob(&file).unwrap();
+            cache.add_ref(&blob, name, rel_path).unwrap();
+        }
+
+        // The shared workspace overrides a file for every profile
+        let shared_dir = settings.get_shared_workspace_directory();
+        fs::create_dir_all(shared_dir.join("data")).unwrap();
+        fs::write(shared_dir.join("data/handling.cfg"), "shared handling").unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let record = |name: &str| {
+            let mut plan = planner.compute_plan(name).unwrap();
+            planner.record_base_fingerprint(&mut plan, &base_dir).unwrap();
+            planner.save_plan(&plan).unwrap();
+        };
+        for name in ["modded", "tuned", "vanilla"] {
+            record(name);
+        }
+        assert_eq!(planner.changed_base_files("modded", &base_dir).unwrap(), Some(Vec::new()));
+        assert!(planner.profiles_affected_by_base_changes().unwrap().is_empty());
+
+        // A patch changes both overridden files, and adds a file
+        fs::write(base_dir.join("data/gta.dat"), "patched dat").unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "patched handling").unwrap();
+        fs::write(base_dir.join("data/new.dat"), "new").unwrap();
+        assert_eq!(planner.changed_base_files("modded", &base_dir).unwrap(), Some(vec![
+            "data/gta.dat".to_string(), "data/handling.cfg".to_string(), "data/new.dat".to_string(),
+        ]));
+
+        // Rebuilding one profile doesn't hide the update from the others
+        record("vanilla");
+        assert_eq!(planner.changed_base_files("vanilla", &base_dir).unwrap(), Some(Vec::new()));
+
+        let impacts = planner.profiles_affected_by_base_changes().unwrap();
+        assert_eq!(impacts.len(), 2);
+        assert_eq!(impacts[0].rel_path, "data/gta.dat");
+        assert_eq!(impacts[0].profiles, vec!["modded".to_string(), "tuned".to_string()]);
+        assert_eq!(impacts[1].rel_path, "data/handling.cfg");
+        assert_eq!(impacts[1].profiles, vec!["modded".to_string(), "tuned".to_string()]);
+        assert_eq!(cache.profiles_overriding("data/handling.cfg").unwrap(), vec!["tuned".to_string()]);
+    }
+
+    #[test]
+    fn test_largest_build_copy_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+
+        let mut settings = Settings::for_wizard(base_dir, data_root.clone());
+        settings.preferences.protected_globs = vec!["*.set".to_string()];
+        let profiles = ProfileManager::new(data_root.join("profiles"));
+        let small = profiles.create_profile("small".to_string()).unwrap();
+        let large = profiles.create_profile("large".to_string()).unwrap();
+        profiles.create_profile("unplanned".to_string()).unwrap();
+        fs::write(small.workspace_dir.join("gta_sa.set"), "set").unwrap();
+        fs::write(large.workspace_dir.join("mod.asi"), "a much larger mod").unwrap();
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(large.workspace_dir.join("mod.asi")).unwrap();
+        cache.add_ref(&blob, "large", "mod.asi").unwrap();
+
+        let planner = RuntimePlanner::new(settings.clone());
+        assert_eq!(planner.largest_build_copy_size().unwrap(), 0);
+        planner.save_plan(&planner.compute_plan("small").unwrap()).unwrap();
+        planner.save_plan(&planner.compute_plan("large").unwrap()).unwrap();
+
+        // Hardlinked files take no space: only the protected file is copied
+        assert_eq!(planner.largest_build_copy_size().unwrap(), 3);
+
+        // Copy extensions are copied too, base files included
+        settings.preferences.copy_extensions = vec![".asi".to_string(), "exe".to_string()];
+        let planner = RuntimePlanner::new(settings);
+        assert_eq!(planner.largest_build_copy_size().unwrap(), 17 + 4);
+    }
+
+    #[test]
+    fn test_excluded_base_files_are_left_out_of_the_plan() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("_CommonRedist")).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+        fs::write(base_dir.join("unins000.exe"), "uninstall").unwrap();
+        fs::write(base_dir.join("_CommonRedist").join("vc_redist.x86.exe"), "redist").unwrap();
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let plan = planner.compute_plan("test").unwrap();
+        assert_eq!(plan.total_files, 1);
+        assert_eq!(plan.excluded_files, 2);
+
+        let explanation = planner.explain("test", "unins000.exe").unwrap();
+        assert!(explanation.has_base && explanation.is_excluded);
+        assert_eq!(explanation.source, None);
+    }
+
+    #[test]
+    fn test_compute_plan_rejects_missing_blobs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "base").unwrap();
+
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+        fs::write(profile.workspace_dir.join("mod.asi"), "mod").unwrap();
+
+        let cache = BlobCache::new(settings.get_cache_directory());
+        let blob = cache.ensure_blob(profile.workspace_dir.join("mod.asi")).unwrap();
+        cache.add_ref(&blob, "test", "mod.asi").unwrap();
+
+        let planner = RuntimePlanner::new(settings);
+        let plan = planner.compute_plan("test").unwrap();
+        assert_eq!(plan.blob_files, 1);
+
+        // A blob deleted behind the index's back fails the plan with the affected file
+        fs::remove_file(&blob.path).unwrap();
+        let error = planner.compute_plan("test").unwrap_err().to_string();
+        assert!(error.contains("missing from the blob cache"));
+        assert!(error.contains("mod.asi"));
+    }
+}
@@ -0,0 +1,45 @@
+/// Command-line flag that starts the app in safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Environment variable that starts the app in safe mode when set to anything but "0" or empty
+pub const SAFE_MODE_ENV: &str = "DELTARUNTIME_SAFE_MODE";
+
+/// Safe mode starts the app without touching the filesystem on its own, so a badly broken configuration
+/// (corrupt settings, an inaccessible data root) can be inspected and repaired from the UI
+/// Settings are loaded without self-healing, no watchers or background maintenance are started,
+/// and only diagnostic and repair commands are registered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SafeMode(pub bool);
+
+impl SafeMode {
+    /// Whether this launch asked for safe mode, through the command line or the environment
+    pub fn from_launch() -> Self {
+        Self::detect(std::env::args().skip(1), std::env::var(SAFE_MODE_ENV).ok().as_deref())
+    }
+
+    fn detect(mut args: impl Iterator<Item = String>, env_value: Option<&str>) -> Self {
+        let from_env = env_value.is_some_and(|value| !matches!(value.trim(), "" | "0"));
+        Self(from_env || args.any(|arg| arg == SAFE_MODE_FLAG))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_safe_mode() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter();
+
+        assert!(!SafeMode::detect(args(&[]), None).is_enabled());
+        assert!(SafeMode::detect(args(&["--safe-mode"]), None).is_enabled());
+        assert!(!SafeMode::detect(args(&["--safe"]), None).is_enabled());
+        assert!(SafeMode::detect(args(&[]), Some("1")).is_enabled());
+        assert!(!SafeMode::detect(args(&[]), Some("0")).is_enabled());
+        assert!(!SafeMode::detect(args(&[]), Some("")).is_enabled());
+    }
+}
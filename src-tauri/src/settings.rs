@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
 use std::fs;
-use crate::path_utils::{get_drive_letter, is_ntfs_volume, get_free_space, format_size};
+use crate::path_utils::{compile_globs, get_drive_letter, is_ntfs_volume, get_free_space, format_size, normalize_path, relative_path_from, is_within};
 use tracing::{info, warn};
+use crate::blob_cache::{BlobCache, HashMode};
 
 /// Application settings schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +18,28 @@ pub struct Settings {
     /// Root directory for all runtime data
     pub data_root: PathBuf,
     
+    /// Blob cache location (defaults to `data_root/cache` when unset)
+    #[serde(default)]
+    pub cache_root: Option<PathBuf>,
+    
+    /// Where relocatable transient files go, such as staging copies of new blobs (defaults to `data_root/tmp` when unset)
+    /// Best kept on the cache's volume; elsewhere, staged files take an extra copy to move into the cache
+    #[serde(default)]
+    pub temp_root: Option<PathBuf>,
+    
+    /// Namespace for this data root's references when several data roots share one `cache_root`
+    /// Each data root sharing a cache needs its own namespace, and all of them must be on the cache's volume
+    #[serde(default)]
+    pub cache_namespace: Option<String>,
+    
     /// Overlay mode (currently only "hardlink" supported)
     pub overlay_mode: String,
     
+    /// Portable mode: store paths relative to the settings file so the setup survives drive letter changes
+    /// Paths are resolved back to absolute on load, so validation always sees real locations
+    #[serde(default)]
+    pub portable: bool,
+    
     /// Settings for the first-run wizard
     #[serde(default)]
     pub wizard: WizardSettings,
@@ -59,6 +79,169 @@ pub struct UserPreferences {
     
     /// Whether to show file operation progress
     pub show_progress: bool,
+    
+    /// Whether to follow symlinks and junctions inside workspaces
+    #[serde(default)]
+    pub follow_links: bool,
+    
+    /// Glob patterns (relative to the workspace) for files the watcher must not normalize
+    /// Protected files stay regular writable files and are copied into each runtime build.
+    /// Save games don't need protecting: they live in the profile's saves directory or shared saves pool.
+    #[serde(default)]
+    pub protected_globs: Vec<String>,
+    
+    /// How normalized workspace files are stored: "hardlink" (workspace file becomes a link to its blob)
+    /// or "copy" (workspace keeps an editable real file; the blob is still stored for runtime builds,
+    /// so every workspace file takes twice the disk space)
+    #[serde(default = "default_workspace_link_mode")]
+    pub workspace_link_mode: String,
+    
+    /// Glob patterns (relative to the base installation) for base files and directories never linked into runtimes
+    /// Matching a directory excludes everything inside it. Workspace files are never excluded.
+    #[serde(default = "default_excluded_base_globs")]
+    pub excluded_base_globs: Vec<String>,
+    
+    /// Seconds a workspace file must go unmodified before the watcher normalizes it
+    /// Keeps the watcher from hardlinking (and making read-only) a file that's still being edited
+    #[serde(default = "default_normalize_idle_seconds")]
+    pub normalize_idle_seconds: u64,
+    
+    /// How often (ms) a workspace is rescanned when the OS watcher backend is unavailable and polling is used
+    #[serde(default = "default_watcher_poll_interval_ms")]
+    pub watcher_poll_interval_ms: u64,
+    
+    /// Workspace subdirectories the watcher watches (e.g. "modloader"); empty watches the whole workspace
+    /// Limits watch handles on very large workspaces. Files elsewhere are only picked up by a manual normalize
+    #[serde(default)]
+    pub watch_roots: Vec<String>,
+    
+    /// Whether subdirectories of the watch roots are watched too, or only the files directly in them
+    #[serde(default = "default_watch_recursive")]
+    pub watch_recursive: bool,
+    
+    /// Consecutive failures to normalize a workspace file before the watcher quarantines it and stops retrying (0 never quarantines)
+    #[serde(default = "default_quarantine_after_failures")]
+    pub quarantine_after_failures: u32,
+    
+    /// File extensions (e.g. "img") always copied into runtimes instead of hardlinked, even in hardlink mode
+    /// For files the game memory-maps or writes to, which would otherwise corrupt the shared base file or blob
+    #[serde(default)]
+    pub copy_extensions: Vec<String>,
+    
+    /// Run cache maintenance (garbage collection and compaction) in the background when the app starts
+    #[serde(default)]
+    pub auto_maintain_cache: bool,
+    
+    /// Record a keyed integrity tag for each blob stored, so cache verification also catches tampered blobs
+    /// The key is random per cache, so tags only verify against the cache that recorded them
+    #[serde(default)]
+    pub integrity_tags: bool,
+    
+    /// Keep a file's original modification time when it's normalized, and on blobs copied into runtimes
+    /// A hardlink shares its blob's metadata, so the mtime is restored on the blob itself: every
+    /// workspace and runtime linking the same content sees the mtime of the file normalized last.
+    /// Per-location timestamps need the copy workspace link mode
+    #[serde(default)]
+    pub preserve_mtimes: bool,
+    
+    /// Files of at least this many MB are checked for changes by a quick fingerprint (size plus first and last MB)
+    /// and only fully re-hashed when it differs (0 disables). A heuristic: an edit confined to the middle of
+    /// a file that keeps its size can go unnoticed until the file changes again
+    #[serde(default)]
+    pub quick_fingerprint_min_mb: u64,
+    
+    /// BLAKE3 hashes (hex) of known-good game executables, on top of `Settings::KNOWN_EXE_HASHES`; validation warns
+    /// about a base executable matching none of them, e.g. a pre-modded or wrong-version install
+    #[serde(default)]
+    pub known_exe_hashes: Vec<String>,
+    
+    /// How files are hashed: "single" (one thread per file, best while many files hash at once),
+    /// "parallel" (each file across all cores, best for a few huge files) or "auto" (parallel for large files)
+    #[serde(default = "default_hash_mode")]
+    pub hash_mode: String,
+    
+    /// Plan workspace overrides whose content is identical to the base file as base files,
+    /// so builds don't replace a base link with an identical blob
+    #[serde(default)]
+    pub skip_identical_overrides: bool,
+    
+    /// How often builds and planning emit progress: "percent" (every `progress_percent` of the files)
+    /// or "interval" (at most once every `progress_interval_ms`)
+    #[serde(default = "default_progress_mode")]
+    pub progress_mode: String,
+    
+    /// Percentage of files between progress events in "percent" mode
+    #[serde(default = "default_progress_percent")]
+    pub progress_percent: f64,
+    
+    /// Minimum time (ms) between progress events in "interval" mode
+    #[serde(default = "default_progress_interval_ms")]
+    pub progress_interval_ms: u64,
+}
+
+fn default_hash_mode() -> String {
+    "single".to_string()
+}
+
+fn default_progress_mode() -> String {
+    "percent".to_string()
+}
+
+fn default_progress_percent() -> f64 {
+    1.0
+}
+
+fn default_progress_interval_ms() -> u64 {
+    100
+}
+
+fn default_normalize_idle_seconds() -> u64 {
+    1
+}
+
+fn default_watcher_poll_interval_ms() -> u64 {
+    2000
+}
+
+fn default_watch_recursive() -> bool {
+    true
+}
+
+fn default_quarantine_after_failures() -> u32 {
+    5
+}
+
+fn default_workspace_link_mode() -> String {
+    "hardlink".to_string()
+}
+
+/// Redistributable installers and uninstallers shipped with the game, which it never needs at runtime
+fn default_excluded_base_globs() -> Vec<String> {
+    ["_CommonRedist", "DirectX", "Redist", "unins*.exe", "unins*.dat"]
+        .iter()
+        .map(|glob| glob.to_string())
+        .collect()
+}
+
+impl UserPreferences {
+    /// Whether workspace files stay real copies instead of hardlinks to their blobs
+    pub fn keeps_workspace_copies(&self) -> bool {
+        self.workspace_link_mode == "copy"
+    }
+
+    /// Whether a runtime file's extension is configured to be copied instead of hardlinked
+    pub fn is_copy_extension(&self, rel_path: &str) -> bool {
+        let Some(extension) = Path::new(rel_path).extension().and_then(|extension| extension.to_str()) else {
+            return false;
+        };
+        self.copy_extensions.iter()
+            .any(|copy_extension| copy_extension.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+
+    /// Size from which files get quick fingerprints, if enabled
+    pub fn quick_fingerprint_min_size(&self) -> Option<u64> {
+        (self.quick_fingerprint_min_mb > 0).then(|| self.quick_fingerprint_min_mb * 1024 * 1024)
+    }
 }
 
 impl Default for UserPreferences {
@@ -69,16 +252,120 @@ impl Default for UserPreferences {
             auto_check_updates: true,
             max_runtime_builds: 5,
             show_progress: true,
+            follow_links: false,
+            protected_globs: Vec::new(),
+            workspace_link_mode: default_workspace_link_mode(),
+            excluded_base_globs: default_excluded_base_globs(),
+            normalize_idle_seconds: default_normalize_idle_seconds(),
+            watcher_poll_interval_ms: default_watcher_poll_interval_ms(),
+            watch_roots: Vec::new(),
+            watch_recursive: default_watch_recursive(),
+            quarantine_after_failures: default_quarantine_after_failures(),
+            copy_extensions: Vec::new(),
+            auto_maintain_cache: false,
+            integrity_tags: false,
+            preserve_mtimes: false,
+            quick_fingerprint_min_mb: 0,
+            known_exe_hashes: Vec::new(),
+            hash_mode: default_hash_mode(),
+            skip_identical_overrides: false,
+            progress_mode: default_progress_mode(),
+            progress_percent: default_progress_percent(),
+            progress_interval_ms: default_progress_interval_ms(),
+        }
+    }
+}
+
+impl UserPreferences {
+    /// Check the preferences that have a restricted set of valid values
+    fn validate_into(&self, result: &mut ValidationResult) {
+        if !matches!(self.workspace_link_mode.as_str(), "hardlink" | "copy") {
+            result.add_error(format!("Unknown workspace link mode: {}", self.workspace_link_mode));
+        }
+        if !matches!(self.hash_mode.as_str(), "single" | "parallel" | "auto") {
+            result.add_error(format!("Unknown hash mode: {}", self.hash_mode));
+        }
+        if !matches!(self.progress_mode.as_str(), "percent" | "interval") {
+            result.add_error(format!("Unknown progress mode: {}", self.progress_mode));
+        }
+        for root in &self.watch_roots {
+            let root_path = Path::new(root);
+            if root.trim().is_empty() || root_path.is_absolute() || root_path.components().any(|c| c == std::path::Component::ParentDir) {
+                result.add_error(format!("Watch root must be a subdirectory of the workspace: {}", root));
+            }
+        }
+        // Checked one by one so every invalid pattern is reported, not just the first
+        for (kind, globs) in [("Protected", &self.protected_globs), ("Excluded base", &self.excluded_base_globs)] {
+            for glob in globs {
+                if let Err(e) = compile_globs(std::slice::from_ref(glob)) {
+                    result.add_error(format!("{} glob is not valid: {:#}", kind, e));
+                }
+            }
+        }
+        for hash in &self.known_exe_hashes {
+            if blake3::Hash::from_hex(hash.trim()).is_err() {
+                result.add_error(format!("Known executable hash is not a BLAKE3 hex hash: {}", hash));
+            }
+        }
+        if !(self.progress_percent > 0.0 && self.progress_percent <= 100.0) {
+            result.add_error(format!("Progress percentage must be between 0 and 100: {}", self.progress_percent));
         }
     }
 }
 
+/// The base game executable's hash, checked against `UserPreferences::known_exe_hashes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseIntegrityCheck {
+    pub exe_path: PathBuf,
+    /// BLAKE3 hash (hex) of the executable
+    pub exe_hash: String,
+    /// Whether the hash is a known-good one (None when there are no known hashes, built in or configured)
+    pub matches_known: Option<bool>,
+}
+
+/// Preferences a single profile can override, e.g. a longer idle time for a profile with huge archives
+/// Unset fields fall back to the global preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSettingsOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_idle_seconds: Option<u64>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watcher_poll_interval_ms: Option<u64>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_roots: Option<Vec<String>>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_recursive: Option<bool>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_extensions: Option<Vec<String>>,
+}
+
 impl Settings {
     /// Current schema version
     pub const CURRENT_SCHEMA: u32 = 1;
     
     /// Default settings file name
     pub const SETTINGS_FILE: &'static str = "settings.json";
+    
+    /// Game executable expected at the root of the base installation and built runtimes
+    pub const GAME_EXECUTABLE: &'static str = "gta_sa.exe";
+
+    /// BLAKE3 hashes (hex) of known-good game executables, always checked along with the configured ones
+    /// Only hashes taken from untouched releases belong here
+    pub const KNOWN_EXE_HASHES: &'static [&'static str] = &[];
+    
+    /// File in the config directory that records the chosen data root
+    pub const DATA_ROOT_POINTER_FILE: &'static str = "data_root.txt";
+    
+    /// Directory under the data root holding the shared workspace
+    pub const SHARED_WORKSPACE_DIR: &'static str = "shared-workspace";
+    
+    /// Oldest wizard version whose setup is still complete
+    /// Raise this to the current version when a release adds a required setup step, so earlier setups are prompted again
+    pub const MIN_WIZARD_VERSION: &'static str = "0.1.0";
 
     /// Create new default settings
     pub fn new() -> Self {
@@ -86,7 +373,11 @@ impl Settings {
             schema: Self::CURRENT_SCHEMA,
             base_path: PathBuf::new(),
             data_root: PathBuf::new(),
+            cache_root: None,
+            temp_root: None,
+            cache_namespace: None,
             overlay_mode: "hardlink".to_string(),
+            portable: false,
             wizard: WizardSettings::default(),
             preferences: UserPreferences::default(),
         }
@@ -114,6 +405,11 @@ impl Settings {
         // Migrate settings if needed
         settings = settings.migrate()?;
         
+        // Resolve portable (relative) paths against the settings file location
+        if let (true, Some(settings_dir)) = (settings.portable, path.parent()) {
+            settings.resolve_relative_paths(settings_dir)?;
+        }
+        
         info!("Settings loaded successfully");
         info!("Base path: {}", settings.base_path.display());
         info!("Data root: {}", settings.data_root.display());
@@ -132,7 +428,13 @@ impl Settings {
                 .with_context(|| format!("Failed to create settings directory: {}", parent.display()))?;
         }
         
-        let content = serde_json::to_string_pretty(self)
+        // In portable mode, store paths relative to the settings file where possible
+        let to_save = match (self.portable, path.parent()) {
+            (true, Some(settings_dir)) => self.with_paths_relative_to(settings_dir),
+            _ => self.clone(),
+        };
+        
+        let content = serde_json::to_string_pretty(&to_save)
             .context("Failed to serialize settings")?;
         
         fs::write(path, content)
@@ -142,6 +444,38 @@ impl Settings {
         Ok(())
     }
 
+    /// Copy of these settings with paths made relative to `dir` (paths on other volumes stay absolute)
+    fn with_paths_relative_to(&self, dir: &Path) -> Self {
+        let relative = |path: &PathBuf| relative_path_from(path, dir).unwrap_or_else(|| path.clone());
+        
+        let mut settings = self.clone();
+        settings.base_path = relative(&self.base_path);
+        settings.data_root = relative(&self.data_root);
+        settings.cache_root = self.cache_root.as_ref().map(relative);
+        settings.temp_root = self.temp_root.as_ref().map(relative);
+        settings
+    }
+
+    /// Resolve any relative paths against `dir`
+    fn resolve_relative_paths(&mut self, dir: &Path) -> Result<()> {
+        let resolve = |path: &mut PathBuf| -> Result<()> {
+            if !path.as_os_str().is_empty() && path.is_relative() {
+                *path = normalize_path(dir.join(&*path))?;
+            }
+            Ok(())
+        };
+        
+        resolve(&mut self.base_path)?;
+        resolve(&mut self.data_root)?;
+        if let Some(cache_root) = self.cache_root.as_mut() {
+            resolve(cache_root)?;
+        }
+        if let Some(temp_root) = self.temp_root.as_mut() {
+            resolve(temp_root)?;
+        }
+        Ok(())
+    }
+
     /// Load settings from the data root directory
     pub fn load_from_data_root<P: AsRef<std::path::Path>>(data_root: P) -> Result<Self> {
         let settings_path = data_root.as_ref().join(Self::SETTINGS_FILE);
@@ -154,15 +488,68 @@ impl Settings {
         self.save(settings_path)
     }
 
+    /// Get the application config directory (shared with logging)
+    pub fn config_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("DeltaRuntime"))
+    }
+
+    /// Record the data root in the given config directory so it can be found on next launch
+    pub fn save_data_root_pointer_to(&self, config_dir: &Path) -> Result<()> {
+        fs::create_dir_all(config_dir)
+            .with_context(|| format!("Failed to create config directory: {}", config_dir.display()))?;
+        
+        let pointer_path = config_dir.join(Self::DATA_ROOT_POINTER_FILE);
+        fs::write(&pointer_path, self.data_root.to_string_lossy().as_bytes())
+            .with_context(|| format!("Failed to write data root pointer: {}", pointer_path.display()))?;
+        
+        info!("Saved data root pointer to: {}", pointer_path.display());
+        Ok(())
+    }
+
+    /// Record the data root in the application config directory
+    pub fn save_data_root_pointer(&self) -> Result<()> {
+        let config_dir = Self::config_dir()
+            .context("Failed to get config directory")?;
+        self.save_data_root_pointer_to(&config_dir)
+    }
+
+    /// Load settings from the data root recorded in the given config directory
+    pub fn load_from_pointer(config_dir: &Path) -> Option<Self> {
+        let pointer_path = config_dir.join(Self::DATA_ROOT_POINTER_FILE);
+        let data_root = fs::read_to_string(&pointer_path).ok()?;
+        let data_root = PathBuf::from(data_root.trim());
+        
+        match Self::load_from_data_root(&data_root) {
+            Ok(settings) => {
+                info!("Found existing settings via pointer at: {}", pointer_path.display());
+                Some(settings)
+            }
+            Err(e) => {
+                warn!("Data root pointer {} is stale: {}", data_root.display(), e);
+                None
+            }
+        }
+    }
+
     /// Try to find and load existing settings
+    /// Checks the data root pointer in the config directory first, then common locations
     pub fn try_load_existing() -> Option<Self> {
+        if let Some(settings) = Self::config_dir().and_then(|dir| Self::load_from_pointer(&dir)) {
+            return Some(settings);
+        }
+
         // Try common locations for existing settings
-        let possible_locations = vec![
+        let mut possible_locations = vec![
             std::env::current_dir().ok()?.join("DeltaRuntime").join(Self::SETTINGS_FILE),
             PathBuf::from("C:\\DeltaRuntime").join(Self::SETTINGS_FILE),
             PathBuf::from("D:\\DeltaRuntime").join(Self::SETTINGS_FILE),
             PathBuf::from("E:\\DeltaRuntime").join(Self::SETTINGS_FILE),
         ];
+        
+        // Portable installs keep their data next to the executable
+        if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+            possible_locations.insert(1, exe_dir.join("DeltaRuntime").join(Self::SETTINGS_FILE));
+        }
 
         for location in possible_locations {
             if location.exists() {
@@ -199,6 +586,28 @@ impl Settings {
         Ok(self)
     }
 
+    /// Known-good game executable hashes: the built-in ones, then the configured ones
+    pub fn known_exe_hashes(&self) -> Vec<&str> {
+        Self::KNOWN_EXE_HASHES.iter()
+            .copied()
+            .chain(self.preferences.known_exe_hashes.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Hash the game executable of the base installation at `base_path` (the global base or a profile's
+    /// override) and compare it with the known-good hashes
+    pub fn verify_base_integrity(&self, base_path: &Path) -> Result<BaseIntegrityCheck> {
+        let exe_path = base_path.join(Self::GAME_EXECUTABLE);
+        let exe_hash = BlobCache::hash_file(&exe_path)
+            .with_context(|| format!("Failed to hash game executable: {}", exe_path.display()))?
+            .to_hex()
+            .to_string();
+        let known = self.known_exe_hashes();
+        let matches_known = (!known.is_empty()).then(|| known.iter().any(|hash| hash.trim().eq_ignore_ascii_case(&exe_hash)));
+
+        Ok(BaseIntegrityCheck { exe_path, exe_hash, matches_known })
+    }
+
     /// Validate that the settings are consistent and paths exist
     pub fn validate(&self) -> Result<ValidationResult> {
         let mut result = ValidationResult::new();
@@ -208,11 +617,26 @@ impl Settings {
             result.add_error(format!("Base game path does not exist: {}", self.base_path.display()));
         } else if !self.base_path.is_dir() {
             result.add_error(format!("Base path is not a directory: {}", self.base_path.display()));
+        } else if let Err(e) = fs::read_dir(&self.base_path) {
+            // Read access is all that's needed; the base may be a read-only mount
+            result.add_error(format!("Base game path is not readable: {} ({})", self.base_path.display(), e));
         } else {
             // Check for game executable (GTA:SA as example)
-            let gta_exe = self.base_path.join("gta_sa.exe");
+            let gta_exe = self.base_path.join(Self::GAME_EXECUTABLE);
             if !gta_exe.exists() {
                 result.add_warning(format!("Game executable not found at: {}", gta_exe.display()));
+            } else if let Err(e) = fs::File::open(&gta_exe) {
+                result.add_warning(format!("Game executable is not readable: {} ({})", gta_exe.display(), e));
+            } else if !self.known_exe_hashes().is_empty() {
+                // Only a warning: legitimate variants (patches, other releases) aren't all known
+                match self.verify_base_integrity(&self.base_path) {
+                    Ok(check) if check.matches_known == Some(false) => result.add_warning(format!(
+                        "Game executable matches no known version (hash {}); the install may be pre-modded or a different version",
+                        check.exe_hash
+                    )),
+                    Ok(_) => {}
+                    Err(e) => result.add_warning(format!("Could not verify the game executable: {}", e)),
+                }
             }
         }
 
@@ -223,6 +647,19 @@ impl Settings {
             result.add_error(format!("Data root is not a directory: {}", self.data_root.display()));
         }
 
+        // Nesting either directory in the other makes the data directory look like game files (or vice versa)
+        if is_within(&self.data_root, &self.base_path) {
+            result.add_error(format!("Data root must not be inside the base game path: {}", self.data_root.display()));
+        } else if is_within(&self.base_path, &self.data_root) {
+            result.add_error(format!("Base game path must not be inside the data root: {}", self.base_path.display()));
+        }
+
+        // Runtimes are only ever built from hardlinks
+        if self.overlay_mode != "hardlink" {
+            result.add_error(format!("Unsupported overlay mode: {} (only \"hardlink\" is supported)", self.overlay_mode));
+        }
+        self.preferences.validate_into(&mut result);
+
         // Check if base and data root are on the same NTFS volume
         match (get_drive_letter(&self.base_path), get_drive_letter(&self.data_root)) {
             (Ok(Some(base_drive)), Ok(Some(data_drive))) => {
@@ -244,6 +681,32 @@ impl Settings {
             }
         }
 
+        // A custom cache root must share the data root's volume so blobs can be hardlinked into workspaces and runtimes
+        if let Some(cache_root) = &self.cache_root {
+            if let (Ok(Some(cache_drive)), Ok(Some(data_drive))) = (get_drive_letter(cache_root), get_drive_letter(&self.data_root)) {
+                if cache_drive != data_drive {
+                    result.add_error(format!("Cache root and data root must be on the same drive for hardlinks. Cache: {}, Data: {}", cache_drive, data_drive));
+                }
+            }
+        }
+
+        if let Some(temp_root) = &self.temp_root {
+            if let (Ok(Some(temp_drive)), Ok(Some(cache_drive))) = (get_drive_letter(temp_root), get_drive_letter(self.get_cache_directory())) {
+                if temp_drive != cache_drive {
+                    result.add_warning(format!("Temp root is on a different drive than the cache, so new blobs are copied twice. Temp: {}, Cache: {}", temp_drive, cache_drive));
+                }
+            }
+        }
+
+        // Namespaces become a prefix of the index's profile names
+        if let Some(namespace) = &self.cache_namespace {
+            if namespace.trim().is_empty() || namespace.contains(['/', '\\']) {
+                result.add_error(format!("Invalid cache namespace '{}': must be non-empty and contain no path separators", namespace));
+            }
+        } else if self.cache_root.is_some() {
+            result.add_warning("Custom cache root has no namespace; give each data root sharing it a distinct cache_namespace".to_string());
+        }
+
         // Check free space
         if let Ok(free_space) = get_free_space(&self.data_root) {
             if free_space < 1024 * 1024 * 1024 {  // Less than 1GB
@@ -254,18 +717,85 @@ impl Settings {
         Ok(result)
     }
 
+    /// These settings with a profile's overrides applied, for operating on that profile
+    pub fn with_overrides(&self, overrides: &ProfileSettingsOverrides) -> Settings {
+        let mut settings = self.clone();
+        let preferences = &mut settings.preferences;
+        if let Some(normalize_idle_seconds) = overrides.normalize_idle_seconds {
+            preferences.normalize_idle_seconds = normalize_idle_seconds;
+        }
+        if let Some(watcher_poll_interval_ms) = overrides.watcher_poll_interval_ms {
+            preferences.watcher_poll_interval_ms = watcher_poll_interval_ms;
+        }
+        if let Some(watch_roots) = &overrides.watch_roots {
+            preferences.watch_roots = watch_roots.clone();
+        }
+        if let Some(watch_recursive) = overrides.watch_recursive {
+            preferences.watch_recursive = watch_recursive;
+        }
+        if let Some(copy_extensions) = &overrides.copy_extensions {
+            preferences.copy_extensions = copy_extensions.clone();
+        }
+        settings
+    }
+
+    /// Validate a profile's overrides the same way the global preferences are validated
+    pub fn validate_overrides(&self, overrides: &ProfileSettingsOverrides) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        self.with_overrides(overrides).preferences.validate_into(&mut result);
+        result
+    }
+
+    /// Validate settings before completing setup, failing on any fatal error
+    pub fn validate_for_setup(&self) -> Result<ValidationResult> {
+        let result = self.validate()?;
+        if !result.is_valid() {
+            anyhow::bail!("Setup validation failed: {}", result.errors.join("; "));
+        }
+        Ok(result)
+    }
+
     /// Get the expected directory structure under data_root
     pub fn get_data_structure(&self) -> Vec<PathBuf> {
         vec![
-            self.data_root.join("cache"),
-            self.data_root.join("cache").join("blobs"),
+            self.get_cache_directory(),
+            self.get_cache_directory().join("blobs"),
             self.data_root.join("profiles"),
             self.data_root.join("runtimes"),
             self.data_root.join("logs"),
-            self.data_root.join("tmp"),
+            self.get_temp_directory(),
         ]
     }
 
+    /// Report whether each expected directory under data_root still exists
+    pub fn check_data_structure(&self) -> Vec<(PathBuf, bool)> {
+        self.get_data_structure()
+            .into_iter()
+            .map(|dir| {
+                let exists = dir.is_dir();
+                (dir, exists)
+            })
+            .collect()
+    }
+
+    /// Recreate any expected directories that are missing, leaving existing ones and the cache index untouched
+    /// Returns the directories that were recreated
+    pub fn ensure_data_structure(&self) -> Result<Vec<PathBuf>> {
+        let mut recreated = Vec::new();
+        for (dir, exists) in self.check_data_structure() {
+            if exists {
+                continue;
+            }
+            
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+            warn!("Recreated missing directory: {}", dir.display());
+            recreated.push(dir);
+        }
+        
+        Ok(recreated)
+    }
+
     /// Create the directory structure under data_root
     pub fn create_data_structure(&self) -> Result<()> {
         info!("Creating data directory structure at: {}", self.data_root.display());
@@ -277,7 +807,7 @@ impl Settings {
         }
 
         // Create index.json in cache directory
-        let cache_index = self.data_root.join("cache").join("index.json");
+        let cache_index = self.get_cache_directory().join("index.json");
         if !cache_index.exists() {
             let empty_index = serde_json::json!({
                 "version": 1,
@@ -287,6 +817,8 @@ impl Settings {
                 .with_context(|| format!("Failed to create cache index: {}", cache_index.display()))?;
             info!("Created cache index: {}", cache_index.display());
         }
+        self.blob_cache().ensure_meta()
+            .context("Failed to create cache metadata")?;
 
         info!("Data directory structure created successfully");
         Ok(())
@@ -300,19 +832,149 @@ impl Settings {
         info!("Wizard marked as completed");
     }
 
+    /// Mark the wizard as not completed so it is shown again
+    /// When `clear_paths` is set the base path and data root are forgotten too; data on disk is untouched
+    pub fn reset_wizard(&mut self, clear_paths: bool) {
+        self.wizard = WizardSettings::default();
+        if clear_paths {
+            self.base_path = PathBuf::new();
+            self.data_root = PathBuf::new();
+        }
+        info!("Wizard reset (paths cleared: {})", clear_paths);
+    }
+
     /// Check if the wizard needs to be shown
     pub fn needs_wizard(&self) -> bool {
+        self.needs_wizard_for_version(Self::MIN_WIZARD_VERSION)
+    }
+
+    /// Check if the wizard needs to be shown, treating setups completed by a wizard older than `min_version` as incomplete
+    pub fn needs_wizard_for_version(&self, min_version: &str) -> bool {
         !self.wizard.completed || 
         self.base_path.as_os_str().is_empty() || 
-        self.data_root.as_os_str().is_empty()
+        self.data_root.as_os_str().is_empty() ||
+        self.wizard_outdated(min_version)
+    }
+
+    /// Whether the wizard was completed by a version older than `min_version`
+    /// A missing or unreadable version counts as outdated
+    pub fn wizard_outdated(&self, min_version: &str) -> bool {
+        match self.wizard.wizard_version.as_deref().and_then(parse_version) {
+            Some(completed) => parse_version(min_version).is_some_and(|min| completed < min),
+            None => true,
+        }
+    }
+
+    /// Shared workspace layered beneath every profile's workspace, for mods common to all profiles
+    /// Optional: profiles see no shared files until it's created
+    pub fn get_shared_workspace_directory(&self) -> PathBuf {
+        self.data_root.join(Self::SHARED_WORKSPACE_DIR)
     }
 
     /// Get the cache directory path
+    /// This is the single source of truth for where blobs live
     pub fn get_cache_directory(&self) -> PathBuf {
-        self.data_root.join("cache")
+        self.cache_root.clone()
+            .unwrap_or_else(|| self.data_root.join("cache"))
+    }
+
+    /// Get the directory for relocatable transient files
+    pub fn get_temp_directory(&self) -> PathBuf {
+        self.temp_root.clone()
+            .unwrap_or_else(|| self.data_root.join("tmp"))
+    }
+
+    /// Open the blob cache for this data root, recording references under its namespace
+    pub fn blob_cache(&self) -> BlobCache {
+        BlobCache::new(self.get_cache_directory())
+            .with_namespace(self.cache_namespace.clone())
+            .with_integrity_tags(self.preferences.integrity_tags)
+            .with_preserve_mtimes(self.preferences.preserve_mtimes)
+            .with_quick_fingerprints(self.preferences.quick_fingerprint_min_size())
+            .with_hash_mode(HashMode::from_name(&self.preferences.hash_mode))
+            .with_temp_dir(Some(self.get_temp_directory()))
+    }
+
+    /// Move the data root to `new_root` and persist the re-pointed settings there
+    ///
+    /// The tree is moved with a rename, so only moves on the same drive are performed (hardlinks stay intact).
+    /// For another drive, move the folder manually first; a `new_root` that already holds settings is just re-pointed.
+    pub fn relocate_data_root(&self, new_root: PathBuf) -> Result<DataRootRelocation> {
+        let old_root = self.data_root.clone();
+        if new_root == old_root {
+            anyhow::bail!("New data root is the same as the current one: {}", new_root.display());
+        }
+        if new_root.starts_with(&old_root) {
+            anyhow::bail!("New data root cannot be inside the current one: {}", new_root.display());
+        }
+
+        let mut settings = self.clone();
+        settings.data_root = new_root.clone();
+        // A cache kept inside the data root moves with it
+        if let Some(rest) = self.cache_root.as_ref().and_then(|cache_root| cache_root.strip_prefix(&old_root).ok()) {
+            settings.cache_root = Some(new_root.join(rest));
+        }
+
+        let validation = settings.validate_for_setup()?;
+        for warning in &validation.warnings {
+            warn!("Relocation warning: {}", warning);
+        }
+
+        let changes_drive = match (get_drive_letter(&old_root), get_drive_letter(&new_root)) {
+            (Ok(Some(old_drive)), Ok(Some(new_drive))) => old_drive != new_drive,
+            _ => false,
+        };
+
+        let already_moved = new_root.join(Self::SETTINGS_FILE).exists();
+        if already_moved {
+            info!("Data root already moved to {}, re-pointing settings", new_root.display());
+        } else {
+            if changes_drive {
+                anyhow::bail!(
+                    "Moving the data root to another drive would break the hardlinks in workspaces and runtimes. Move {} to {} manually, then relocate again to re-point settings",
+                    old_root.display(),
+                    new_root.display()
+                );
+            }
+            if new_root.exists() {
+                anyhow::bail!("New data root already exists: {}", new_root.display());
+            }
+            if let Some(parent) = new_root.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create parent directory: {}", parent.display()))?;
+            }
+
+            info!("Moving data root from {} to {}", old_root.display(), new_root.display());
+            fs::rename(&old_root, &new_root)
+                .with_context(|| format!("Failed to move data root from {} to {}", old_root.display(), new_root.display()))?;
+        }
+
+        settings.save_to_data_root()?;
+
+        // Runtimes hardlink the base installation, which a manual move to another drive can't keep
+        if changes_drive {
+            warn!("Data root changed drives; existing runtimes must be rebuilt");
+        }
+
+        Ok(DataRootRelocation {
+            settings,
+            moved: !already_moved,
+            runtimes_need_rebuild: changes_drive,
+        })
     }
 }
 
+/// Outcome of relocating the data root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataRootRelocation {
+    /// Settings pointing at the new data root
+    pub settings: Settings,
+    /// Whether the tree was moved (false if it had already been moved and was only re-pointed)
+    pub moved: bool,
+    /// Whether existing runtimes must be rebuilt because the data root changed drives
+    pub runtimes_need_rebuild: bool,
+}
+
 /// Result of settings validation
 #[derive(Debug, Clone)]
 pub struct ValidationResult {
@@ -345,6 +1007,19 @@ impl ValidationResult {
     }
 }
 
+/// Parse a dotted numeric version such as "1.2.0", ignoring any pre-release or build suffix
+/// Trailing zero components are dropped so "1.2" and "1.2.0" compare equal
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,6 +1058,295 @@ mod tests {
         assert_eq!(loaded.data_root, settings.data_root);
     }
 
+    #[test]
+    fn test_data_root_pointer_discovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("config");
+        let data_root = temp_dir.path().join("F").join("Games").join("DeltaRuntime");
+
+        let mut settings = Settings::new();
+        settings.base_path = PathBuf::from("C:\\Games\\GTA San Andreas");
+        settings.data_root = data_root.clone();
+        settings.save_to_data_root().unwrap();
+
+        // Nothing is discovered before the pointer exists
+        assert!(Settings::load_from_pointer(&config_dir).is_none());
+
+        settings.save_data_root_pointer_to(&config_dir).unwrap();
+        let discovered = Settings::load_from_pointer(&config_dir).unwrap();
+        assert_eq!(discovered.data_root, data_root);
+        assert_eq!(discovered.base_path, settings.base_path);
+    }
+
+    #[test]
+    fn test_validate_rejects_nested_base_and_data_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let games = temp_dir.path().join("Games");
+        let base_path = games.join("GTA San Andreas");
+        fs::create_dir_all(&base_path).unwrap();
+
+        let nested_errors = |base: PathBuf, data_root: PathBuf| {
+            fs::create_dir_all(&data_root).unwrap();
+            Settings::for_wizard(base, data_root).validate().unwrap()
+                .errors.into_iter()
+                .filter(|e| e.contains("must not be inside"))
+                .count()
+        };
+
+        // Data root inside the base install, and the base install inside the data root
+        assert_eq!(nested_errors(base_path.clone(), base_path.join("DeltaRuntime")), 1);
+        assert_eq!(nested_errors(games.join("DeltaRuntime").join("GTA"), games.join("DeltaRuntime")), 1);
+        assert_eq!(nested_errors(base_path.join("..").join("GTA San Andreas").join("data"), base_path.clone()), 1); // after resolving `..`
+
+        // Siblings that only share a name prefix are fine
+        assert_eq!(nested_errors(base_path.clone(), games.join("GTA San Andreas DeltaRuntime")), 0);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = Settings::for_wizard(temp_dir.path().join("base"), temp_dir.path().join("data"));
+        settings.preferences.protected_globs = vec!["*.log".to_string(), "saves/[".to_string()];
+        settings.preferences.excluded_base_globs = vec!["movies/[a-".to_string()];
+
+        let glob_errors: Vec<String> = settings.validate().unwrap()
+            .errors.into_iter()
+            .filter(|e| e.contains("glob is not valid"))
+            .collect();
+        assert_eq!(glob_errors.len(), 2);
+        assert!(glob_errors[0].contains("saves/["));
+        assert!(glob_errors[1].contains("movies/[a-"));
+    }
+
+    #[test]
+    fn test_unknown_game_executable_is_a_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("GTA San Andreas");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_path).unwrap();
+        fs::create_dir_all(&data_root).unwrap();
+        fs::write(base_path.join(Settings::GAME_EXECUTABLE), "original exe").unwrap();
+        let original_hash = blake3::hash(b"original exe").to_hex().to_string();
+
+        let mut settings = Settings::for_wizard(base_path.clone(), data_root);
+        let exe_warnings = |settings: &Settings| {
+            settings.validate().unwrap().warnings.into_iter().filter(|w| w.contains("no known version")).count()
+        };
+
+        // Without known hashes nothing is compared, and the built-in ones never match a fake executable
+        let unconfigured = settings.verify_base_integrity(&base_path).unwrap().matches_known;
+        assert_eq!(unconfigured, (!settings.known_exe_hashes().is_empty()).then_some(false));
+
+        settings.preferences.known_exe_hashes = vec![blake3::hash(b"other version").to_hex().to_string(), original_hash.to_uppercase()];
+        let check = settings.verify_base_integrity(&base_path).unwrap();
+        assert_eq!(check.exe_hash, original_hash);
+        assert_eq!(check.matches_known, Some(true));
+        assert_eq!(exe_warnings(&settings), 0);
+
+        // A modded executable is flagged but doesn't make the settings invalid
+        fs::write(base_path.join(Settings::GAME_EXECUTABLE), "modded exe").unwrap();
+        assert_eq!(settings.verify_base_integrity(&base_path).unwrap().matches_known, Some(false));
+        let result = settings.validate().unwrap();
+        assert_eq!(exe_warnings(&settings), 1);
+        assert!(!result.errors.iter().any(|e| e.contains("executable")));
+
+        // A profile's own base install is checked on its own
+        let override_base = temp_dir.path().join("GTA San Andreas (original)");
+        fs::create_dir_all(&override_base).unwrap();
+        fs::write(override_base.join(Settings::GAME_EXECUTABLE), "original exe").unwrap();
+        assert_eq!(settings.verify_base_integrity(&override_base).unwrap().matches_known, Some(true));
+
+        settings.preferences.known_exe_hashes.push("not a hash".to_string());
+        assert!(settings.validate().unwrap().errors.iter().any(|e| e.contains("not a BLAKE3 hex hash")));
+    }
+
+    #[test]
+    fn test_only_hardlink_overlay_mode_is_supported() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = Settings::for_wizard(temp_dir.path().join("base"), temp_dir.path().join("DeltaRuntime"));
+        for mode in ["copy", "symlink"] {
+            settings.overlay_mode = mode.to_string();
+            assert!(settings.validate().unwrap().errors.iter().any(|e| e.contains("Unsupported overlay mode")));
+        }
+        settings.overlay_mode = "hardlink".to_string();
+        assert!(!settings.validate().unwrap().errors.iter().any(|e| e.contains("overlay mode")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_validate_rejects_cross_volume_setup() {
+        let settings = Settings::for_wizard(PathBuf::from("C:\\Games\\GTA San Andreas"), PathBuf::from("D:\\DeltaRuntime"));
+        let result = settings.validate().unwrap();
+
+        assert!(!result.is_valid());
+        assert!(result.errors.iter().any(|e| e.contains("same drive")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_setup_rejects_cross_volume_hardlink_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("GTA San Andreas");
+        fs::create_dir_all(&base_path).unwrap();
+        fs::write(base_path.join("gta_sa.exe"), "exe").unwrap();
+
+        let base_drive = get_drive_letter(&base_path).unwrap().unwrap();
+        let other_drive = if base_drive == 'Z' { 'Y' } else { 'Z' };
+        let data_root = PathBuf::from(format!("{}:\\DeltaRuntime", other_drive));
+
+        let mut settings = Settings::for_wizard(base_path, data_root);
+        assert!(settings.validate_for_setup().is_err());
+
+        // Other overlay modes aren't implemented, so they don't get around the volume requirement
+        settings.overlay_mode = "copy".to_string();
+        let error = settings.validate_for_setup().unwrap_err().to_string();
+        assert!(error.contains("Unsupported overlay mode") && error.contains("same drive"));
+    }
+
+    #[test]
+    fn test_portable_settings_survive_relocation() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("drive_e");
+        let data_root = original.join("DeltaRuntime");
+        let base_path = original.join("Games").join("GTA San Andreas");
+
+        let mut settings = Settings::for_wizard(base_path, data_root.clone());
+        settings.portable = true;
+        settings.save_to_data_root().unwrap();
+
+        // Paths are stored relative to the settings file
+        let content = fs::read_to_string(data_root.join(Settings::SETTINGS_FILE)).unwrap();
+        let raw: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert!(PathBuf::from(raw["data_root"].as_str().unwrap()).is_relative());
+        assert!(PathBuf::from(raw["base_path"].as_str().unwrap()).is_relative());
+
+        // Moving the whole drive resolves the paths at the new location
+        let relocated = temp_dir.path().join("drive_f");
+        fs::rename(&original, &relocated).unwrap();
+        let loaded = Settings::load_from_data_root(relocated.join("DeltaRuntime")).unwrap();
+        assert_eq!(loaded.data_root, relocated.join("DeltaRuntime"));
+        assert_eq!(loaded.base_path, relocated.join("Games").join("GTA San Andreas"));
+    }
+
+    #[test]
+    fn test_relocate_data_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("base");
+        let old_root = temp_dir.path().join("old").join("DeltaRuntime");
+        let new_root = temp_dir.path().join("new").join("DeltaRuntime");
+        fs::create_dir_all(&base_path).unwrap();
+
+        let mut settings = Settings::for_wizard(base_path, old_root.clone());
+        settings.cache_root = Some(old_root.join("blob-cache"));
+        settings.create_data_structure().unwrap();
+        fs::create_dir_all(old_root.join("profiles").join("modded")).unwrap();
+        settings.save_to_data_root().unwrap();
+
+        // The tree is moved and the settings saved there point at it
+        let relocation = settings.relocate_data_root(new_root.clone()).unwrap();
+        assert!(relocation.moved);
+        assert!(!relocation.runtimes_need_rebuild);
+        assert!(!old_root.exists());
+        assert!(new_root.join("profiles").join("modded").is_dir());
+        let loaded = Settings::load_from_data_root(&new_root).unwrap();
+        assert_eq!(loaded.data_root, new_root);
+        assert_eq!(loaded.cache_root, Some(new_root.join("blob-cache")));
+
+        // A tree that was already moved by hand is only re-pointed
+        let manual_root = temp_dir.path().join("manual").join("DeltaRuntime");
+        fs::create_dir_all(manual_root.parent().unwrap()).unwrap();
+        fs::rename(&new_root, &manual_root).unwrap();
+        let relocation = loaded.relocate_data_root(manual_root.clone()).unwrap();
+        assert!(!relocation.moved);
+        assert_eq!(Settings::load_from_data_root(&manual_root).unwrap().data_root, manual_root);
+
+        // Moving into itself is refused
+        assert!(relocation.settings.relocate_data_root(manual_root.join("nested")).is_err());
+    }
+
+    #[test]
+    fn test_missing_data_directories_are_reported_and_recreated() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let settings = Settings::for_wizard(temp_dir.path().join("base"), temp_dir.path().join("DeltaRuntime"));
+        settings.create_data_structure().unwrap();
+        assert!(settings.check_data_structure().iter().all(|(_, exists)| *exists));
+
+        // A cleaner tool removed the runtimes directory
+        let runtimes_dir = settings.data_root.join("runtimes");
+        fs::remove_dir(&runtimes_dir).unwrap();
+        let missing: Vec<_> = settings.check_data_structure()
+            .into_iter()
+            .filter(|(_, exists)| !exists)
+            .map(|(dir, _)| dir)
+            .collect();
+        assert_eq!(missing, vec![runtimes_dir.clone()]);
+
+        assert_eq!(settings.ensure_data_structure().unwrap(), vec![runtimes_dir.clone()]);
+        assert!(runtimes_dir.is_dir());
+        assert!(settings.ensure_data_structure().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reset_wizard() {
+        let mut settings = Settings::for_wizard(PathBuf::from("C:\\Games"), PathBuf::from("C:\\DeltaRuntime"));
+        settings.complete_wizard();
+        assert!(!settings.needs_wizard());
+
+        // Relaunching keeps the configured paths
+        settings.reset_wizard(false);
+        assert!(settings.needs_wizard());
+        assert!(settings.wizard.completed_at.is_none());
+        assert_eq!(settings.data_root, PathBuf::from("C:\\DeltaRuntime"));
+
+        settings.reset_wizard(true);
+        assert!(settings.base_path.as_os_str().is_empty());
+        assert!(settings.data_root.as_os_str().is_empty());
+    }
+
+    #[test]
+    fn test_stale_wizard_version_prompts_again() {
+        let mut settings = Settings::for_wizard(PathBuf::from("C:\\Games"), PathBuf::from("C:\\DeltaRuntime"));
+        settings.complete_wizard();
+        assert!(!settings.needs_wizard());
+        assert!(!settings.needs_wizard_for_version(env!("CARGO_PKG_VERSION")));
+
+        // A release requiring a new setup step re-prompts setups from older wizards
+        settings.wizard.wizard_version = Some("0.0.9".to_string());
+        assert!(settings.needs_wizard_for_version("0.1.0"));
+        assert!(!settings.needs_wizard_for_version("0.0.9"));
+        assert!(!settings.needs_wizard_for_version("0.0.9-beta"));
+
+        // Components compare numerically, not as text
+        settings.wizard.wizard_version = Some("0.10.0".to_string());
+        assert!(!settings.needs_wizard_for_version("0.9"));
+        assert!(settings.needs_wizard_for_version("1.0"));
+
+        settings.wizard.wizard_version = None;
+        assert!(settings.needs_wizard());
+    }
+
+    #[test]
+    fn test_cache_directory() {
+        let mut settings = Settings::new();
+        settings.data_root = PathBuf::from("C:\\DeltaRuntime");
+        assert_eq!(settings.get_cache_directory(), PathBuf::from("C:\\DeltaRuntime").join("cache"));
+
+        settings.cache_root = Some(PathBuf::from("C:\\BlobCache"));
+        assert_eq!(settings.get_cache_directory(), PathBuf::from("C:\\BlobCache"));
+
+        // Settings written before cache_root existed still load
+        let json = r#"{"schema":1,"base_path":"C:\\Games","data_root":"C:\\DeltaRuntime","overlay_mode":"hardlink"}"#;
+        let loaded: Settings = serde_json::from_str(json).unwrap();
+        assert!(loaded.cache_root.is_none());
+        assert!(loaded.cache_namespace.is_none());
+
+        // References are recorded under the namespace
+        settings.cache_namespace = Some("laptop".to_string());
+        let cache = settings.blob_cache();
+        assert_eq!(cache.cache_dir, PathBuf::from("C:\\BlobCache"));
+        assert_eq!(cache.ref_profile("default"), "laptop/default");
+    }
+
     #[test]
     fn test_validation_result() {
         let mut result = ValidationResult::new();
@@ -1,8 +1,13 @@
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Deserialize, Serialize};
 use anyhow::{Context, Result};
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::blob_cache::BlobCache;
+use crate::path_utils::{compile_globs, is_link, is_within, matches_any_glob, rel_path_key, safe_join};
 
 /// Represents a file or directory in the virtual file system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +28,57 @@ pub struct VirtualNode {
     pub children: Option<Vec<VirtualNode>>,
     /// File modification time
     pub modified: Option<String>,
+    /// Totals over all descendant files (directories whose children were built only)
+    #[serde(default)]
+    pub stats: Option<VirtualTreeStats>,
+}
+
+/// Aggregate file counts and sizes below a virtual directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct VirtualTreeStats {
+    /// Number of descendant files
+    pub file_count: usize,
+    /// Combined size of descendant files in bytes
+    pub total_bytes: u64,
+    /// Files coming from the base installation
+    pub base_files: usize,
+    /// Files that only exist in the workspace
+    pub workspace_files: usize,
+    /// Workspace files overriding a base or shared file
+    pub override_files: usize,
+    /// Files coming from the shared workspace
+    #[serde(default)]
+    pub shared_files: usize,
+}
+
+impl VirtualTreeStats {
+    /// Totals for a directory's direct children, reusing the stats already computed for child directories
+    fn from_children(children: &[VirtualNode]) -> Self {
+        let mut stats = Self::default();
+        for child in children {
+            if child.is_directory {
+                if let Some(child_stats) = &child.stats {
+                    stats.file_count += child_stats.file_count;
+                    stats.total_bytes += child_stats.total_bytes;
+                    stats.base_files += child_stats.base_files;
+                    stats.workspace_files += child_stats.workspace_files;
+                    stats.override_files += child_stats.override_files;
+                    stats.shared_files += child_stats.shared_files;
+                }
+                continue;
+            }
+
+            stats.file_count += 1;
+            stats.total_bytes += child.size.unwrap_or(0);
+            match child.source {
+                VirtualNodeSource::Base => stats.base_files += 1,
+                VirtualNodeSource::Workspace => stats.workspace_files += 1,
+                VirtualNodeSource::Override => stats.override_files += 1,
+                VirtualNodeSource::Shared => stats.shared_files += 1,
+            }
+        }
+        stats
+    }
 }
 
 /// Source of a virtual node
@@ -32,16 +88,85 @@ pub enum VirtualNodeSource {
     Base,
     /// File/directory from the workspace overlay  
     Workspace,
-    /// File/directory that exists in both (workspace overrides base)
+    /// File/directory that exists in the workspace and a layer below it (workspace overrides shared and base)
     Override,
+    /// File/directory from the shared workspace common to all profiles (shared overrides base)
+    Shared,
+}
+
+/// Result of deleting a file from the workspace overlay
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum WorkspaceDeleteOutcome {
+    /// The deleted file overrode a base (or shared) file, which is visible again
+    BaseRevealed,
+    /// The file only existed in the workspace and is now gone
+    Removed,
+}
+
+/// Progress of copying a directory into the workspace, and its totals once finished
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DirCopyProgress {
+    /// File just copied
+    pub current_file: Option<String>,
+    pub files_copied: usize,
+    /// Files already in the workspace, left untouched
+    pub files_skipped: usize,
+    pub total_files: usize,
+    pub bytes_copied: u64,
+    pub total_bytes: u64,
+    /// Bytes of content the cache didn't hold yet
+    pub bytes_stored: u64,
+    /// Bytes of content the cache already held, linked without storing it again
+    pub bytes_deduped: u64,
+    /// Whether the copy was cancelled before every file was copied
+    pub cancelled: bool,
+}
+
+/// Result of reverting one file of a bulk revert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertFileResult {
+    pub rel_path: String,
+    /// What reverting did, or None if it failed
+    pub outcome: Option<WorkspaceDeleteOutcome>,
+    pub error: Option<String>,
 }
 
-/// Virtual file system that overlays workspace on top of base game installation
+/// Per-file results and totals of a bulk revert
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RevertReport {
+    pub results: Vec<RevertFileResult>,
+    /// Overrides removed, revealing the base (or shared) file
+    pub reverted: usize,
+    /// Workspace-only files deleted
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+/// Overrides removed for being identical to their base file
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PruneReport {
+    pub pruned: Vec<String>,
+    /// Total size of the removed workspace files; hardlinked ones only free space once their blob is collected
+    pub bytes_reclaimed: u64,
+}
+
+/// Virtual file system that overlays workspace on top of base game installation,
+/// with an optional shared workspace layered between them
 pub struct VirtualFileSystem {
     /// Path to the base game installation
     base_path: PathBuf,
     /// Path to the workspace overlay
     workspace_path: PathBuf,
+    /// Path to the shared workspace applied beneath every profile's workspace
+    shared_path: Option<PathBuf>,
+    /// Whether to follow symlinks and junctions found in the workspace
+    follow_links: bool,
+    /// Whether files placed in the workspace are real copies instead of hardlinks to their blobs
+    keep_workspace_copies: bool,
+    /// DeltaRuntime's data root, never listed as base game files even if it sits inside the base installation
+    data_root: Option<PathBuf>,
+    /// Glob patterns for base files and directories hidden from the tree (and so from runtimes)
+    excluded_base_globs: Vec<glob::Pattern>,
 }
 
 impl VirtualFileSystem {
@@ -50,9 +175,97 @@ impl VirtualFileSystem {
         Self {
             base_path,
             workspace_path,
+            shared_path: None,
+            follow_links: false,
+            keep_workspace_copies: false,
+            data_root: None,
+            excluded_base_globs: Vec::new(),
         }
     }
 
+    /// Set the shared workspace layered between the base installation and the profile's workspace
+    /// The directory doesn't have to exist; a missing shared workspace simply adds no files
+    pub fn set_shared_path(&mut self, shared_path: PathBuf) {
+        self.shared_path = Some(shared_path);
+    }
+
+    /// Shared workspace path for a virtual path, if the shared layer has an entry there
+    fn shared_entry(&self, virtual_path: &str) -> Option<PathBuf> {
+        self.shared_path.as_ref()
+            .map(|shared_path| shared_path.join(virtual_path))
+            .filter(|path| path.exists())
+    }
+
+    /// Set whether symlinks and junctions in the workspace are followed when building the tree
+    pub fn set_follow_links(&mut self, follow_links: bool) {
+        self.follow_links = follow_links;
+    }
+
+    /// Set whether files copied into the workspace stay real copies instead of hardlinks to their blobs
+    pub fn set_keep_workspace_copies(&mut self, keep_workspace_copies: bool) {
+        self.keep_workspace_copies = keep_workspace_copies;
+    }
+
+    /// Set the data root to exclude from the base installation's files
+    pub fn set_data_root(&mut self, data_root: PathBuf) {
+        self.data_root = Some(data_root);
+    }
+
+    /// Set glob patterns for base entries to hide; a matching directory hides everything inside it
+    /// Fails if any pattern isn't a valid glob
+    pub fn set_excluded_base_globs(&mut self, excluded_base_globs: &[String]) -> Result<()> {
+        self.excluded_base_globs = compile_globs(excluded_base_globs)
+            .context("Invalid excluded base glob")?;
+        Ok(())
+    }
+
+    /// Whether a base path is hidden by the excluded globs, directly or through one of its parent directories
+    pub fn is_excluded_base(&self, virtual_path: &str) -> bool {
+        if self.excluded_base_globs.is_empty() {
+            return false;
+        }
+
+        let mut prefix = String::new();
+        virtual_path.split('/').filter(|component| !component.is_empty()).any(|component| {
+            if !prefix.is_empty() {
+                prefix.push('/');
+            }
+            prefix.push_str(component);
+            matches_any_glob(&prefix, &self.excluded_base_globs)
+        })
+    }
+
+    /// Count the base files hidden by the excluded globs that the workspace doesn't provide instead
+    pub fn excluded_base_file_count(&self) -> usize {
+        if self.excluded_base_globs.is_empty() {
+            return 0;
+        }
+
+        self.base_files()
+            .into_iter()
+            .filter(|(rel_path, _)| {
+                self.is_excluded_base(rel_path)
+                    && !self.workspace_path.join(rel_path).exists()
+                    && self.shared_entry(rel_path).is_none()
+            })
+            .count()
+    }
+
+    /// Every file of the base installation as a relative path with its size, excluded ones included
+    /// The data root is skipped even when it sits inside the base installation
+    pub fn base_files(&self) -> Vec<(String, u64)> {
+        walkdir::WalkDir::new(&self.base_path)
+            .into_iter()
+            .filter_entry(|entry| self.data_root.as_ref().map_or(true, |data_root| !is_within(entry.path(), data_root)))
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let rel_path = entry.path().strip_prefix(&self.base_path).ok().and_then(rel_path_key)?;
+                Some((rel_path, entry.metadata().map_or(0, |metadata| metadata.len())))
+            })
+            .collect()
+    }
+
     /// Initialize the virtual file system
     pub fn initialize(&mut self) -> Result<()> {
         // No initialization needed without tombstones
@@ -62,24 +275,52 @@ impl VirtualFileSystem {
     /// Get virtual file system tree starting from root or a specific path
     pub fn get_virtual_tree(&self, virtual_path: Option<&str>) -> Result<VirtualNode> {
         let root_path = virtual_path.unwrap_or("");
-        self.build_virtual_node(root_path, true)
+        self.build_virtual_node(root_path, true, &mut HashSet::new())
+    }
+
+    /// Get a single virtual node without building its children
+    pub fn get_node(&self, virtual_path: &str) -> Result<VirtualNode> {
+        self.build_virtual_node(virtual_path, false, &mut HashSet::new())
+    }
+
+    /// Resolve a virtual path to the physical file backing it
+    /// Workspace and override nodes resolve into the workspace, shared nodes into the shared workspace,
+    /// base nodes into the base installation
+    pub fn resolve_physical(&self, virtual_path: &str) -> Result<PathBuf> {
+        let root = if self.workspace_path.join(virtual_path).exists() {
+            &self.workspace_path
+        } else if let Some(shared_path) = self.shared_path.as_ref().filter(|_| self.shared_entry(virtual_path).is_some()) {
+            shared_path
+        } else if self.base_path.join(virtual_path).exists() {
+            &self.base_path
+        } else {
+            return Err(anyhow::anyhow!("Path does not exist: {}", virtual_path));
+        };
+
+        safe_join(root, virtual_path)
+            .with_context(|| format!("Invalid virtual path: {}", virtual_path))
     }
 
-    /// Build a virtual node by merging base and workspace  
-    fn build_virtual_node(&self, virtual_path: &str, include_children: bool) -> Result<VirtualNode> {
+    /// Build a virtual node by merging base, shared workspace and workspace
+    /// `visited` holds canonical directory paths already expanded, so link cycles can't recurse forever
+    fn build_virtual_node(&self, virtual_path: &str, include_children: bool, visited: &mut HashSet<PathBuf>) -> Result<VirtualNode> {
         let base_full_path = self.base_path.join(virtual_path);
         let workspace_full_path = self.workspace_path.join(virtual_path);
+        let shared_full_path = self.shared_entry(virtual_path);
 
-        let base_exists = base_full_path.exists();
+        let base_exists = base_full_path.exists() && !self.is_excluded_base(virtual_path);
         let workspace_exists = workspace_full_path.exists();
 
         // Determine the source and primary path to use
-        let (source, primary_path, writable) = if workspace_exists && base_exists {
-            // Workspace file overrides base file
+        let (source, primary_path, writable) = if workspace_exists && (base_exists || shared_full_path.is_some()) {
+            // Workspace file overrides the shared or base file
             (VirtualNodeSource::Override, &workspace_full_path, true)
         } else if workspace_exists {
             // Workspace-only file (new file added to workspace)
             (VirtualNodeSource::Workspace, &workspace_full_path, true)
+        } else if let Some(shared_full_path) = &shared_full_path {
+            // Shared file, over the base file if there is one (read-only from a profile)
+            (VirtualNodeSource::Shared, shared_full_path, false)
         } else if base_exists {
             // Base file only (read-only)
             (VirtualNodeSource::Base, &base_full_path, false)
@@ -112,10 +353,18 @@ impl VirtualFileSystem {
         };
 
         let children = if is_directory && include_children {
-            Some(self.build_virtual_children(virtual_path)?)
+            let canonical = fs::canonicalize(primary_path)
+                .unwrap_or_else(|_| primary_path.clone());
+            if visited.insert(canonical) {
+                Some(self.build_virtual_children(virtual_path, visited)?)
+            } else {
+                warn!("Skipping already visited directory (link cycle?): {}", virtual_path);
+                Some(Vec::new())
+            }
         } else {
             None
         };
+        let stats = children.as_deref().map(VirtualTreeStats::from_children);
 
         Ok(VirtualNode {
             name,
@@ -126,22 +375,28 @@ impl VirtualFileSystem {
             writable,
             children,
             modified,
+            stats,
         })
     }
 
     /// Build children for a virtual directory
-    fn build_virtual_children(&self, virtual_path: &str) -> Result<Vec<VirtualNode>> {
+    fn build_virtual_children(&self, virtual_path: &str, visited: &mut HashSet<PathBuf>) -> Result<Vec<VirtualNode>> {
         let mut children = Vec::new();
         let mut seen_names = std::collections::HashSet::new();
 
         let base_dir = self.base_path.join(virtual_path);
         let workspace_dir = self.workspace_path.join(virtual_path);
+        let shared_dir = self.shared_entry(virtual_path);
 
         // First, add all workspace files (they take priority)
         if workspace_dir.exists() && workspace_dir.is_dir() {
             for entry in fs::read_dir(&workspace_dir)? {
                 let entry = entry?;
-                let name = entry.file_name().to_string_lossy().to_string();
+                // A lossy name wouldn't point back at the file, so such entries can't be planned
+                let Ok(name) = entry.file_name().into_string() else {
+                    warn!("Skipping entry with a name that isn't valid Unicode: {}", entry.path().display());
+                    continue;
+                };
                 
                 // Skip tombstones file
                 // Skip tombstones file (legacy)
@@ -149,13 +404,50 @@ impl VirtualFileSystem {
                     continue;
                 }
 
+                // Skip symlinks/junctions unless following them is enabled
+                if !self.follow_links && is_link(entry.path()) {
+                    info!("Skipping link in workspace: {}", entry.path().display());
+                    continue;
+                }
+
+                let child_virtual_path = if virtual_path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", virtual_path, name)
+                };
+
+                if let Ok(child) = self.build_virtual_node(&child_virtual_path, true, visited) {
+                    children.push(child);
+                    seen_names.insert(name);
+                }
+            }
+        }
+
+        // Then shared files the workspace doesn't override
+        if let Some(shared_dir) = shared_dir.filter(|shared_dir| shared_dir.is_dir()) {
+            for entry in fs::read_dir(&shared_dir)? {
+                let entry = entry?;
+                let Ok(name) = entry.file_name().into_string() else {
+                    warn!("Skipping entry with a name that isn't valid Unicode: {}", entry.path().display());
+                    continue;
+                };
+
+                if seen_names.contains(&name) {
+                    continue; // Already added from workspace
+                }
+
+                if !self.follow_links && is_link(entry.path()) {
+                    info!("Skipping link in shared workspace: {}", entry.path().display());
+                    continue;
+                }
+
                 let child_virtual_path = if virtual_path.is_empty() {
                     name.clone()
                 } else {
                     format!("{}/{}", virtual_path, name)
                 };
 
-                if let Ok(child) = self.build_virtual_node(&child_virtual_path, true) {
+                if let Ok(child) = self.build_virtual_node(&child_virtual_path, true, visited) {
                     children.push(child);
                     seen_names.insert(name);
                 }
@@ -166,10 +458,19 @@ impl VirtualFileSystem {
         if base_dir.exists() && base_dir.is_dir() {
             for entry in fs::read_dir(&base_dir)? {
                 let entry = entry?;
-                let name = entry.file_name().to_string_lossy().to_string();
+                let Ok(name) = entry.file_name().into_string() else {
+                    warn!("Skipping entry with a name that isn't valid Unicode: {}", entry.path().display());
+                    continue;
+                };
 
                 if seen_names.contains(&name) {
-                    continue; // Already added from workspace
+                    continue; // Already added from workspace or shared workspace
+                }
+
+                // Never treat DeltaRuntime's own data (cache, runtimes) as game files
+                if self.data_root.as_ref().is_some_and(|data_root| is_within(entry.path(), data_root)) {
+                    warn!("Skipping data root inside base installation: {}", entry.path().display());
+                    continue;
                 }
 
                 let child_virtual_path = if virtual_path.is_empty() {
@@ -178,7 +479,7 @@ impl VirtualFileSystem {
                     format!("{}/{}", virtual_path, name)
                 };
 
-                if let Ok(child) = self.build_virtual_node(&child_virtual_path, true) {
+                if let Ok(child) = self.build_virtual_node(&child_virtual_path, true, visited) {
                     children.push(child);
                 }
             }
@@ -196,9 +497,12 @@ impl VirtualFileSystem {
         Ok(children)
     }
 
-    /// Copy a file from base to workspace (make it writable)
-    pub fn copy_to_workspace(&self, virtual_path: &str) -> Result<()> {
-        let base_file = self.base_path.join(virtual_path);
+    /// Copy a file from base (or the shared workspace) to workspace (make it writable)
+    /// The file is stored in the blob cache and hardlinked into the workspace, so content the cache
+    /// already holds isn't copied again
+    pub fn copy_to_workspace(&self, profile: &str, virtual_path: &str, cache: &BlobCache) -> Result<()> {
+        let base_file = self.shared_entry(virtual_path)
+            .unwrap_or_else(|| self.base_path.join(virtual_path));
         let workspace_file = self.workspace_path.join(virtual_path);
 
         if !base_file.exists() {
@@ -211,14 +515,114 @@ impl VirtualFileSystem {
                 .with_context(|| format!("Failed to create workspace directory: {}", parent.display()))?;
         }
 
-        fs::copy(&base_file, &workspace_file)
-            .with_context(|| format!("Failed to copy file to workspace: {}", virtual_path))?;
+        let blob = cache.ensure_blob(&base_file)
+            .with_context(|| format!("Failed to store blob for: {}", virtual_path))?;
+        if self.keep_workspace_copies {
+            cache.copy_blob_to(&workspace_file, &blob)
+        } else {
+            cache.link_blob_to(&workspace_file, &blob)
+        }
+        .with_context(|| format!("Failed to copy file to workspace: {}", virtual_path))?;
+        // Reference with native separators, matching what the watcher records
+        cache.add_ref_for_file(&blob, profile, virtual_path, &workspace_file)
+            .with_context(|| format!("Failed to record blob reference for: {}", virtual_path))?;
 
         info!("Copied base file to workspace: {}", virtual_path);
         Ok(())
     }
 
-    /// Revert workspace file to reveal base file (only works for workspace overrides)
+    /// Copy every file under a base (or shared) directory into the workspace, through the blob cache like `copy_to_workspace`
+    /// Files already in the workspace are skipped so edits survive. `on_progress` is called after each file;
+    /// once `cancel_flag` is set the copy stops between files, keeping the files already copied
+    pub fn copy_dir_to_workspace<F: FnMut(&DirCopyProgress)>(
+        &self,
+        profile: &str,
+        virtual_path: &str,
+        cache: &BlobCache,
+        cancel_flag: Option<&AtomicBool>,
+        mut on_progress: F,
+    ) -> Result<DirCopyProgress> {
+        let virtual_path = virtual_path.replace('\\', "/").trim_matches('/').to_string();
+        let base_dir = safe_join(&self.base_path, &virtual_path)
+            .with_context(|| format!("Invalid virtual path: {}", virtual_path))?;
+        let shared_dir = self.shared_entry(&virtual_path);
+        if !base_dir.is_dir() && !shared_dir.as_ref().is_some_and(|dir| dir.is_dir()) {
+            return Err(anyhow::anyhow!("Base directory does not exist: {}", virtual_path));
+        }
+
+        // Source of each file, the shared layer winning over base
+        let mut sources = BTreeMap::new();
+        for (root, dir) in [(&self.base_path, Some(base_dir)), (self.shared_path.as_ref().unwrap_or(&self.base_path), shared_dir)] {
+            let Some(dir) = dir.filter(|dir| dir.is_dir()) else {
+                continue;
+            };
+            let entries = walkdir::WalkDir::new(&dir)
+                .into_iter()
+                .filter_entry(|entry| self.data_root.as_ref().map_or(true, |data_root| !is_within(entry.path(), data_root)))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file());
+            for entry in entries {
+                let Some(rel_path) = entry.path().strip_prefix(root).ok().and_then(rel_path_key) else {
+                    continue;
+                };
+                if root == &self.base_path && self.is_excluded_base(&rel_path) {
+                    continue;
+                }
+                sources.insert(rel_path, (entry.path().to_path_buf(), entry.metadata().map(|m| m.len()).unwrap_or(0)));
+            }
+        }
+
+        let mut progress = DirCopyProgress {
+            total_files: sources.len(),
+            total_bytes: sources.values().map(|(_, size)| size).sum(),
+            ..DirCopyProgress::default()
+        };
+        for (rel_path, (source, size)) in sources {
+            if cancel_flag.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                progress.cancelled = true;
+                info!("Directory copy cancelled after {} files: {}", progress.files_copied, virtual_path);
+                break;
+            }
+
+            let workspace_file = self.workspace_path.join(&rel_path);
+            if workspace_file.exists() {
+                progress.files_skipped += 1;
+            } else {
+                if let Some(parent) = workspace_file.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create workspace directory: {}", parent.display()))?;
+                }
+                let (blob, newly_stored) = cache.store_blob(&source)
+                    .with_context(|| format!("Failed to store blob for: {}", rel_path))?;
+                if self.keep_workspace_copies {
+                    cache.copy_blob_to(&workspace_file, &blob)
+                } else {
+                    cache.link_blob_to(&workspace_file, &blob)
+                }
+                .with_context(|| format!("Failed to copy file to workspace: {}", rel_path))?;
+                cache.add_ref_for_file(&blob, profile, &rel_path, &workspace_file)
+                    .with_context(|| format!("Failed to record blob reference for: {}", rel_path))?;
+
+                progress.files_copied += 1;
+                if newly_stored {
+                    progress.bytes_stored += size;
+                } else {
+                    progress.bytes_deduped += size;
+                }
+            }
+            progress.bytes_copied += size;
+            progress.current_file = Some(rel_path);
+            on_progress(&progress);
+        }
+
+        info!(
+            "Copied directory to workspace: {} ({} files, {} skipped, {} bytes stored, {} deduped)",
+            virtual_path, progress.files_copied, progress.files_skipped, progress.bytes_stored, progress.bytes_deduped
+        );
+        Ok(progress)
+    }
+
+    /// Revert workspace file to reveal the shared or base file (only works for workspace overrides)
     pub fn revert_to_original(&self, virtual_path: &str) -> Result<()> {
         let workspace_path = self.workspace_path.join(virtual_path);
         let base_path = self.base_path.join(virtual_path);
@@ -228,7 +632,7 @@ impl VirtualFileSystem {
             return Err(anyhow::anyhow!("No workspace file to revert: {}", virtual_path));
         }
 
-        if !base_path.exists() {
+        if !base_path.exists() && self.shared_entry(virtual_path).is_none() {
             return Err(anyhow::anyhow!("Cannot revert workspace-only file (no base file exists): {}", virtual_path));
         }
 
@@ -244,6 +648,140 @@ impl VirtualFileSystem {
         info!("Reverted workspace file to original: {}", virtual_path);
         Ok(())
     }
+
+    /// Move a workspace directory to a new path, carrying its files' blob references along
+    /// The files are renamed, not re-normalized, so the move costs one rename and one index update.
+    /// Returns the number of references moved
+    pub fn move_workspace_dir(&self, profile: &str, from: &str, to: &str, cache: &BlobCache) -> Result<usize> {
+        let from_dir = safe_join(&self.workspace_path, from)
+            .with_context(|| format!("Invalid virtual path: {}", from))?;
+        let to_dir = safe_join(&self.workspace_path, to)
+            .with_context(|| format!("Invalid virtual path: {}", to))?;
+
+        if !from_dir.is_dir() {
+            return Err(anyhow::anyhow!("Workspace directory does not exist: {}", from));
+        }
+        if to_dir.exists() {
+            return Err(anyhow::anyhow!("Destination already exists in the workspace: {}", to));
+        }
+        if is_within(&to_dir, &from_dir) {
+            return Err(anyhow::anyhow!("Cannot move a directory into itself: {} -> {}", from, to));
+        }
+
+        if let Some(parent) = to_dir.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create workspace directory: {}", parent.display()))?;
+        }
+        fs::rename(&from_dir, &to_dir)
+            .with_context(|| format!("Failed to move workspace directory: {} -> {}", from, to))?;
+
+        let moved = cache.rename_ref_prefix(profile, from, to)
+            .with_context(|| format!("Failed to update blob references for: {} -> {}", from, to))?;
+        info!("Moved workspace directory: {} -> {} ({} references)", from, to, moved);
+        Ok(moved)
+    }
+
+    /// Delete a workspace file and drop its blob reference (GCing the blob if orphaned)
+    /// Returns whether a base file is revealed underneath or the file is gone entirely
+    pub fn delete_workspace_file(&self, profile: &str, virtual_path: &str, cache: &BlobCache) -> Result<WorkspaceDeleteOutcome> {
+        let workspace_file = safe_join(&self.workspace_path, virtual_path)
+            .with_context(|| format!("Invalid virtual path: {}", virtual_path))?;
+
+        if !workspace_file.exists() {
+            return Err(anyhow::anyhow!("File does not exist in workspace: {}", virtual_path));
+        }
+
+        if workspace_file.is_dir() {
+            return Err(anyhow::anyhow!("Cannot delete a directory: {}", virtual_path));
+        }
+
+        // Remove the file first, so a failed delete doesn't leave it in place without a reference
+        fs::remove_file(&workspace_file)
+            .with_context(|| format!("Failed to remove workspace file: {}", virtual_path))?;
+
+        match cache.remove_existing_ref(profile, virtual_path)? {
+            Some(hash) => info!("Removed blob reference {} for: {}", hash.to_hex(), virtual_path),
+            None => info!("No blob reference found for: {}", virtual_path),
+        }
+
+        let outcome = if self.base_path.join(virtual_path).exists() || self.shared_entry(virtual_path).is_some() {
+            WorkspaceDeleteOutcome::BaseRevealed
+        } else {
+            WorkspaceDeleteOutcome::Removed
+        };
+
+        info!("Deleted workspace file: {} ({:?})", virtual_path, outcome);
+        Ok(outcome)
+    }
+
+    /// Revert a selection of workspace files, dropping their blob references, and continuing past failures
+    /// Overrides are reverted to the file below them; workspace-only files have nothing to reveal
+    /// and are only deleted with `delete_workspace_only`, otherwise they fail
+    pub fn revert_many(&self, profile: &str, rel_paths: &[String], cache: &BlobCache, delete_workspace_only: bool) -> Result<RevertReport> {
+        let mut report = RevertReport::default();
+        for rel_path in rel_paths {
+            let overrides_lower_layer = self.base_path.join(rel_path).exists() || self.shared_entry(rel_path).is_some();
+            let outcome = if overrides_lower_layer || delete_workspace_only {
+                self.delete_workspace_file(profile, rel_path, cache)
+            } else {
+                Err(anyhow::anyhow!("Workspace-only file has no base file to reveal; deleting it must be requested explicitly: {}", rel_path))
+            };
+
+            let result = match outcome {
+                Ok(outcome) => {
+                    match outcome {
+                        WorkspaceDeleteOutcome::BaseRevealed => report.reverted += 1,
+                        WorkspaceDeleteOutcome::Removed => report.deleted += 1,
+                    }
+                    RevertFileResult { rel_path: rel_path.clone(), outcome: Some(outcome), error: None }
+                }
+                Err(e) => {
+                    warn!("Failed to revert {}: {}", rel_path, e);
+                    report.failed += 1;
+                    RevertFileResult { rel_path: rel_path.clone(), outcome: None, error: Some(e.to_string()) }
+                }
+            };
+            report.results.push(result);
+        }
+
+        info!("Reverted {} files, deleted {}, {} failed", report.reverted, report.deleted, report.failed);
+        Ok(report)
+    }
+
+    /// Delete workspace overrides whose content is byte-identical to their base file, with their blob references
+    /// These are typically files copied to the workspace and never edited. Files over a shared workspace
+    /// entry or an excluded base file are kept, since removing them would reveal the shared file or nothing
+    pub fn prune_redundant_overrides(&self, profile: &str, cache: &BlobCache) -> Result<PruneReport> {
+        let mut report = PruneReport::default();
+        for entry in walkdir::WalkDir::new(&self.workspace_path).into_iter().filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Some(rel_path) = entry.path().strip_prefix(&self.workspace_path).ok().and_then(rel_path_key) else {
+                continue;
+            };
+            let base_file = self.base_path.join(&rel_path);
+            if self.shared_entry(&rel_path).is_some() || self.is_excluded_base(&rel_path) || !base_file.is_file() {
+                continue;
+            }
+            let size = entry.metadata()?.len();
+            if fs::metadata(&base_file)?.len() != size
+                || BlobCache::hash_file(&base_file)? != cache.hash(entry.path())?
+            {
+                continue;
+            }
+
+            fs::remove_file(entry.path())
+                .with_context(|| format!("Failed to remove workspace file: {}", entry.path().display()))?;
+            cache.remove_existing_ref(profile, &rel_path)
+                .with_context(|| format!("Failed to remove blob reference for: {}", rel_path))?;
+            report.bytes_reclaimed += size;
+            report.pruned.push(rel_path);
+        }
+
+        info!("Pruned {} overrides identical to base ({} bytes) from profile: {}", report.pruned.len(), report.bytes_reclaimed, profile);
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
@@ -284,4 +822,417 @@ mod tests {
         assert!(base_file.writable);
     }
 
+    #[test]
+    fn test_directory_stats_aggregate_descendants() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+
+        fs::create_dir_all(base_dir.join("data/maps")).unwrap();
+        fs::create_dir_all(workspace_dir.join("data")).unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "12345").unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        fs::write(base_dir.join("data/maps/LA.ide"), "la").unwrap();
+        fs::write(workspace_dir.join("data/handling.cfg"), "override").unwrap();
+        fs::write(workspace_dir.join("mod.asi"), "mod").unwrap();
+
+        let vfs = VirtualFileSystem::new(base_dir, workspace_dir);
+        let root = vfs.get_virtual_tree(None).unwrap();
+
+        assert_eq!(root.stats, Some(VirtualTreeStats {
+            file_count: 4,
+            total_bytes: 5 + 8 + 2 + 3,
+            base_files: 2,
+            workspace_files: 1,
+            override_files: 1,
+            shared_files: 0,
+        }));
+
+        let data = root.children.unwrap().into_iter().find(|c| c.name == "data").unwrap();
+        assert_eq!(data.stats.as_ref().map(|stats| stats.file_count), Some(2));
+
+        // Nodes fetched without children carry no stats
+        assert!(vfs.get_node("data").unwrap().stats.is_none());
+        assert!(vfs.get_node("gta_sa.exe").unwrap().stats.is_none());
+    }
+
+    #[test]
+    fn test_shared_workspace_layer_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let shared_dir = temp_dir.path().join("shared-workspace");
+        let workspace_dir = temp_dir.path().join("workspace");
+        for dir in [&base_dir, &shared_dir, &workspace_dir] {
+            fs::create_dir_all(dir.join("data")).unwrap();
+        }
+
+        fs::write(base_dir.join("data/base.cfg"), "base").unwrap();
+        fs::write(base_dir.join("data/shared.cfg"), "base").unwrap();
+        fs::write(shared_dir.join("data/shared.cfg"), "shared").unwrap();
+        fs::write(shared_dir.join("data/shared_only.asi"), "shared").unwrap();
+        fs::write(base_dir.join("data/all.cfg"), "base").unwrap();
+        fs::write(shared_dir.join("data/all.cfg"), "shared").unwrap();
+        fs::write(workspace_dir.join("data/all.cfg"), "profile").unwrap();
+        fs::write(shared_dir.join("data/over_shared.asi"), "shared").unwrap();
+        fs::write(workspace_dir.join("data/over_shared.asi"), "profile").unwrap();
+
+        let mut vfs = VirtualFileSystem::new(base_dir.clone(), workspace_dir.clone());
+        vfs.set_shared_path(shared_dir.clone());
+
+        // Shared overrides base, the profile overrides both
+        let expected = [
+            ("data/base.cfg", VirtualNodeSource::Base, base_dir.join("data/base.cfg")),
+            ("data/shared.cfg", VirtualNodeSource::Shared, shared_dir.join("data/shared.cfg")),
+            ("data/shared_only.asi", VirtualNodeSource::Shared, shared_dir.join("data/shared_only.asi")),
+            ("data/all.cfg", VirtualNodeSource::Override, workspace_dir.join("data/all.cfg")),
+            ("data/over_shared.asi", VirtualNodeSource::Override, workspace_dir.join("data/over_shared.asi")),
+        ];
+        for (virtual_path, source, physical) in &expected {
+            let node = vfs.get_node(virtual_path).unwrap();
+            assert_eq!(&node.source, source, "{}", virtual_path);
+            assert_eq!(node.writable, *source == VirtualNodeSource::Override, "{}", virtual_path);
+            assert_eq!(&vfs.resolve_physical(virtual_path).unwrap(), physical);
+        }
+
+        // Each file appears once in the merged tree
+        let data = vfs.get_virtual_tree(Some("data")).unwrap();
+        assert_eq!(data.children.as_ref().unwrap().len(), expected.len());
+        let stats = data.stats.unwrap();
+        assert_eq!((stats.base_files, stats.shared_files, stats.override_files), (1, 2, 2));
+
+        // Removing the profile's override reveals the shared file, not the base one
+        vfs.revert_to_original("data/all.cfg").unwrap();
+        assert_eq!(vfs.get_node("data/all.cfg").unwrap().source, VirtualNodeSource::Shared);
+
+        // A missing shared workspace adds nothing
+        vfs.set_shared_path(temp_dir.path().join("missing"));
+        assert_eq!(vfs.get_node("data/shared.cfg").unwrap().source, VirtualNodeSource::Base);
+        assert!(vfs.get_node("data/shared_only.asi").is_err());
+    }
+
+    #[test]
+    fn test_data_root_inside_base_is_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = base_dir.join("DeltaRuntime");
+        let workspace_dir = data_root.join("profiles").join("default").join("workspace");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        fs::create_dir_all(data_root.join("cache")).unwrap();
+        fs::write(data_root.join("cache").join("blob"), "cached").unwrap();
+        fs::write(base_dir.join("gta_sa.exe"), "game").unwrap();
+
+        let mut vfs = VirtualFileSystem::new(base_dir, workspace_dir);
+        vfs.set_data_root(data_root);
+
+        let names: Vec<String> = vfs.get_virtual_tree(None).unwrap()
+            .children.unwrap()
+            .into_iter()
+            .map(|child| child.name)
+            .collect();
+        assert_eq!(names, vec!["gta_sa.exe".to_string()]);
+    }
+
+    #[test]
+    fn test_excluded_base_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        fs::create_dir_all(base_dir.join("_CommonRedist").join("vcredist")).unwrap();
+        fs::create_dir_all(base_dir.join("DirectX")).unwrap();
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(workspace_dir.join("DirectX")).unwrap();
+        fs::write(base_dir.join("_CommonRedist").join("vcredist").join("vc_redist.x86.exe"), "redist").unwrap();
+        fs::write(base_dir.join("DirectX").join("dxsetup.exe"), "dx").unwrap();
+        fs::write(base_dir.join("Unins000.DAT"), "uninstall").unwrap();
+        fs::write(base_dir.join("data").join("handling.cfg"), "handling").unwrap();
+        fs::write(workspace_dir.join("DirectX").join("mod.txt"), "mod").unwrap();
+
+        let mut vfs = VirtualFileSystem::new(base_dir, workspace_dir);
+        vfs.set_excluded_base_globs(&crate::settings::UserPreferences::default().excluded_base_globs).unwrap();
+
+        // Matches are case-insensitive and cover everything below a matching directory
+        assert!(vfs.is_excluded_base("unins000.dat"));
+        assert!(vfs.is_excluded_base("_CommonRedist/vcredist/vc_redist.x86.exe"));
+        assert!(!vfs.is_excluded_base("data/handling.cfg"));
+        assert!(!vfs.is_excluded_base(""));
+
+        let root = vfs.get_virtual_tree(None).unwrap();
+        let names: Vec<&str> = root.children.as_ref().unwrap().iter().map(|child| child.name.as_str()).collect();
+        assert_eq!(names, vec!["data", "DirectX"]);
+
+        // Workspace files inside an excluded directory still show, without the base files beside them
+        let directx = vfs.get_node("DirectX").unwrap();
+        assert_eq!(directx.source, VirtualNodeSource::Workspace);
+        let directx = &root.children.as_ref().unwrap()[1];
+        let names: Vec<&str> = directx.children.as_ref().unwrap().iter().map(|child| child.name.as_str()).collect();
+        assert_eq!(names, vec!["mod.txt"]);
+        assert!(vfs.get_node("DirectX/dxsetup.exe").is_err());
+
+        assert_eq!(vfs.excluded_base_file_count(), 3);
+    }
+
+    #[test]
+    fn test_resolve_physical() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(workspace_dir.join("data")).unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        fs::write(base_dir.join("data/gta.dat"), "base").unwrap();
+        fs::write(workspace_dir.join("data/handling.cfg"), "override").unwrap();
+        fs::write(temp_dir.path().join("outside.txt"), "outside").unwrap();
+
+        let vfs = VirtualFileSystem::new(base_dir.clone(), workspace_dir.clone());
+
+        // Overrides resolve into the workspace, base-only files into the base install
+        assert_eq!(vfs.resolve_physical("data/handling.cfg").unwrap(), workspace_dir.join("data/handling.cfg"));
+        assert_eq!(vfs.resolve_physical("data/gta.dat").unwrap(), base_dir.join("data/gta.dat"));
+
+        // Missing files and traversal outside the roots are rejected
+        assert!(vfs.resolve_physical("data/missing.cfg").is_err());
+        assert!(vfs.resolve_physical("../outside.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+        std::os::unix::fs::symlink(target, link)
+    }
+
+    #[cfg(windows)]
+    fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+        std::os::windows::fs::symlink_dir(target, link)
+    }
+
+    #[test]
+    fn test_symlinked_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let external_dir = temp_dir.path().join("external");
+
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::create_dir_all(workspace_dir.join("mods")).unwrap();
+        fs::create_dir_all(&external_dir).unwrap();
+        fs::write(workspace_dir.join("mods/mod.txt"), "mod").unwrap();
+        fs::write(external_dir.join("external.txt"), "external").unwrap();
+
+        // Creating symlinks may require privileges on Windows
+        if symlink_dir(&external_dir, &workspace_dir.join("linked")).is_err() {
+            return;
+        }
+        // Self-referential link back into the workspace
+        symlink_dir(&workspace_dir, &workspace_dir.join("mods/loop")).unwrap();
+
+        let child_names = |node: &VirtualNode| -> Vec<String> {
+            node.children.as_ref().unwrap().iter().map(|c| c.name.clone()).collect()
+        };
+
+        // Links are skipped by default
+        let vfs = VirtualFileSystem::new(base_dir.clone(), workspace_dir.clone());
+        let root = vfs.get_virtual_tree(None).unwrap();
+        assert_eq!(child_names(&root), vec!["mods".to_string()]);
+        let mods = root.children.as_ref().unwrap().iter().find(|c| c.name == "mods").unwrap();
+        assert_eq!(child_names(mods), vec!["mod.txt".to_string()]);
+
+        // When following links, the linked directory is included and the cycle terminates
+        let mut vfs = VirtualFileSystem::new(base_dir, workspace_dir);
+        vfs.set_follow_links(true);
+        let root = vfs.get_virtual_tree(None).unwrap();
+        let linked = root.children.as_ref().unwrap().iter().find(|c| c.name == "linked").unwrap();
+        assert_eq!(child_names(linked), vec!["external.txt".to_string()]);
+        let mods = root.children.as_ref().unwrap().iter().find(|c| c.name == "mods").unwrap();
+        let looped = mods.children.as_ref().unwrap().iter().find(|c| c.name == "loop").unwrap();
+        assert_eq!(looped.children.as_ref().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_delete_workspace_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(workspace_dir.join("data")).unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        fs::write(workspace_dir.join("data/handling.cfg"), "override").unwrap();
+        fs::write(workspace_dir.join("data/new.cfg"), "new").unwrap();
+
+        let override_blob = cache.ensure_blob(workspace_dir.join("data/handling.cfg")).unwrap();
+        cache.add_ref(&override_blob, "test", "data/handling.cfg").unwrap();
+        let new_blob = cache.ensure_blob(workspace_dir.join("data/new.cfg")).unwrap();
+        cache.add_ref(&new_blob, "test", "data/new.cfg").unwrap();
+
+        let vfs = VirtualFileSystem::new(base_dir, workspace_dir.clone());
+
+        // Deleting an override reveals the base file and GCs the orphaned blob
+        let outcome = vfs.delete_workspace_file("test", "data/handling.cfg", &cache).unwrap();
+        assert_eq!(outcome, WorkspaceDeleteOutcome::BaseRevealed);
+        assert!(!workspace_dir.join("data/handling.cfg").exists());
+        assert_eq!(vfs.get_node("data/handling.cfg").unwrap().source, VirtualNodeSource::Base);
+        assert!(!override_blob.path.exists());
+
+        // Deleting a workspace-only file removes it from the virtual tree entirely
+        let outcome = vfs.delete_workspace_file("test", "data/new.cfg", &cache).unwrap();
+        assert_eq!(outcome, WorkspaceDeleteOutcome::Removed);
+        assert!(vfs.get_node("data/new.cfg").is_err());
+        assert!(!new_blob.path.exists());
+        assert!(cache.load_index().unwrap().refs.is_empty());
+
+        // Files that aren't in the workspace can't be deleted
+        assert!(vfs.delete_workspace_file("test", "data/handling.cfg", &cache).is_err());
+    }
+
+    #[test]
+    fn test_copy_dir_to_workspace_reports_progress_and_cancels() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        fs::create_dir_all(base_dir.join("models/cars")).unwrap();
+        fs::create_dir_all(&workspace_dir).unwrap();
+        fs::write(base_dir.join("models/a.dff"), "model").unwrap();
+        fs::write(base_dir.join("models/cars/b.dff"), "model").unwrap();
+        fs::write(base_dir.join("models/cars/c.txd"), "texture").unwrap();
+        fs::write(base_dir.join("gta.dat"), "dat").unwrap();
+        let vfs = VirtualFileSystem::new(base_dir, workspace_dir.clone());
+
+        // Cancelled after the first file: it stays, nothing after it is copied
+        let cancel_flag = AtomicBool::new(false);
+        let report = vfs.copy_dir_to_workspace("test", "models", &cache, Some(&cancel_flag), |_| cancel_flag.store(true, Ordering::SeqCst)).unwrap();
+        assert!(report.cancelled);
+        assert_eq!((report.files_copied, report.total_files), (1, 3));
+        assert!(workspace_dir.join("models/a.dff").exists());
+        assert!(!workspace_dir.join("models/cars/b.dff").exists());
+
+        // Running again copies the rest, skipping the copied file and deduping identical content
+        let mut events = Vec::new();
+        let report = vfs.copy_dir_to_workspace("test", "models", &cache, None, |progress| events.push(progress.clone())).unwrap();
+        assert!(!report.cancelled);
+        assert_eq!((report.files_copied, report.files_skipped), (2, 1));
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.last().unwrap().bytes_copied, report.total_bytes);
+        assert_eq!(report.bytes_deduped, "model".len() as u64);
+        assert_eq!(report.bytes_stored, "texture".len() as u64);
+        assert_eq!(vfs.get_node("models/cars/c.txd").unwrap().source, VirtualNodeSource::Override);
+        assert!(!workspace_dir.join("gta.dat").exists());
+        assert!(cache.find_blob_hash_for_file("test", "models/cars/c.txd").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_move_workspace_dir_carries_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::create_dir_all(workspace_dir.join("mods/cars/sports")).unwrap();
+        for rel_path in ["mods/cars/infernus.dff", "mods/cars/sports/cheetah.dff", "mods/carsounds.asi"] {
+            fs::write(workspace_dir.join(rel_path), rel_path).unwrap();
+            let blob = cache.ensure_blob(workspace_dir.join(rel_path)).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+        let vfs = VirtualFileSystem::new(base_dir, workspace_dir.clone());
+
+        assert_eq!(vfs.move_workspace_dir("test", "mods/cars", "vehicles/cars", &cache).unwrap(), 2);
+        assert!(workspace_dir.join("vehicles/cars/sports/cheetah.dff").exists());
+        assert!(!workspace_dir.join("mods/cars").exists());
+        assert!(cache.find_blob_hash_for_file("test", "vehicles/cars/infernus.dff").unwrap().is_some());
+        assert!(cache.find_blob_hash_for_file("test", "vehicles/cars/sports/cheetah.dff").unwrap().is_some());
+        assert!(cache.find_blob_hash_for_file("test", "mods/cars/infernus.dff").unwrap().is_none());
+
+        // A sibling sharing the name prefix isn't moved
+        assert!(cache.find_blob_hash_for_file("test", "mods/carsounds.asi").unwrap().is_some());
+
+        // Existing destinations and moves into the directory itself are refused
+        assert!(vfs.move_workspace_dir("test", "vehicles", "mods", &cache).is_err());
+        assert!(vfs.move_workspace_dir("test", "vehicles", "vehicles/cars/inner", &cache).is_err());
+    }
+
+    #[test]
+    fn test_revert_many_mixed_selection() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(workspace_dir.join("data")).unwrap();
+        fs::write(base_dir.join("data/handling.cfg"), "base").unwrap();
+        fs::write(workspace_dir.join("data/handling.cfg"), "override").unwrap();
+        fs::write(workspace_dir.join("data/new.cfg"), "new").unwrap();
+        for rel_path in ["data/handling.cfg", "data/new.cfg"] {
+            let blob = cache.ensure_blob(workspace_dir.join(rel_path)).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+        let vfs = VirtualFileSystem::new(base_dir, workspace_dir.clone());
+        let selection: Vec<String> = ["data/handling.cfg", "data/new.cfg", "data/missing.cfg"].iter().map(|p| p.to_string()).collect();
+
+        // Without the flag the workspace-only file is kept, and the missing file fails without stopping the rest
+        let report = vfs.revert_many("test", &selection, &cache, false).unwrap();
+        assert_eq!((report.reverted, report.deleted, report.failed), (1, 0, 2));
+        assert_eq!(report.results[0].outcome, Some(WorkspaceDeleteOutcome::BaseRevealed));
+        assert!(report.results[1].error.is_some() && report.results[2].error.is_some());
+        assert_eq!(vfs.get_node("data/handling.cfg").unwrap().source, VirtualNodeSource::Base);
+        assert!(workspace_dir.join("data/new.cfg").exists());
+
+        let report = vfs.revert_many("test", &selection[1..2], &cache, true).unwrap();
+        assert_eq!((report.reverted, report.deleted, report.failed), (0, 1, 0));
+        assert!(!workspace_dir.join("data/new.cfg").exists());
+        assert!(cache.load_index().unwrap().refs.is_empty());
+    }
+
+    #[test]
+    fn test_prune_redundant_overrides_keeps_real_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let workspace_dir = temp_dir.path().join("workspace");
+        let shared_dir = temp_dir.path().join("shared");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        fs::create_dir_all(base_dir.join("data")).unwrap();
+        fs::create_dir_all(base_dir.join("movies")).unwrap();
+        fs::create_dir_all(workspace_dir.join("data")).unwrap();
+        fs::create_dir_all(workspace_dir.join("movies")).unwrap();
+        fs::create_dir_all(&shared_dir).unwrap();
+        for rel_path in ["data/copied.cfg", "data/edited.cfg", "data/resized.cfg", "shadowed.cfg", "movies/intro.cfg"] {
+            fs::write(base_dir.join(rel_path), "base").unwrap();
+        }
+        fs::write(shared_dir.join("shadowed.cfg"), "shared").unwrap();
+
+        let mut vfs = VirtualFileSystem::new(base_dir, workspace_dir.clone());
+        vfs.set_shared_path(shared_dir);
+        vfs.set_excluded_base_globs(&["movies".to_string()]).unwrap();
+        // Unedited copies, including ones over a shared file and an excluded base file that they stand in for
+        fs::write(workspace_dir.join("data/copied.cfg"), "base").unwrap();
+        fs::write(workspace_dir.join("shadowed.cfg"), "base").unwrap();
+        fs::write(workspace_dir.join("movies/intro.cfg"), "base").unwrap();
+        // Real edits, with and without a size change, and a workspace-only file
+        fs::write(workspace_dir.join("data/edited.cfg"), "edit").unwrap();
+        fs::write(workspace_dir.join("data/resized.cfg"), "edited").unwrap();
+        fs::write(workspace_dir.join("data/new.cfg"), "base").unwrap();
+        for rel_path in ["data/copied.cfg", "shadowed.cfg", "movies/intro.cfg", "data/edited.cfg", "data/resized.cfg", "data/new.cfg"] {
+            let blob = cache.ensure_blob(workspace_dir.join(rel_path)).unwrap();
+            cache.add_ref(&blob, "test", rel_path).unwrap();
+        }
+
+        let report = vfs.prune_redundant_overrides("test", &cache).unwrap();
+        assert_eq!(report.pruned, vec!["data/copied.cfg".to_string()]);
+        assert_eq!(report.bytes_reclaimed, 4);
+        assert!(!workspace_dir.join("data/copied.cfg").exists());
+        assert_eq!(vfs.get_node("data/copied.cfg").unwrap().source, VirtualNodeSource::Base);
+        assert!(cache.find_blob_hash_for_file("test", "data/copied.cfg").unwrap().is_none());
+
+        for rel_path in ["shadowed.cfg", "movies/intro.cfg", "data/edited.cfg", "data/resized.cfg", "data/new.cfg"] {
+            assert!(workspace_dir.join(rel_path).exists(), "{} was pruned", rel_path);
+        }
+        assert_eq!(cache.load_index().unwrap().refs.values().map(|refs| refs.len()).sum::<usize>(), 5);
+
+        // Nothing is left to prune
+        assert!(vfs.prune_redundant_overrides("test", &cache).unwrap().pruned.is_empty());
+    }
 }
\ No newline at end of file
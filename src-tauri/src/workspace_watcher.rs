@@ -1,85 +1,48 @@
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fs;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
 use log::{info, warn, error, debug};
 use tauri::Emitter;
-use crate::blob_cache::BlobCache;
+use walkdir::WalkDir;
+use crate::blob_cache::{BlobCache, HashMode};
+use crate::long_path::from_long_path;
+use crate::path_utils::{is_link, compile_globs, matches_any_glob, are_files_hardlinked, rel_path_key};
+use crate::profiles::Profile;
 use crate::settings::Settings;
 
-/// Check if two files are hardlinked using Windows API
-#[cfg(windows)]
-pub fn are_files_hardlinked(path1: &Path, path2: &Path) -> bool {
-    use windows::Win32::Foundation::HANDLE;
-    use windows::Win32::Storage::FileSystem::{
-        CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
-        OPEN_EXISTING, FILE_READ_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE,
-    };
-    use windows::core::PCWSTR;
-    use std::os::windows::ffi::OsStrExt;
-    
-    unsafe {
-        // Convert paths to wide strings
-        let path1_wide: Vec<u16> = path1.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
-        let path2_wide: Vec<u16> = path2.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
-        
-        // Open both files
-        let handle1 = CreateFileW(
-            PCWSTR(path1_wide.as_ptr()),
-            FILE_READ_ATTRIBUTES.0,
-            FILE_SHARE_READ | FILE_SHARE_WRITE,
-            None,
-            OPEN_EXISTING,
-            Default::default(),
-            None,
-        );
-        
-        let handle2 = CreateFileW(
-            PCWSTR(path2_wide.as_ptr()),
-            FILE_READ_ATTRIBUTES.0,
-            FILE_SHARE_READ | FILE_SHARE_WRITE,
-            None,
-            OPEN_EXISTING,
-            Default::default(),
-            None,
-        );
-        
-        let (handle1, handle2) = match (handle1, handle2) {
-            (Ok(h1), Ok(h2)) => (h1, h2),
-            _ => return false,
-        };
-        
-        if handle1.is_invalid() || handle2.is_invalid() {
-            return false;
-        }
-        
-        // Get file information
-        let mut info1 = BY_HANDLE_FILE_INFORMATION::default();
-        let mut info2 = BY_HANDLE_FILE_INFORMATION::default();
-        
-        let success1 = GetFileInformationByHandle(handle1, &mut info1).is_ok();
-        let success2 = GetFileInformationByHandle(handle2, &mut info2).is_ok();
-        
-        if success1 && success2 {
-            // Compare volume serial and file index
-            info1.dwVolumeSerialNumber == info2.dwVolumeSerialNumber &&
-            info1.nFileIndexLow == info2.nFileIndexLow &&
-            info1.nFileIndexHigh == info2.nFileIndexHigh
-        } else {
-            false
-        }
-    }
-}
+/// How long a file must go unmodified before it's normalized, unless configured otherwise
+pub const DEFAULT_IDLE_THRESHOLD: Duration = Duration::from_secs(1);
 
-#[cfg(not(windows))]
-fn are_files_hardlinked(_path1: &Path, _path2: &Path) -> bool {
-    // On non-Windows platforms, we'd use dev/ino comparison
-    // For now, just return false to disable optimization
-    false
-}
+/// How often the polling fallback rescans the workspace, unless configured otherwise
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Consecutive normalization failures after which a file is quarantined, unless configured otherwise
+pub const DEFAULT_QUARANTINE_THRESHOLD: u32 = 5;
+
+/// File in the profile directory (next to the workspace) listing the workspace's quarantined files
+pub const QUARANTINE_FILE: &str = "quarantine.json";
+
+/// Times the watchdog restarts a watcher whose debounce thread died before giving up, unless configured otherwise
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// How often the watchdog checks that running watchers are still alive
+pub const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wait after a watchdog restart before the next one, doubled for every restart after it, unless configured otherwise
+pub const DEFAULT_RESTART_BACKOFF: Duration = Duration::from_secs(10);
+
+/// How often the debounce thread checks that the workspace directory still exists
+const WORKSPACE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of recent normalizations and deletions a watcher remembers
+pub const ACTIVITY_CAPACITY: usize = 200;
 
 /// Debounced file change event
 #[derive(Debug, Clone)]
@@ -97,30 +60,348 @@ pub enum FileChangeKind {
     Renamed,
 }
 
+/// Whether a workspace file is normalized: hardlinked to its blob with a matching index reference
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NormalizationStatus {
+    /// Whether the file exists in the workspace
+    pub exists: bool,
+    /// Whether the file is a hardlink to the blob its reference points at
+    pub is_hardlinked: bool,
+    /// Whether the index has a reference for this profile and path
+    pub has_reference: bool,
+    /// Hash of the referenced blob, if any
+    pub blob_hash: Option<String>,
+}
+
+/// File system watching backend in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatcherBackend {
+    /// The OS change notification API
+    Native,
+    /// Periodic rescans, used when the native backend can't be created (higher latency and CPU)
+    Polling,
+}
+
+/// Which parts of the workspace a watcher registers with the OS and accepts events from
+/// Narrowing it saves watch handles on very large workspace trees
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchScope {
+    /// Subdirectories to watch, relative to the workspace (empty watches the workspace root)
+    pub roots: Vec<PathBuf>,
+    /// Whether subdirectories of the roots are watched too, or only the files directly in them
+    pub recursive: bool,
+}
+
+impl Default for WatchScope {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            recursive: true,
+        }
+    }
+}
+
+impl WatchScope {
+    /// Directories to register with the watcher backend
+    fn root_paths(&self, workspace_path: &Path) -> Vec<PathBuf> {
+        if self.roots.is_empty() {
+            return vec![workspace_path.to_path_buf()];
+        }
+        self.roots.iter().map(|root| workspace_path.join(root)).collect()
+    }
+
+    fn recursive_mode(&self) -> RecursiveMode {
+        if self.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        }
+    }
+
+    /// Whether a file at `rel_path` (relative to the workspace) is within the scope
+    pub fn includes(&self, rel_path: &Path) -> bool {
+        let parent = rel_path.parent().unwrap_or(Path::new(""));
+        if self.roots.is_empty() {
+            return self.recursive || parent.as_os_str().is_empty();
+        }
+        self.roots.iter().any(|root| {
+            if self.recursive {
+                rel_path.starts_with(root) && rel_path != root.as_path()
+            } else {
+                parent == root.as_path()
+            }
+        })
+    }
+}
+
+/// Sent to the UI when a watcher falls back to polling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatcherFallback {
+    pub profile_name: String,
+    /// Why the native backend couldn't be used
+    pub error: String,
+    pub poll_interval_ms: u64,
+}
+
+/// Sent to the UI when the watchdog restarts a dead watcher, or gives up on one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatcherRestart {
+    pub profile_name: String,
+    /// Restart attempt this was (for an exhausted watcher, the attempt that wasn't made)
+    pub attempt: u32,
+    pub max_restarts: u32,
+    /// Why starting the watcher again failed, if it did
+    pub error: Option<String>,
+}
+
+/// What the watchdog did about a watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchdogOutcome {
+    /// Not watching, or watching with a live debounce thread
+    Healthy,
+    /// The watcher had died and a restart was attempted
+    Restarted,
+    /// The watcher is down, waiting out the backoff after its last restart
+    BackingOff,
+    /// The watcher had died with no restarts left, so it was left stopped
+    Exhausted,
+}
+
+/// Health of a workspace watcher, updated by its debounce thread
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatcherStatus {
+    /// Whether the watcher is currently receiving file system events
+    pub running: bool,
+    /// When the watcher was started (RFC 3339)
+    pub started_at: Option<String>,
+    /// Number of file changes processed since the watcher started
+    pub events_processed: u64,
+    /// Most recent error hit by the watcher, if any
+    pub last_error: Option<String>,
+    /// Backend receiving file system events while running
+    #[serde(default)]
+    pub backend: Option<WatcherBackend>,
+    /// Times the watchdog has restarted the watcher since it was last started explicitly
+    #[serde(default)]
+    pub restarts: u32,
+}
+
+impl WatcherStatus {
+    fn record_error(&mut self, error: String) {
+        self.last_error = Some(error);
+    }
+}
+
+/// Watcher status shared between the watcher and its debounce thread
+pub type SharedWatcherStatus = Arc<Mutex<WatcherStatus>>;
+
+/// What the watcher did to a workspace file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityKind {
+    /// Stored in the cache (and linked back) after being added or edited
+    Normalized,
+    /// Removed from the workspace, dropping its reference
+    Deleted,
+}
+
+/// One entry in a watcher's recent activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    /// Path relative to the workspace, with forward slashes
+    pub rel_path: String,
+    pub kind: ActivityKind,
+    /// Blob the file was normalized into, or the blob a deleted file referenced
+    pub hash: Option<String>,
+    /// When it happened (RFC 3339)
+    pub timestamp: String,
+}
+
+/// Recent activity shared between the watcher and its debounce thread, oldest first and at most `ACTIVITY_CAPACITY` long
+pub type SharedActivity = Arc<Mutex<VecDeque<ActivityEntry>>>;
+
+/// A workspace file the watcher stopped retrying after it failed to normalize too many times in a row
+/// Also sent to the UI when a file is quarantined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedFile {
+    pub profile_name: String,
+    /// Path relative to the workspace, with forward slashes
+    pub rel_path: String,
+    /// Consecutive failed attempts before the file was quarantined
+    pub failures: u32,
+    /// Error from the last attempt
+    pub last_error: String,
+    /// When the file was quarantined (RFC 3339)
+    pub quarantined_at: String,
+}
+
+/// Held while a quarantine list is read and written back, so watchers of the same workspace
+/// (e.g. one created just to release a file) don't overwrite each other's changes
+static QUARANTINE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Consecutive normalization failures per workspace file, and the files quarantined for failing too often
+/// Quarantined files are persisted so they stay skipped across restarts; failure counts are not.
+/// The list is always read from disk, so every watcher of the workspace sees the same files
+#[derive(Debug)]
+pub struct FileQuarantine {
+    file: PathBuf,
+    /// Failures before a file is quarantined (0 never quarantines)
+    threshold: u32,
+    failures: HashMap<String, u32>,
+}
+
+impl FileQuarantine {
+    /// Quarantine backed by the list stored at `file`
+    pub fn new(file: PathBuf, threshold: u32) -> Self {
+        Self {
+            file,
+            threshold,
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Only guards the list file, so a thread that panicked holding it left nothing to refuse over
+    fn lock_list() -> MutexGuard<'static, ()> {
+        QUARANTINE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Read the quarantine list; a missing or unreadable list is empty
+    fn load(&self) -> BTreeMap<String, QuarantinedFile> {
+        match fs::read_to_string(&self.file) {
+            Ok(content) => serde_json::from_str::<Vec<QuarantinedFile>>(&content)
+                .map(|files| files.into_iter().map(|entry| (entry.rel_path.clone(), entry)).collect())
+                .unwrap_or_else(|e| {
+                    warn!("Ignoring unreadable quarantine list {}: {}", self.file.display(), e);
+                    BTreeMap::new()
+                }),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+
+    fn save(&self, quarantined: &BTreeMap<String, QuarantinedFile>) {
+        let files: Vec<&QuarantinedFile> = quarantined.values().collect();
+        let result = serde_json::to_string_pretty(&files)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(&self.file, json));
+        if let Err(e) = result {
+            warn!("Failed to save quarantine list {}: {}", self.file.display(), e);
+        }
+    }
+
+    /// Load the list, apply `change` and save the list if it reports a change, all under the quarantine lock
+    fn update<R>(&self, change: impl FnOnce(&mut BTreeMap<String, QuarantinedFile>) -> (R, bool)) -> R {
+        let _guard = Self::lock_list();
+        let mut quarantined = self.load();
+        let (result, changed) = change(&mut quarantined);
+        if changed {
+            self.save(&quarantined);
+        }
+        result
+    }
+
+    pub fn is_quarantined(&self, rel_path: &str) -> bool {
+        let _guard = Self::lock_list();
+        self.load().contains_key(rel_path)
+    }
+
+    /// Quarantined files, ordered by path
+    pub fn files(&self) -> Vec<QuarantinedFile> {
+        let _guard = Self::lock_list();
+        self.load().into_values().collect()
+    }
+
+    /// Count a failed attempt; returns the entry if this failure quarantined the file
+    fn record_failure(&mut self, profile_name: &str, rel_path: &str, error: &str) -> Option<QuarantinedFile> {
+        let failures = self.failures.entry(rel_path.to_string()).or_insert(0);
+        *failures += 1;
+        if self.threshold == 0 || *failures < self.threshold {
+            return None;
+        }
+
+        let entry = QuarantinedFile {
+            profile_name: profile_name.to_string(),
+            rel_path: rel_path.to_string(),
+            failures: *failures,
+            last_error: error.to_string(),
+            quarantined_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.failures.remove(rel_path);
+        self.update(|quarantined| {
+            quarantined.insert(rel_path.to_string(), entry.clone());
+            ((), true)
+        });
+        Some(entry)
+    }
+
+    fn record_success(&mut self, rel_path: &str) {
+        self.failures.remove(rel_path);
+    }
+
+    /// Take a file out of quarantine so it's retried; returns false if it wasn't quarantined
+    pub fn release(&mut self, rel_path: &str) -> bool {
+        self.failures.remove(rel_path);
+        self.update(|quarantined| {
+            let released = quarantined.remove(rel_path).is_some();
+            (released, released)
+        })
+    }
+}
+
+/// Quarantine shared between the watcher and its debounce thread
+pub type SharedQuarantine = Arc<Mutex<FileQuarantine>>;
+
+/// The watcher's settings and shared state, handed to the debounce thread when a watch starts
+struct DebounceConfig {
+    profile_name: String,
+    workspace_path: PathBuf,
+    cache: BlobCache,
+    app_handle: Option<tauri::AppHandle>,
+    status: SharedWatcherStatus,
+    quarantine: SharedQuarantine,
+    activity: SharedActivity,
+    clear_requested: Arc<AtomicBool>,
+    follow_links: bool,
+    protected_globs: Vec<glob::Pattern>,
+    watch_scope: WatchScope,
+    keep_workspace_copies: bool,
+    idle_threshold: Duration,
+}
+
 /// Workspace watcher that normalizes files to global cache
 pub struct WorkspaceWatcher {
     profile_name: String,
     workspace_path: PathBuf,
     cache: BlobCache,
-    watcher: Option<RecommendedWatcher>,
+    watcher: Option<Box<dyn Watcher + Send>>,
     event_sender: Option<Sender<notify::Result<notify::Event>>>,
     app_handle: Option<tauri::AppHandle>,
+    status: SharedWatcherStatus,
+    /// Set to ask the debounce thread to drop its backlog and stop the in-flight batch
+    clear_requested: Arc<AtomicBool>,
+    follow_links: bool,
+    protected_globs: Vec<glob::Pattern>,
+    keep_workspace_copies: bool,
+    idle_threshold: Duration,
+    poll_interval: Duration,
+    quarantine: SharedQuarantine,
+    watch_scope: WatchScope,
+    activity: SharedActivity,
+    /// Debounce thread of the current watch, which only exits on its own if the watch died
+    debounce_thread: Option<thread::JoinHandle<()>>,
+    /// Whether the watcher was started and not explicitly stopped since
+    should_watch: bool,
+    restarts: u32,
+    max_restarts: u32,
+    restart_backoff: Duration,
+    /// Earliest time the watchdog may restart the watcher again
+    next_restart_at: Option<Instant>,
 }
 
 impl WorkspaceWatcher {
-    pub fn new(profile_name: String, workspace_path: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
-        // Try to load existing settings, fall back to default cache location
-        let cache_dir = if let Some(settings) = Settings::try_load_existing() {
-            settings.get_cache_directory()
-        } else {
-            // Fallback to default location if no settings exist yet
-            let data_root = dirs::data_dir()
-                .unwrap_or_else(|| PathBuf::from("./data"))
-                .join("DeltaRuntime");
-            data_root.join("cache")
-        };
-        
+    /// Create a watcher that normalizes into the given cache directory
+    /// The cache dir must come from the loaded settings so the watcher and builder share one cache
+    pub fn new(profile_name: String, workspace_path: PathBuf, cache_dir: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
         let cache = BlobCache::new(cache_dir);
+        let quarantine = FileQuarantine::new(workspace_path.with_file_name(QUARANTINE_FILE), DEFAULT_QUARANTINE_THRESHOLD);
 
         Ok(Self {
             profile_name,
@@ -129,25 +410,295 @@ impl WorkspaceWatcher {
             watcher: None,
             event_sender: None,
             app_handle: None,
+            status: Arc::new(Mutex::new(WatcherStatus::default())),
+            clear_requested: Arc::new(AtomicBool::new(false)),
+            follow_links: false,
+            protected_globs: Vec::new(),
+            keep_workspace_copies: false,
+            idle_threshold: DEFAULT_IDLE_THRESHOLD,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            quarantine: Arc::new(Mutex::new(quarantine)),
+            watch_scope: WatchScope::default(),
+            activity: Arc::new(Mutex::new(VecDeque::new())),
+            debounce_thread: None,
+            should_watch: false,
+            restarts: 0,
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            restart_backoff: DEFAULT_RESTART_BACKOFF,
+            next_restart_at: None,
         })
     }
 
+    /// Create a watcher for a profile, configured from the settings with the profile's overrides applied
+    pub fn for_profile(settings: &Settings, profile: &Profile) -> Result<Self, Box<dyn std::error::Error>> {
+        let settings = settings.with_overrides(&profile.metadata.overrides);
+        let preferences = &settings.preferences;
+        let mut watcher = Self::new(
+            profile.metadata.name.clone(),
+            profile.workspace_dir.clone(),
+            settings.get_cache_directory(),
+        )?;
+
+        watcher.set_cache_namespace(settings.cache_namespace.clone());
+        watcher.set_follow_links(preferences.follow_links);
+        watcher.set_preserve_mtimes(preferences.preserve_mtimes);
+        watcher.set_quick_fingerprint_min_size(preferences.quick_fingerprint_min_size());
+        watcher.set_hash_mode(HashMode::from_name(&preferences.hash_mode));
+        watcher.set_protected_globs(&preferences.protected_globs)?;
+        watcher.set_keep_workspace_copies(preferences.keeps_workspace_copies());
+        watcher.set_idle_threshold(Duration::from_secs(preferences.normalize_idle_seconds));
+        watcher.set_poll_interval(Duration::from_millis(preferences.watcher_poll_interval_ms));
+        watcher.set_quarantine_threshold(preferences.quarantine_after_failures);
+        watcher.set_watch_scope(WatchScope {
+            roots: preferences.watch_roots.iter().map(PathBuf::from).collect(),
+            recursive: preferences.watch_recursive,
+        });
+        Ok(watcher)
+    }
+
     pub fn set_app_handle(&mut self, app_handle: tauri::AppHandle) {
         self.app_handle = Some(app_handle);
     }
 
+    /// Set the namespace references are recorded under when the cache is shared between data roots
+    pub fn set_cache_namespace(&mut self, namespace: Option<String>) {
+        self.cache = self.cache.clone().with_namespace(namespace);
+    }
+
+    /// Set whether normalizing a file keeps its original modification time
+    pub fn set_preserve_mtimes(&mut self, preserve_mtimes: bool) {
+        self.cache = self.cache.clone().with_preserve_mtimes(preserve_mtimes);
+    }
+
+    /// Set the size from which files are checked for changes by quick fingerprint (None always hashes in full)
+    pub fn set_quick_fingerprint_min_size(&mut self, min_size: Option<u64>) {
+        self.cache = self.cache.clone().with_quick_fingerprints(min_size);
+    }
+
+    /// Set whether the watcher hashes files single-threaded or in parallel
+    pub fn set_hash_mode(&mut self, mode: HashMode) {
+        self.cache = self.cache.clone().with_hash_mode(mode);
+    }
+
+    /// Set whether symlinks and junctions in the workspace are followed and normalized
+    pub fn set_follow_links(&mut self, follow_links: bool) {
+        self.follow_links = follow_links;
+    }
+
+    /// Set glob patterns for workspace files that must never be normalized into blobs
+    /// Fails if any pattern isn't a valid glob
+    pub fn set_protected_globs(&mut self, protected_globs: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.protected_globs = compile_globs(protected_globs)?;
+        Ok(())
+    }
+
+    /// Set whether normalized files stay real copies in the workspace instead of becoming hardlinks to their blobs
+    pub fn set_keep_workspace_copies(&mut self, keep_workspace_copies: bool) {
+        self.keep_workspace_copies = keep_workspace_copies;
+    }
+
+    /// Set how long a file must go unmodified before it's normalized, so files still being edited aren't hardlinked
+    pub fn set_idle_threshold(&mut self, idle_threshold: Duration) {
+        self.idle_threshold = idle_threshold;
+    }
+
+    /// Set how often the workspace is rescanned if the watcher has to fall back to polling
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval;
+    }
+
+    /// Limit watching to some subdirectories of the workspace, or to the files directly in them
+    /// Takes effect the next time watching starts; `normalize_all` still covers the whole workspace
+    pub fn set_watch_scope(&mut self, watch_scope: WatchScope) {
+        self.watch_scope = watch_scope;
+    }
+
+    /// Set how many consecutive normalization failures quarantine a file (0 never quarantines)
+    pub fn set_quarantine_threshold(&mut self, threshold: u32) {
+        if let Ok(mut quarantine) = self.quarantine.lock() {
+            quarantine.threshold = threshold;
+        }
+    }
+
+    /// Set how many times the watchdog restarts this watcher after it dies (0 never restarts it)
+    pub fn set_max_restarts(&mut self, max_restarts: u32) {
+        self.max_restarts = max_restarts;
+    }
+
+    /// Set the wait after the watchdog's first restart before it may restart again; doubled for every restart after it
+    pub fn set_restart_backoff(&mut self, restart_backoff: Duration) {
+        self.restart_backoff = restart_backoff;
+    }
+
+    /// Files the watcher has stopped retrying
+    pub fn quarantined_files(&self) -> Vec<QuarantinedFile> {
+        self.quarantine.lock()
+            .map(|quarantine| quarantine.files())
+            .unwrap_or_default()
+    }
+
+    /// A copy of the watcher that shares its cache, status, quarantine and activity but not the running watch,
+    /// for one-off work that shouldn't hold the watcher registry lock
+    pub fn detached(&self) -> Self {
+        Self {
+            profile_name: self.profile_name.clone(),
+            workspace_path: self.workspace_path.clone(),
+            cache: self.cache.clone(),
+            watcher: None,
+            event_sender: None,
+            app_handle: self.app_handle.clone(),
+            status: self.status.clone(),
+            clear_requested: self.clear_requested.clone(),
+            follow_links: self.follow_links,
+            protected_globs: self.protected_globs.clone(),
+            keep_workspace_copies: self.keep_workspace_copies,
+            idle_threshold: self.idle_threshold,
+            poll_interval: self.poll_interval,
+            quarantine: self.quarantine.clone(),
+            watch_scope: self.watch_scope.clone(),
+            activity: self.activity.clone(),
+            debounce_thread: None,
+            should_watch: false,
+            restarts: 0,
+            max_restarts: self.max_restarts,
+            restart_backoff: self.restart_backoff,
+            next_restart_at: None,
+        }
+    }
+
+    /// Take a file out of quarantine and retry normalizing it right away
+    /// `rel_path` may use either separator. Returns false if the file wasn't quarantined
+    pub fn unquarantine_file(&self, rel_path: &str) -> bool {
+        let rel_path = rel_path.replace('\\', "/");
+        let released = self.quarantine.lock()
+            .map(|mut quarantine| quarantine.release(&rel_path))
+            .unwrap_or(false);
+        if !released {
+            return false;
+        }
+        info!("Released quarantined file: {} | Profile: {}", rel_path, self.profile_name);
+
+        let change = FileChangeEvent {
+            path: self.workspace_path.join(&rel_path),
+            kind: FileChangeKind::Modified,
+            timestamp: Instant::now(),
+        };
+        Self::process_file_changes(&[change], &self.one_off_config());
+        true
+    }
+
+    /// The most recent normalizations and deletions, newest first
+    pub fn recent_activity(&self, limit: usize) -> Vec<ActivityEntry> {
+        self.activity.lock()
+            .map(|activity| activity.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get a snapshot of the watcher's current status
+    pub fn status(&self) -> WatcherStatus {
+        match self.status.lock() {
+            Ok(status) => status.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Drop all pending changes and stop normalizing the current batch
+    /// Files already normalized keep their blobs and refs; the remainder is left untouched
+    pub fn clear_pending_normalization(&self) {
+        self.clear_requested.store(true, Ordering::SeqCst);
+        info!("Requested clearing of pending normalization for profile '{}'", self.profile_name);
+    }
+
     /// Start watching the workspace directory
+    /// Falls back to polling if the native backend can't be created (network drives, some policies)
     pub fn start_watching(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.should_watch = true;
+        self.restarts = 0;
+        self.next_restart_at = None;
+        self.start_backend()
+    }
+
+    /// Create the file system watcher and its debounce thread
+    fn start_backend(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // The polling backend accepts missing paths, so check up front
+        if !self.workspace_path.is_dir() {
+            let message = format!("Workspace directory not found: {}", self.workspace_path.display());
+            if let Ok(mut status) = self.status.lock() {
+                status.running = false;
+                status.record_error(format!("Failed to start watcher: {}", message));
+            }
+            return Err(message.into());
+        }
+
         let (tx, rx) = mpsc::channel();
+        let config = Config::default().with_follow_symlinks(self.follow_links);
         
-        // Create watcher with Windows backend
-        let mut watcher = RecommendedWatcher::new(
-            tx.clone(),
-            Config::default(),
-        )?;
+        // Roots that don't exist yet are left out, like any directory created after watching starts
+        let mode = self.watch_scope.recursive_mode();
+        let roots: Vec<PathBuf> = self.watch_scope.root_paths(&self.workspace_path)
+            .into_iter()
+            .filter(|root| {
+                let exists = root.is_dir();
+                if !exists {
+                    warn!("Skipping missing watch root: {}", root.display());
+                }
+                exists
+            })
+            .collect();
+        let watch_roots = |watcher: &mut dyn Watcher| -> notify::Result<()> {
+            for root in &roots {
+                watcher.watch(root, mode)?;
+            }
+            Ok(())
+        };
+        
+        // Create watcher with the OS backend and watch the workspace roots
+        let native = RecommendedWatcher::new(tx.clone(), config)
+            .and_then(|mut watcher| {
+                watch_roots(&mut watcher)?;
+                Ok(watcher)
+            });
+
+        let (watcher, backend): (Box<dyn Watcher + Send>, WatcherBackend) = match native {
+            Ok(watcher) => (Box::new(watcher), WatcherBackend::Native),
+            Err(native_error) => {
+                warn!(
+                    "Native watcher unavailable for {} ({}), falling back to polling every {:?}",
+                    self.workspace_path.display(), native_error, self.poll_interval
+                );
+                let polling = PollWatcher::new(tx.clone(), config.with_poll_interval(self.poll_interval))
+                    .and_then(|mut watcher| {
+                        watch_roots(&mut watcher)?;
+                        Ok(watcher)
+                    });
 
-        // Watch the workspace directory recursively
-        watcher.watch(&self.workspace_path, RecursiveMode::Recursive)?;
+                match polling {
+                    Ok(watcher) => {
+                        self.send_fallback_warning(&native_error.to_string());
+                        (Box::new(watcher), WatcherBackend::Polling)
+                    }
+                    Err(e) => {
+                        if let Ok(mut status) = self.status.lock() {
+                            status.running = false;
+                            status.backend = None;
+                            status.record_error(format!("Failed to start watcher: {} (polling fallback: {})", native_error, e));
+                        }
+                        return Err(e.into());
+                    }
+                }
+            }
+        };
+
+        if let Ok(mut status) = self.status.lock() {
+            *status = WatcherStatus {
+                running: true,
+                started_at: Some(chrono::Utc::now().to_rfc3339()),
+                events_processed: 0,
+                last_error: None,
+                backend: Some(backend),
+                restarts: self.restarts,
+            };
+        }
 
         self.watcher = Some(watcher);
         self.event_sender = Some(tx);
@@ -156,88 +707,234 @@ impl WorkspaceWatcher {
         // Self::normalize_existing_files(&self.profile_name, &self.workspace_path, &self.cache)?;
 
         // Start the debounce thread
-        let profile_name = self.profile_name.clone();
-        let workspace_path = self.workspace_path.clone();
-        let cache = BlobCache::new(self.cache.cache_dir.clone());
-        let app_handle = self.app_handle.clone();
+        let config = self.debounce_config();
 
-        thread::spawn(move || {
-            Self::debounce_handler(rx, profile_name, workspace_path, cache, app_handle);
-        });
+        self.debounce_thread = Some(thread::spawn(move || {
+            Self::debounce_handler(rx, config);
+        }));
 
         info!("Started watching workspace: {}", self.workspace_path.display());
         Ok(())
     }
 
+    /// The watcher's settings and shared state, as the debounce thread and one-off batches use them
+    fn debounce_config(&self) -> DebounceConfig {
+        DebounceConfig {
+            profile_name: self.profile_name.clone(),
+            workspace_path: self.workspace_path.clone(),
+            cache: self.cache.clone(),
+            app_handle: self.app_handle.clone(),
+            status: self.status.clone(),
+            quarantine: self.quarantine.clone(),
+            activity: self.activity.clone(),
+            clear_requested: self.clear_requested.clone(),
+            follow_links: self.follow_links,
+            protected_globs: self.protected_globs.clone(),
+            watch_scope: self.watch_scope.clone(),
+            keep_workspace_copies: self.keep_workspace_copies,
+            idle_threshold: self.idle_threshold,
+        }
+    }
+
+    /// Config for a batch run outside the debounce thread, which a clear meant for the watched backlog doesn't stop
+    fn one_off_config(&self) -> DebounceConfig {
+        DebounceConfig {
+            clear_requested: Arc::new(AtomicBool::new(false)),
+            ..self.debounce_config()
+        }
+    }
+
     /// Stop watching the workspace
     pub fn stop_watching(&mut self) {
+        self.should_watch = false;
         if let Some(watcher) = self.watcher.take() {
             drop(watcher);
         }
         self.event_sender = None;
+        self.debounce_thread = None;
+        if let Ok(mut status) = self.status.lock() {
+            status.running = false;
+            status.backend = None;
+        }
         info!("Stopped watching workspace: {}", self.workspace_path.display());
     }
 
+    /// Restart the watcher if its debounce thread exited without it being stopped, e.g. because the
+    /// notify backend died on sleep/resume or a drive disconnect, or the workspace directory went missing.
+    /// Restarts up to the retry limit with a growing backoff between them, after which the watcher is
+    /// left stopped with the error in its status
+    pub fn supervise(&mut self) -> WatchdogOutcome {
+        let handler_exited = self.debounce_thread.as_ref().map_or(true, |thread| thread.is_finished());
+        if !self.should_watch || (self.watcher.is_some() && !handler_exited) {
+            return WatchdogOutcome::Healthy;
+        }
+
+        // Drop what's left of the dead watch
+        self.watcher = None;
+        self.event_sender = None;
+        self.debounce_thread = None;
+
+        let attempt = self.restarts + 1;
+        if attempt > self.max_restarts {
+            error!("Watcher for profile '{}' stopped unexpectedly and no restarts are left", self.profile_name);
+            self.should_watch = false;
+            if let Ok(mut status) = self.status.lock() {
+                status.running = false;
+                status.backend = None;
+                status.record_error(format!("Watcher stopped unexpectedly after {} restarts", self.restarts));
+            }
+            self.send_restart_event("watcher-restarts-exhausted", attempt, None);
+            return WatchdogOutcome::Exhausted;
+        }
+        if self.next_restart_at.is_some_and(|next_restart_at| Instant::now() < next_restart_at) {
+            return WatchdogOutcome::BackingOff;
+        }
+
+        warn!(
+            "Watcher for profile '{}' stopped unexpectedly, restarting (attempt {} of {})",
+            self.profile_name, attempt, self.max_restarts
+        );
+        self.restarts = attempt;
+        self.next_restart_at = Some(Instant::now() + self.restart_backoff.saturating_mul(1 << (attempt - 1).min(16)));
+        let error = self.start_backend().err().map(|e| e.to_string());
+        if let Ok(mut status) = self.status.lock() {
+            status.restarts = attempt;
+        }
+        self.send_restart_event("watcher-restarted", attempt, error);
+        WatchdogOutcome::Restarted
+    }
+
     /// Debounce handler that batches file changes
-    fn debounce_handler(
-        rx: Receiver<notify::Result<notify::Event>>,
-        profile_name: String,
-        workspace_path: PathBuf,
-        cache: BlobCache,
-        app_handle: Option<tauri::AppHandle>,
-    ) {
+    /// A batch is flushed once the workspace is quiet, but only with the files that have been idle for `idle_threshold`
+    fn debounce_handler(rx: Receiver<notify::Result<notify::Event>>, config: DebounceConfig) {
+        let DebounceConfig {
+            profile_name,
+            workspace_path,
+            app_handle,
+            status,
+            clear_requested,
+            follow_links,
+            protected_globs,
+            watch_scope,
+            idle_threshold,
+            ..
+        } = &config;
         let mut pending_changes: HashMap<PathBuf, FileChangeEvent> = HashMap::new();
         let debounce_duration = Duration::from_millis(200); // 200ms debounce
         let mut last_activity = Instant::now();
+        let mut last_workspace_check = Instant::now();
+
+        // Exiting leaves the watcher for the watchdog to restart
+        let watch_died = |reason: String| {
+            error!("Watcher for profile '{}' stopped: {}", profile_name, reason);
+            if let Ok(mut status) = status.lock() {
+                status.running = false;
+                status.record_error(reason);
+            }
+        };
 
         loop {
+            // Drop the backlog if a clear was requested (an in-flight batch has already stopped)
+            if clear_requested.swap(false, Ordering::SeqCst) {
+                info!("Cleared {} pending changes for profile '{}'", pending_changes.len(), profile_name);
+                pending_changes.clear();
+            }
+
             // Try to receive events with a timeout
             match rx.recv_timeout(Duration::from_millis(50)) {
                 Ok(event_result) => {
                     match event_result {
                         Ok(event) => {
                             last_activity = Instant::now();
-                            Self::process_notify_event(event, &workspace_path, &mut pending_changes);
+                            Self::process_notify_event(event, workspace_path, *follow_links, protected_globs, watch_scope, &mut pending_changes);
+                        }
+                        Err(e) if Self::is_fatal_watch_error(&e) || !workspace_path.is_dir() => {
+                            watch_died(format!("File watcher error: {}", e));
+                            break;
                         }
                         Err(e) => {
                             warn!("File watcher error: {}", e);
+                            if let Ok(mut status) = status.lock() {
+                                status.record_error(format!("File watcher error: {}", e));
+                            }
                         }
                     }
                 }
                 Err(mpsc::RecvTimeoutError::Timeout) => {
+                    // Some backends report nothing when the watched directory is removed
+                    if last_workspace_check.elapsed() >= WORKSPACE_CHECK_INTERVAL {
+                        last_workspace_check = Instant::now();
+                        if !workspace_path.is_dir() {
+                            watch_died(format!("Workspace directory not found: {}", workspace_path.display()));
+                            break;
+                        }
+                    }
+
                     // Check if we should process pending changes
                     if !pending_changes.is_empty() && 
                        last_activity.elapsed() >= debounce_duration {
                         
-                        let changes: Vec<FileChangeEvent> = pending_changes.values().cloned().collect();
-                        pending_changes.clear();
+                        // Files modified too recently stay pending until they've been idle long enough
+                        let changes = Self::take_idle_changes(&mut pending_changes, *idle_threshold, Instant::now());
+                        if changes.is_empty() {
+                            continue;
+                        }
                         
                         // Process the batched changes
-                        let normalized_count = Self::process_file_changes(
-                            &changes, 
-                            &profile_name, 
-                            &workspace_path, 
-                            &cache
-                        );
+                        let normalized_count = Self::process_file_changes(&changes, &config);
 
                         // Send toast notification to UI
                         if normalized_count > 0 {
-                            Self::send_toast_notification(&app_handle, normalized_count);
+                            Self::send_toast_notification(app_handle, normalized_count);
                         }
                     }
                 }
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
                     debug!("Watcher channel disconnected");
+                    if let Ok(mut status) = status.lock() {
+                        status.running = false;
+                    }
                     break;
                 }
             }
         }
     }
 
+    /// Whether a backend error means the watch is gone rather than a single missed event
+    fn is_fatal_watch_error(error: &notify::Error) -> bool {
+        matches!(
+            error.kind,
+            notify::ErrorKind::PathNotFound | notify::ErrorKind::WatchNotFound | notify::ErrorKind::MaxFilesWatch
+        )
+    }
+
+    /// Remove and return the pending changes ready to process: files unmodified for `idle_threshold`, and deletions
+    /// Each event for a path replaces its pending change, so a file's idle timer restarts on every modification
+    fn take_idle_changes(
+        pending_changes: &mut HashMap<PathBuf, FileChangeEvent>,
+        idle_threshold: Duration,
+        now: Instant,
+    ) -> Vec<FileChangeEvent> {
+        let ready: Vec<PathBuf> = pending_changes.iter()
+            .filter(|(_, change)| {
+                change.kind == FileChangeKind::Deleted
+                    || now.saturating_duration_since(change.timestamp) >= idle_threshold
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        ready.into_iter()
+            .filter_map(|path| pending_changes.remove(&path))
+            .collect()
+    }
+
     /// Convert notify events to our file change events
     fn process_notify_event(
         event: notify::Event,
         workspace_path: &Path,
+        follow_links: bool,
+        protected_globs: &[glob::Pattern],
+        watch_scope: &WatchScope,
         pending_changes: &mut HashMap<PathBuf, FileChangeEvent>,
     ) {
         for path in event.paths {
@@ -246,6 +943,12 @@ impl WorkspaceWatcher {
                 continue;
             }
 
+            // Skip links (and anything reached through one) unless following them is enabled
+            if !follow_links && Self::is_within_link(&path, workspace_path) {
+                debug!("Skipping linked path: {}", path.display());
+                continue;
+            }
+
             // Skip directories and hidden files
             if path.is_dir() || 
                path.file_name()
@@ -255,6 +958,18 @@ impl WorkspaceWatcher {
                 continue;
             }
 
+            // Leave protected files as regular writable files, and ignore files outside the watch scope
+            if let Ok(rel_path) = Self::workspace_rel_path(&path, workspace_path) {
+                if !watch_scope.includes(&rel_path) {
+                    debug!("Skipping file outside the watch scope: {}", path.display());
+                    continue;
+                }
+                if matches_any_glob(&rel_path.to_string_lossy(), protected_globs) {
+                    debug!("Skipping protected file: {}", path.display());
+                    continue;
+                }
+            }
+
             let change_kind = match event.kind {
                 EventKind::Create(_) => FileChangeKind::Created,
                 EventKind::Modify(_) => FileChangeKind::Modified,
@@ -273,48 +988,151 @@ impl WorkspaceWatcher {
         }
     }
 
-    /// Process batched file changes and normalize them
-    fn process_file_changes(
-        changes: &[FileChangeEvent],
-        profile_name: &str,
-        workspace_path: &Path,
-        cache: &BlobCache,
-    ) -> usize {
-        let mut normalized_count = 0;
-
-        for change in changes {
-            match change.kind {
-                FileChangeKind::Created | FileChangeKind::Modified => {
-                    if let Err(e) = Self::normalize_file(&change.path, profile_name, workspace_path, cache) {
-                        error!("Failed to normalize file {}: {}", change.path.display(), e);
-                    } else {
-                        normalized_count += 1;
-                    }
-                }
-                FileChangeKind::Deleted => {
-                    if let Err(e) = Self::handle_file_deletion(&change.path, profile_name, workspace_path, cache) {
-                        error!("Failed to handle deletion of {}: {}", change.path.display(), e);
-                    }
-                }
-                FileChangeKind::Renamed => {
-                    // Treat renames as creation of new file
-                    if let Err(e) = Self::normalize_file(&change.path, profile_name, workspace_path, cache) {
-                        error!("Failed to normalize renamed file {}: {}", change.path.display(), e);
-                    } else {
-                        normalized_count += 1;
-                    }
-                }
+    /// Normalize every file in the workspace now, with the same filtering as watched changes
+    /// Doesn't need the watcher to be running, so a workspace that can't be watched can still be normalized by hand
+    /// Returns the number of files normalized
+    pub fn normalize_all(&self) -> usize {
+        let mut pending_changes = HashMap::new();
+        for entry in WalkDir::new(&self.workspace_path).follow_links(self.follow_links).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_dir() {
+                continue;
             }
+            let event = notify::Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(entry.into_path());
+            Self::process_notify_event(event, &self.workspace_path, self.follow_links, &self.protected_globs, &WatchScope::default(), &mut pending_changes);
         }
 
-        if normalized_count > 0 {
-            info!("Normalized {} files for profile '{}'", normalized_count, profile_name);
-        }
+        let changes: Vec<FileChangeEvent> = pending_changes.into_values().collect();
+        info!("Normalizing {} workspace files for profile '{}'", changes.len(), self.profile_name);
+        Self::process_file_changes(&changes, &self.one_off_config())
+    }
 
-        normalized_count
+    /// Check whether a path or any of its ancestors below the workspace root is a link
+    fn is_within_link(path: &Path, workspace_path: &Path) -> bool {
+        path.ancestors()
+            .take_while(|ancestor| *ancestor != workspace_path)
+            .any(is_link)
     }
 
-    /// Normalize all existing files in workspace when watcher starts
+    /// Process batched file changes and normalize them
+    /// Files that fail to normalize too many times in a row are quarantined and skipped from then on
+    fn process_file_changes(changes: &[FileChangeEvent], config: &DebounceConfig) -> usize {
+        let DebounceConfig {
+            profile_name,
+            workspace_path,
+            cache,
+            app_handle,
+            status,
+            quarantine,
+            activity,
+            clear_requested,
+            keep_workspace_copies,
+            ..
+        } = config;
+        let mut normalized_count = 0;
+        let mut processed_count = 0;
+        let mut last_error = None;
+
+        for change in changes {
+            // Stop between files so every file already handled stays consistent
+            if clear_requested.load(Ordering::SeqCst) {
+                info!("Skipping {} remaining changes for profile '{}'", changes.len() - processed_count, profile_name);
+                break;
+            }
+            processed_count += 1;
+
+            let rel_path = Self::workspace_rel_path(&change.path, workspace_path)
+                .ok()
+                .and_then(rel_path_key);
+
+            match change.kind {
+                // Renames are treated as creation of a new file
+                FileChangeKind::Created | FileChangeKind::Modified | FileChangeKind::Renamed => {
+                    if let Some(rel_path) = &rel_path {
+                        if quarantine.lock().is_ok_and(|quarantine| quarantine.is_quarantined(rel_path)) {
+                            debug!("Skipping quarantined file: {} | Profile: {}", rel_path, profile_name);
+                            continue;
+                        }
+                    }
+
+                    match Self::normalize_file(&change.path, profile_name, workspace_path, cache, *keep_workspace_copies) {
+                        Ok(new_hash) => {
+                            normalized_count += 1;
+                            if let (Some(rel_path), Ok(mut quarantine)) = (&rel_path, quarantine.lock()) {
+                                quarantine.record_success(rel_path);
+                            }
+                            if let (Some(rel_path), Some(hash)) = (&rel_path, new_hash) {
+                                Self::record_activity(activity, rel_path, ActivityKind::Normalized, Some(hash));
+                            }
+                        }
+                        Err(e) => {
+                            let what = if change.kind == FileChangeKind::Renamed { "renamed file" } else { "file" };
+                            let message = format!("Failed to normalize {} {}: {}", what, change.path.display(), e);
+                            error!("{}", message);
+
+                            let quarantined = match (&rel_path, quarantine.lock()) {
+                                (Some(rel_path), Ok(mut quarantine)) => quarantine.record_failure(profile_name, rel_path, &e.to_string()),
+                                _ => None,
+                            };
+                            if let Some(quarantined) = quarantined {
+                                error!("Quarantined {} after {} failed attempts | Profile: {}", quarantined.rel_path, quarantined.failures, profile_name);
+                                Self::send_quarantine_notification(app_handle, &quarantined);
+                            }
+                            last_error = Some(message);
+                        }
+                    }
+                }
+                FileChangeKind::Deleted => {
+                    // A deleted file has nothing left to retry
+                    if let (Some(rel_path), Ok(mut quarantine)) = (&rel_path, quarantine.lock()) {
+                        quarantine.release(rel_path);
+                    }
+                    match Self::handle_file_deletion(&change.path, profile_name, workspace_path, cache) {
+                        Ok(old_hash) => {
+                            // Only files the cache knew about were real workspace content
+                            if let (Some(rel_path), Some(hash)) = (&rel_path, old_hash) {
+                                Self::record_activity(activity, rel_path, ActivityKind::Deleted, Some(hash));
+                            }
+                        }
+                        Err(e) => {
+                            let message = format!("Failed to handle deletion of {}: {}", change.path.display(), e);
+                            error!("{}", message);
+                            last_error = Some(message);
+                        }
+                    }
+                }
+            }
+        }
+
+        if normalized_count > 0 {
+            info!("Normalized {} files for profile '{}'", normalized_count, profile_name);
+        }
+
+        if let Ok(mut status) = status.lock() {
+            status.events_processed += processed_count as u64;
+            if let Some(last_error) = last_error {
+                status.record_error(last_error);
+            }
+        }
+
+        normalized_count
+    }
+
+    /// Append to the recent activity, dropping the oldest entries beyond `ACTIVITY_CAPACITY`
+    fn record_activity(activity: &Mutex<VecDeque<ActivityEntry>>, rel_path: &str, kind: ActivityKind, hash: Option<blake3::Hash>) {
+        if let Ok(mut activity) = activity.lock() {
+            if activity.len() >= ACTIVITY_CAPACITY {
+                activity.pop_front();
+            }
+            activity.push_back(ActivityEntry {
+                rel_path: rel_path.to_string(),
+                kind,
+                hash: hash.map(|hash| hash.to_hex().to_string()),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            });
+        }
+    }
+
+    /// Normalize all existing files in workspace when watcher starts
     /// This ensures that manually copied files are converted to hardlinks
     // pub fn normalize_existing_files(
     //     profile_name: &str,
@@ -397,7 +1215,7 @@ impl WorkspaceWatcher {
                 }
                 
                 // Try to normalize this file
-                match Self::normalize_file(&path, profile_name, workspace_root, cache) {
+                match Self::normalize_file(&path, profile_name, workspace_root, cache, false) {
                     Ok(_) => {
                         *normalized_count += 1;
                         info!("Normalized existing file: {}", path.display());
@@ -412,75 +1230,83 @@ impl WorkspaceWatcher {
         Ok(())
     }
 
+    /// Path of a workspace file relative to the workspace
+    /// The watcher may report paths in long-path (`\\?\`) form while the workspace path isn't (or the
+    /// other way round), so both are brought to the plain form before stripping
+    fn workspace_rel_path(file_path: &Path, workspace_path: &Path) -> Result<PathBuf, std::path::StripPrefixError> {
+        let file_path = from_long_path(file_path);
+        file_path.strip_prefix(from_long_path(workspace_path)).map(Path::to_path_buf)
+    }
+
     /// Normalize a file: hash → ensure_blob → replace with hardlink
+    /// With `keep_copy` the workspace file stays a real copy; the blob and reference are still recorded so runtime builds dedup from the cache,
+    /// at the cost of storing the content twice (workspace + cache)
+    /// Returns the file's blob hash if it was normalized, or None if it was already up to date or skipped
     fn normalize_file(
         file_path: &Path,
         profile_name: &str,
         workspace_path: &Path,
         cache: &BlobCache,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        keep_copy: bool,
+    ) -> Result<Option<blake3::Hash>, Box<dyn std::error::Error>> {
         // Skip if file doesn't exist (might have been deleted while debouncing)
         if !file_path.exists() {
-            return Ok(());
+            return Ok(None);
         }
 
         // Get relative path within workspace
-        let rel_path = file_path.strip_prefix(workspace_path)?;
-        let rel_path_str = rel_path.to_string_lossy().to_string();
+        let rel_path = Self::workspace_rel_path(file_path, workspace_path)?;
+        let Some(rel_path_str) = rel_path_key(&rel_path) else {
+            warn!("Skipping file with a name that isn't valid Unicode: {} | Profile: {}", rel_path.display(), profile_name);
+            return Ok(None);
+        };
+
+        // A large file whose quick fingerprint still matches its reference is taken as unchanged without a full hash
+        if let Some(blob) = cache.unchanged_by_fingerprint(profile_name, &rel_path_str, file_path)? {
+            if keep_copy || are_files_hardlinked(file_path, &blob.path) {
+                debug!("File unchanged by quick fingerprint: {} | Profile: {}", rel_path_str, profile_name);
+                return Ok(None);
+            }
+        }
 
         // Hash the current file to check if it needs normalization
-        let current_hash = BlobCache::hash_file(file_path)?;
+        let current_hash = cache.hash(file_path)?;
+
+        // A copy is up to date once its blob exists and the reference points at it
+        if keep_copy
+            && cache.get_blob_path(&current_hash).exists()
+            && cache.find_blob_hash_for_file(profile_name, &rel_path_str)?.as_deref() == Some(current_hash.to_hex().as_str())
+        {
+            debug!("Workspace copy already tracked: {} | Profile: {}", rel_path_str, profile_name);
+            return Ok(None);
+        }
         
         // Check if file is already a hardlink to the correct blob
         let expected_blob_path = cache.get_blob_path(&current_hash);
-        if expected_blob_path.exists() {
-            // Check if this file is already hardlinked to the correct blob
-            if let Ok(_file_metadata) = fs::metadata(file_path) {
-                if let Ok(_blob_metadata) = fs::metadata(&expected_blob_path) {
-                    // On Windows, compare file indexes to detect hardlinks using Windows API
-                    #[cfg(windows)]
-                    {
-                        if are_files_hardlinked(file_path, &expected_blob_path) {
-                            debug!("File already normalized: {} | {} | Profile: {}", 
-                                   rel_path_str, 
-                                   current_hash.to_hex()[..8].to_string(),
-                                   profile_name);
-                            
-                            // Ensure reference exists (in case index was corrupted)
-                            let blob_path = crate::blob_cache::BlobPath {
-                                hash: current_hash,
-                                path: expected_blob_path,
-                            };
-                            cache.add_ref(&blob_path, profile_name, &rel_path_str)?;
-                            return Ok(());
-                        }
-                    }
-                    
-                    // On Unix-like systems, compare inodes
-                    #[cfg(unix)]
-                    {
-                        use std::os::unix::fs::MetadataExt;
-                        if file_metadata.ino() == blob_metadata.ino() {
-                            debug!("File already normalized: {} | {} | Profile: {}", 
-                                   rel_path_str, 
-                                   current_hash.to_hex()[..8].to_string(),
-                                   profile_name);
-                            
-                            // Ensure reference exists (in case index was corrupted)
-                            let blob_path = crate::blob_cache::BlobPath {
-                                hash: current_hash,
-                                path: expected_blob_path,
-                            };
-                            cache.add_ref(&blob_path, profile_name, &rel_path_str)?;
-                            return Ok(());
-                        }
-                    }
-                }
-            }
+        if expected_blob_path.exists() && are_files_hardlinked(file_path, &expected_blob_path) {
+            debug!("File already normalized: {} | {} | Profile: {}", 
+                   rel_path_str, 
+                   current_hash.to_hex()[..8].to_string(),
+                   profile_name);
+            
+            // Ensure reference exists (in case index was corrupted)
+            let blob_path = crate::blob_cache::BlobPath {
+                hash: current_hash,
+                path: expected_blob_path,
+            };
+            cache.add_ref(&blob_path, profile_name, &rel_path_str)?;
+            return Ok(None);
         }
 
-        // File needs normalization - ensure blob exists in cache
-        let blob_path = cache.ensure_blob(file_path)?;
+        // File needs normalization - reuse the cached blob, or store new content in a single pass
+        let blob_path = if expected_blob_path.exists() {
+            crate::blob_cache::BlobPath {
+                hash: current_hash,
+                path: expected_blob_path,
+            }
+        } else {
+            cache.ensure_blob_streaming(file_path)?
+        };
         let new_hash = blob_path.hash;
 
         // Remove any existing reference for this profile+rel_path combination
@@ -495,31 +1321,45 @@ impl WorkspaceWatcher {
             }
         }
 
-        // Add reference for this profile with the new blob
-        cache.add_ref(&blob_path, profile_name, &rel_path_str)?;
+        // Add reference for this profile with the new blob (a copy keeps its own size/mtime)
+        if keep_copy {
+            cache.add_ref_for_file(&blob_path, profile_name, &rel_path_str, file_path)?;
+        } else {
+            cache.add_ref(&blob_path, profile_name, &rel_path_str)?;
+        }
 
-        // Replace file with hardlink to blob
-        fs::remove_file(file_path)?;
-        cache.link_blob_to(file_path, &blob_path)?;
+        // Replace file with hardlink to blob (copy mode leaves the writable file alone)
+        if !keep_copy {
+            let original_mtime = if cache.preserve_mtimes { Some(fs::metadata(file_path)?.modified()?) } else { None };
+            fs::remove_file(file_path)?;
+            cache.link_blob_to(file_path, &blob_path)?;
+            // The link shares the blob's metadata, so this sets the blob's mtime too
+            if let Some(mtime) = original_mtime {
+                BlobCache::set_mtime(file_path, mtime)?;
+            }
+        }
 
         info!("File normalized: {} | {} | Profile: {}", 
               rel_path_str, 
               new_hash.to_hex()[..8].to_string(),
               profile_name);
 
-        Ok(())
+        Ok(Some(new_hash))
     }
 
     /// Handle file deletion: remove reference, no tombstones
+    /// Returns the hash of the blob the file referenced, if it had a reference
     fn handle_file_deletion(
         file_path: &Path,
         profile_name: &str,
         workspace_path: &Path,
         cache: &BlobCache,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Get relative path within workspace
-        let rel_path = file_path.strip_prefix(workspace_path)?;
-        let rel_path_str = rel_path.to_string_lossy().to_string();
+    ) -> Result<Option<blake3::Hash>, Box<dyn std::error::Error>> {
+        // Get relative path within workspace (names that aren't valid Unicode were never referenced)
+        let rel_path = Self::workspace_rel_path(file_path, workspace_path)?;
+        let Some(rel_path_str) = rel_path_key(&rel_path) else {
+            return Ok(None);
+        };
 
         // Remove any existing reference for this profile+rel_path and handle cleanup
         match cache.remove_existing_ref(profile_name, &rel_path_str) {
@@ -528,17 +1368,77 @@ impl WorkspaceWatcher {
                       rel_path_str, 
                       old_hash.to_hex()[..8].to_string(),
                       profile_name);
+                Ok(Some(old_hash))
             }
             Ok(None) => {
                 // No reference found - this is fine, file might not have been normalized yet
                 debug!("No blob reference found for deleted file: {} | Profile: {}", rel_path_str, profile_name);
+                Ok(None)
             }
             Err(e) => {
                 warn!("Failed to remove blob reference for deleted file {}: {}", rel_path_str, e);
+                Ok(None)
             }
         }
-        
-        Ok(())
+    }
+
+    /// Replace each workspace file hardlinked to its blob with an independent copy (the inverse of normalization)
+    /// References are kept, so the content stays cached. Returns the number of files rehydrated
+    pub fn rehydrate_workspace(
+        profile_name: &str,
+        workspace_path: &Path,
+        cache: &BlobCache,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let index = cache.load_index()?;
+        let mut rehydrated = 0;
+
+        for (hash_str, refs) in &index.refs {
+            let blob_path = crate::blob_cache::BlobPath {
+                hash: blake3::Hash::from_hex(hash_str)?,
+                path: cache.get_blob_path_from_hash(hash_str)?,
+            };
+
+            for blob_ref in refs.iter().filter(|blob_ref| cache.local_profile(&blob_ref.profile) == Some(profile_name)) {
+                let file_path = workspace_path.join(&blob_ref.rel_path);
+                if !are_files_hardlinked(&file_path, &blob_path.path) {
+                    continue;
+                }
+
+                // Copy to a temp file and rename over the link, so the file is never missing
+                cache.copy_blob_to(&file_path, &blob_path)?;
+                debug!("Rehydrated workspace file: {} | Profile: {}", blob_ref.rel_path, profile_name);
+                rehydrated += 1;
+            }
+        }
+
+        info!("Rehydrated {} workspace files | Profile: {}", rehydrated, profile_name);
+        Ok(rehydrated)
+    }
+
+    /// Report whether a workspace file is hardlinked to its blob and referenced in the index (read-only)
+    /// `rel_path` may use either separator
+    pub fn normalization_status(
+        profile_name: &str,
+        workspace_path: &Path,
+        rel_path: &str,
+        cache: &BlobCache,
+    ) -> Result<NormalizationStatus, Box<dyn std::error::Error>> {
+        // References use forward slashes, which also join correctly on Windows
+        let rel_path = rel_path.replace('\\', "/");
+        let file_path = workspace_path.join(&rel_path);
+        let blob_hash = cache.find_blob_hash_for_file(profile_name, &rel_path)?;
+
+        let is_hardlinked = match &blob_hash {
+            Some(hash_str) => are_files_hardlinked(&file_path, &cache.get_blob_path_from_hash(hash_str)?),
+            None => false,
+        };
+
+        Ok(NormalizationStatus {
+            exists: file_path.is_file(),
+            is_hardlinked,
+            has_reference: blob_hash.is_some(),
+            blob_hash,
+        })
     }
 
     /// Find blob hash by searching for a specific profile and relative path reference
@@ -548,11 +1448,12 @@ impl WorkspaceWatcher {
         rel_path: &str,
     ) -> Result<blake3::Hash, Box<dyn std::error::Error>> {
         let index = cache.load_index()?;
+        let rel_path = rel_path.replace('\\', "/");
         
         // Search through all blob references to find matching profile + path
         for (hash_str, refs) in &index.refs {
             for blob_ref in refs {
-                if blob_ref.profile == profile_name && blob_ref.rel_path == rel_path {
+                if cache.local_profile(&blob_ref.profile) == Some(profile_name) && blob_ref.rel_path == rel_path {
                     // Parse the hash string back to Hash using blake3's from_hex
                     match blake3::Hash::from_hex(hash_str) {
                         Ok(hash) => return Ok(hash),
@@ -568,6 +1469,44 @@ impl WorkspaceWatcher {
         Err("No blob reference found for the given profile and path".into())
     }
 
+    /// Tell the UI the workspace is being polled, which reacts slower and costs more CPU
+    fn send_fallback_warning(&self, error: &str) {
+        if let Some(app) = &self.app_handle {
+            let fallback = WatcherFallback {
+                profile_name: self.profile_name.clone(),
+                error: error.to_string(),
+                poll_interval_ms: self.poll_interval.as_millis() as u64,
+            };
+            if let Err(e) = app.emit("watcher-fallback", &fallback) {
+                warn!("Failed to send watcher fallback warning: {}", e);
+            }
+        }
+    }
+
+    /// Tell the UI the watchdog restarted the watcher or gave up on it
+    fn send_restart_event(&self, event: &str, attempt: u32, error: Option<String>) {
+        if let Some(app) = &self.app_handle {
+            let restart = WatcherRestart {
+                profile_name: self.profile_name.clone(),
+                attempt,
+                max_restarts: self.max_restarts,
+                error,
+            };
+            if let Err(e) = app.emit(event, &restart) {
+                warn!("Failed to send watcher restart notification: {}", e);
+            }
+        }
+    }
+
+    /// Tell the UI a file was quarantined, so the user can resolve the problem and retry it
+    fn send_quarantine_notification(app_handle: &Option<tauri::AppHandle>, quarantined: &QuarantinedFile) {
+        if let Some(app) = app_handle {
+            if let Err(e) = app.emit("file-quarantined", quarantined) {
+                warn!("Failed to send quarantine notification: {}", e);
+            }
+        }
+    }
+
     /// Send toast notification to UI
     fn send_toast_notification(app_handle: &Option<tauri::AppHandle>, count: usize) {
         if let Some(app) = app_handle {
@@ -599,11 +1538,621 @@ mod tests {
         let watcher = WorkspaceWatcher::new(
             "test_profile".to_string(),
             workspace_path,
+            temp_dir.path().join("cache"),
         );
 
         assert!(watcher.is_ok());
     }
 
+    #[test]
+    fn test_watcher_uses_provided_cache_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        let cache_dir = temp_dir.path().join("custom_cache");
+        fs::create_dir_all(&workspace_path).unwrap();
+
+        let watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), cache_dir.clone()).unwrap();
+        assert_eq!(watcher.cache.cache_dir, cache_dir);
+
+        // Normalized blobs land in the provided cache
+        let test_file = workspace_path.join("mod.txt");
+        fs::write(&test_file, b"mod content").unwrap();
+        WorkspaceWatcher::normalize_file(&test_file, "test_profile", &workspace_path, &watcher.cache, false).unwrap();
+
+        let blob_path = watcher.cache.get_blob_path(&BlobCache::hash_file(&test_file).unwrap());
+        assert!(blob_path.starts_with(&cache_dir));
+        assert!(blob_path.exists());
+    }
+
+    #[test]
+    fn test_watcher_status_lifecycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path, temp_dir.path().join("cache")).unwrap();
+        assert!(!watcher.status().running);
+
+        watcher.start_watching().unwrap();
+        let status = watcher.status();
+        assert!(status.running);
+        assert!(status.started_at.is_some());
+        assert!(status.last_error.is_none());
+        assert_eq!(status.backend, Some(WatcherBackend::Native));
+
+        watcher.stop_watching();
+        assert!(!watcher.status().running);
+        assert!(watcher.status().backend.is_none());
+
+        // A watcher on a missing directory fails to start and records why
+        let mut missing = WorkspaceWatcher::new("test_profile".to_string(), temp_dir.path().join("missing"), temp_dir.path().join("cache")).unwrap();
+        assert!(missing.start_watching().is_err());
+        assert!(!missing.status().running);
+        assert!(missing.status().last_error.is_some());
+    }
+
+    #[test]
+    fn test_watchdog_restarts_a_dead_watcher_up_to_the_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path, temp_dir.path().join("cache")).unwrap();
+        watcher.set_max_restarts(1);
+        watcher.set_restart_backoff(Duration::ZERO);
+
+        // Not watching yet, so there's nothing to restart
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Healthy);
+        watcher.start_watching().unwrap();
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Healthy);
+
+        // The backend dies: its channel disconnects and the debounce thread exits
+        let kill_backend = |watcher: &mut WorkspaceWatcher| {
+            watcher.watcher = None;
+            watcher.event_sender = None;
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !watcher.debounce_thread.as_ref().unwrap().is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            assert!(!watcher.status().running);
+        };
+
+        kill_backend(&mut watcher);
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Restarted);
+        let status = watcher.status();
+        assert!(status.running);
+        assert_eq!(status.restarts, 1);
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Healthy);
+
+        kill_backend(&mut watcher);
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Exhausted);
+        assert!(!watcher.status().running);
+        assert!(watcher.status().last_error.is_some());
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Healthy);
+
+        // Starting explicitly again resets the restart budget, and stopping isn't mistaken for a death
+        watcher.start_watching().unwrap();
+        assert_eq!(watcher.status().restarts, 0);
+        watcher.stop_watching();
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Healthy);
+    }
+
+    #[test]
+    fn test_fatal_watch_error_stops_the_watcher_and_restarts_back_off() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path, temp_dir.path().join("cache")).unwrap();
+        watcher.set_restart_backoff(Duration::from_secs(60));
+        watcher.start_watching().unwrap();
+
+        let wait_for_exit = |watcher: &WorkspaceWatcher| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !watcher.debounce_thread.as_ref().unwrap().is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+        };
+
+        // A one-off error is recorded but the watch carries on
+        watcher.event_sender.as_ref().unwrap().send(Err(notify::Error::generic("missed event"))).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        assert!(!watcher.debounce_thread.as_ref().unwrap().is_finished());
+        assert!(watcher.status().running);
+
+        // The watched path going away ends the watch, though the backend still holds the channel
+        watcher.event_sender.as_ref().unwrap().send(Err(notify::Error::path_not_found())).unwrap();
+        wait_for_exit(&watcher);
+        assert!(!watcher.status().running);
+        assert_eq!(watcher.supervise(), WatchdogOutcome::Restarted);
+        assert!(watcher.status().running);
+
+        // Dying again right after a restart waits out the backoff instead of spending the next attempt
+        watcher.event_sender.as_ref().unwrap().send(Err(notify::Error::path_not_found())).unwrap();
+        wait_for_exit(&watcher);
+        assert_eq!(watcher.supervise(), WatchdogOutcome::BackingOff);
+        assert_eq!(watcher.status().restarts, 1);
+    }
+
+    #[test]
+    fn test_clear_stops_in_flight_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        fs::write(workspace_path.join("a.txt"), "a").unwrap();
+        fs::write(workspace_path.join("b.txt"), "b").unwrap();
+
+        let watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), temp_dir.path().join("cache")).unwrap();
+        let changes: Vec<FileChangeEvent> = ["a.txt", "b.txt"].iter()
+            .map(|name| FileChangeEvent {
+                path: workspace_path.join(name),
+                kind: FileChangeKind::Created,
+                timestamp: Instant::now(),
+            })
+            .collect();
+
+        // A cleared batch normalizes nothing
+        watcher.clear_pending_normalization();
+        let normalized = WorkspaceWatcher::process_file_changes(&changes, &watcher.debounce_config());
+        assert_eq!(normalized, 0);
+        assert_eq!(watcher.status().events_processed, 0);
+        assert!(watcher.cache.find_blob_hash_for_file("test_profile", "a.txt").unwrap().is_none());
+
+        // Once the request is consumed, batches are processed normally
+        watcher.clear_requested.store(false, Ordering::SeqCst);
+        let normalized = WorkspaceWatcher::process_file_changes(&changes, &watcher.debounce_config());
+        assert_eq!(normalized, 2);
+        assert_eq!(watcher.status().events_processed, 2);
+    }
+
+    fn same_file(path1: &Path, path2: &Path) -> bool {
+        #[cfg(windows)]
+        return are_files_hardlinked(path1, path2);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            fs::metadata(path1).unwrap().ino() == fs::metadata(path2).unwrap().ino()
+        }
+    }
+
+    #[test]
+    fn test_normalize_link_and_copy_modes() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        // Hardlink mode replaces the workspace file with a link to its blob
+        let linked = workspace_path.join("linked.txt");
+        fs::write(&linked, b"linked").unwrap();
+        WorkspaceWatcher::normalize_file(&linked, "test_profile", &workspace_path, &cache, false).unwrap();
+        let linked_blob = cache.get_blob_path(&BlobCache::hash_file(&linked).unwrap());
+        assert!(same_file(&linked, &linked_blob));
+
+        // Copy mode records the blob and reference but leaves a separate, writable file
+        let copied = workspace_path.join("copied.txt");
+        fs::write(&copied, b"copied").unwrap();
+        WorkspaceWatcher::normalize_file(&copied, "test_profile", &workspace_path, &cache, true).unwrap();
+        let hash = BlobCache::hash_file(&copied).unwrap();
+        let copied_blob = cache.get_blob_path(&hash);
+        assert!(copied_blob.exists());
+        assert!(!same_file(&copied, &copied_blob));
+        assert_eq!(cache.find_blob_hash_for_file("test_profile", "copied.txt").unwrap(), Some(hash.to_hex().to_string()));
+
+        // Re-normalizing unchanged content keeps the blob
+        WorkspaceWatcher::normalize_file(&copied, "test_profile", &workspace_path, &cache, true).unwrap();
+        assert!(copied_blob.exists());
+
+        // Editing the copy never touches the blob; the new content gets its own blob
+        fs::write(&copied, b"edited").unwrap();
+        WorkspaceWatcher::normalize_file(&copied, "test_profile", &workspace_path, &cache, true).unwrap();
+        let edited_hash = BlobCache::hash_file(&copied).unwrap();
+        assert!(cache.get_blob_path(&edited_hash).exists());
+        assert!(!copied_blob.exists());
+        assert_eq!(cache.find_blob_hash_for_file("test_profile", "copied.txt").unwrap(), Some(edited_hash.to_hex().to_string()));
+    }
+
+    #[test]
+    fn test_normalization_can_preserve_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache")).with_preserve_mtimes(true);
+        let original_mtime = std::time::UNIX_EPOCH + Duration::from_secs(1_100_000_000);
+
+        let file = workspace_path.join("handling.cfg");
+        fs::write(&file, b"handling").unwrap();
+        BlobCache::set_mtime(&file, original_mtime).unwrap();
+        assert!(WorkspaceWatcher::normalize_file(&file, "test_profile", &workspace_path, &cache, false).unwrap().is_some());
+
+        // The link and its blob both carry the file's original mtime
+        let blob = cache.get_blob_path(&BlobCache::hash_file(&file).unwrap());
+        assert!(are_files_hardlinked(&file, &blob));
+        assert_eq!(fs::metadata(&file).unwrap().modified().unwrap(), original_mtime);
+
+        // Copies of the blob (runtime copy extensions) keep it too
+        let copy = temp_dir.path().join("runtime/handling.cfg");
+        cache.copy_blob_to(&copy, &crate::blob_cache::BlobPath { hash: BlobCache::hash_file(&file).unwrap(), path: blob }).unwrap();
+        assert_eq!(fs::metadata(&copy).unwrap().modified().unwrap(), original_mtime);
+    }
+
+    #[test]
+    fn test_normalize_all_without_watching() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(workspace_path.join("modloader")).unwrap();
+        fs::write(workspace_path.join("modloader/mod.asi"), b"mod").unwrap();
+        fs::write(workspace_path.join("gta.dat"), b"dat").unwrap();
+        fs::write(workspace_path.join("modloader.log"), b"log").unwrap();
+
+        // Never started, as when neither the native nor the polling backend could be created
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), temp_dir.path().join("cache")).unwrap();
+        watcher.set_protected_globs(&["*.log".to_string()]).unwrap();
+        assert_eq!(watcher.normalize_all(), 2);
+
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        for rel_path in ["modloader/mod.asi", "gta.dat"] {
+            let blob = cache.get_blob_path(&BlobCache::hash_file(workspace_path.join(rel_path)).unwrap());
+            assert!(same_file(&workspace_path.join(rel_path), &blob));
+        }
+        assert_eq!(cache.find_blob_hash_for_file("test_profile", "modloader.log").unwrap(), None);
+    }
+
+    #[test]
+    fn test_repeatedly_failing_files_are_quarantined() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        fs::write(workspace_path.join("locked.img"), b"img").unwrap();
+
+        // A file where the cache directory should be makes every attempt to store a blob fail
+        let cache_dir = temp_dir.path().join("cache");
+        fs::write(&cache_dir, b"not a directory").unwrap();
+
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), cache_dir.clone()).unwrap();
+        watcher.set_quarantine_threshold(3);
+        for _ in 0..3 {
+            assert_eq!(watcher.normalize_all(), 0);
+        }
+        let quarantined = watcher.quarantined_files();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].rel_path, "locked.img");
+        assert_eq!(quarantined[0].failures, 3);
+
+        // No longer retried, and still quarantined after a restart
+        let events = watcher.status().events_processed;
+        assert_eq!(watcher.normalize_all(), 0);
+        assert_eq!(watcher.status().events_processed, events + 1);
+        let restarted = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), cache_dir.clone()).unwrap();
+        assert_eq!(restarted.quarantined_files().len(), 1);
+        assert!(temp_dir.path().join(QUARANTINE_FILE).exists());
+
+        // Once the problem is resolved, unquarantining through any watcher of the workspace retries the file,
+        // and the others stop skipping it instead of saving it back into the list
+        fs::remove_file(&cache_dir).unwrap();
+        assert!(restarted.detached().unquarantine_file("locked.img"));
+        assert!(watcher.quarantined_files().is_empty());
+        assert_eq!(watcher.normalize_all(), 1);
+        let blob = BlobCache::new(cache_dir).get_blob_path(&BlobCache::hash_file(workspace_path.join("locked.img")).unwrap());
+        assert!(same_file(&workspace_path.join("locked.img"), &blob));
+        assert!(!watcher.unquarantine_file("locked.img"));
+    }
+
+    #[test]
+    fn test_recent_activity_lists_changes_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), temp_dir.path().join("cache")).unwrap();
+        let change = |name: &str, kind: FileChangeKind| FileChangeEvent {
+            path: workspace_path.join(name),
+            kind,
+            timestamp: Instant::now(),
+        };
+        let process = |changes: &[FileChangeEvent]| {
+            WorkspaceWatcher::process_file_changes(changes, &watcher.debounce_config())
+        };
+
+        for name in ["a.asi", "b.asi", "c.asi"] {
+            fs::write(workspace_path.join(name), name).unwrap();
+        }
+        process(&[change("a.asi", FileChangeKind::Created), change("b.asi", FileChangeKind::Created), change("c.asi", FileChangeKind::Created)]);
+        fs::remove_file(workspace_path.join("a.asi")).unwrap();
+        process(&[change("a.asi", FileChangeKind::Deleted)]);
+
+        // Already normalized files aren't activity
+        process(&[change("b.asi", FileChangeKind::Modified)]);
+
+        let activity = watcher.recent_activity(10);
+        let summary: Vec<(&str, ActivityKind)> = activity.iter().map(|entry| (entry.rel_path.as_str(), entry.kind)).collect();
+        assert_eq!(summary, vec![
+            ("a.asi", ActivityKind::Deleted),
+            ("c.asi", ActivityKind::Normalized),
+            ("b.asi", ActivityKind::Normalized),
+            ("a.asi", ActivityKind::Normalized),
+        ]);
+        assert_eq!(activity[0].hash, activity[3].hash);
+        assert_eq!(watcher.recent_activity(2).len(), 2);
+
+        // Bounded: the oldest entries fall off
+        for i in 0..ACTIVITY_CAPACITY {
+            WorkspaceWatcher::record_activity(&watcher.activity, &format!("{}.asi", i), ActivityKind::Normalized, None);
+        }
+        let activity = watcher.recent_activity(usize::MAX);
+        assert_eq!(activity.len(), ACTIVITY_CAPACITY);
+        assert_eq!(activity.last().unwrap().rel_path, "0.asi");
+    }
+
+    #[test]
+    fn test_non_ascii_names_are_found_by_the_planner() {
+        use crate::profiles::ProfileManager;
+        use crate::runtime_planner::{RuntimePlanner, RuntimeSource};
+        use crate::settings::Settings;
+
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(base_dir.join(Settings::GAME_EXECUTABLE), "game").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        let cache = settings.blob_cache();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        // A CJK name and a name spelled with a combining accent
+        let names = [Path::new("模组").join("车辆.asi"), Path::new("données").join("cafe\u{301}.txt")];
+        for name in &names {
+            let file = profile.workspace_dir.join(name);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, name.to_string_lossy().as_bytes()).unwrap();
+            WorkspaceWatcher::normalize_file(&file, "test", &profile.workspace_dir, &cache, false).unwrap();
+        }
+
+        // Each planned blob is found through the index rather than re-hashed
+        let plan = RuntimePlanner::new(settings).compute_plan("test").unwrap();
+        assert_eq!(plan.blob_files, 2);
+        for entry in &plan.entries {
+            if let RuntimeSource::Blob(hash) = &entry.source {
+                assert_eq!(cache.find_blob_hash_for_file("test", &entry.rel_path).unwrap().as_ref(), Some(hash));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_references_match_planner_paths() {
+        use crate::profiles::ProfileManager;
+        use crate::runtime_planner::{RuntimePlanner, RuntimeSource};
+        use crate::settings::Settings;
+
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let data_root = temp_dir.path().join("DeltaRuntime");
+        fs::create_dir_all(base_dir.join("data").join("maps")).unwrap();
+        fs::write(base_dir.join("data").join("maps").join("city.ipl"), "base").unwrap();
+        let settings = Settings::for_wizard(base_dir, data_root.clone());
+        let cache = settings.blob_cache();
+        let profile = ProfileManager::new(data_root.join("profiles"))
+            .create_profile("test".to_string())
+            .unwrap();
+
+        let file = profile.workspace_dir.join("data").join("maps").join("city.ipl");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "modded").unwrap();
+        WorkspaceWatcher::normalize_file(&file, "test", &profile.workspace_dir, &cache, false).unwrap();
+
+        // The watcher records the same forward-slash path the planner looks up
+        let hash = BlobCache::hash_file(&file).unwrap().to_hex().to_string();
+        assert_eq!(cache.find_blob_hash_for_file("test", "data/maps/city.ipl").unwrap(), Some(hash.clone()));
+
+        let plan = RuntimePlanner::new(settings).compute_plan("test").unwrap();
+        let entry = plan.entries.iter().find(|entry| entry.rel_path == "data/maps/city.ipl").unwrap();
+        assert!(entry.is_override);
+        assert!(matches!(&entry.source, RuntimeSource::Blob(planned) if *planned == hash));
+    }
+
+    #[test]
+    fn test_long_path_prefixed_events_resolve_relative_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+        let file = workspace_path.join("data").join("maps").join("city.ipl");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "modded").unwrap();
+        let long_form = |path: &Path| PathBuf::from(format!(r"\\?\{}", path.display()));
+
+        // Either side may carry the prefix
+        let expected = Path::new("data").join("maps").join("city.ipl");
+        assert_eq!(WorkspaceWatcher::workspace_rel_path(&long_form(&file), &workspace_path).unwrap(), expected);
+        assert_eq!(WorkspaceWatcher::workspace_rel_path(&file, &long_form(&workspace_path)).unwrap(), expected);
+        assert!(WorkspaceWatcher::workspace_rel_path(&temp_dir.path().join("elsewhere"), &workspace_path).is_err());
+
+        // A deletion reported in long-path form drops the reference under the plain relative path
+        WorkspaceWatcher::normalize_file(&file, "test", &workspace_path, &cache, false).unwrap();
+        assert!(cache.find_blob_hash_for_file("test", "data/maps/city.ipl").unwrap().is_some());
+        WorkspaceWatcher::handle_file_deletion(&long_form(&file), "test", &workspace_path, &cache).unwrap();
+        assert!(cache.find_blob_hash_for_file("test", "data/maps/city.ipl").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rehydrate_breaks_hardlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        let file = workspace_path.join("mod.asi");
+        fs::write(&file, b"mod").unwrap();
+        WorkspaceWatcher::normalize_file(&file, "test_profile", &workspace_path, &cache, false).unwrap();
+        let hash = BlobCache::hash_file(&file).unwrap();
+        let blob = cache.get_blob_path(&hash);
+        assert!(same_file(&file, &blob));
+
+        let rehydrated = WorkspaceWatcher::rehydrate_workspace("test_profile", &workspace_path, &cache).unwrap();
+        assert_eq!(rehydrated, 1);
+        assert!(!same_file(&file, &blob));
+        assert_eq!(fs::read(&file).unwrap(), b"mod");
+
+        // The content stays cached and referenced
+        assert!(blob.exists());
+        assert_eq!(cache.find_blob_hash_for_file("test_profile", "mod.asi").unwrap(), Some(hash.to_hex().to_string()));
+
+        // Already rehydrated files are left alone
+        assert_eq!(WorkspaceWatcher::rehydrate_workspace("test_profile", &workspace_path, &cache).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_normalization_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(workspace_path.join("data")).unwrap();
+        let cache = BlobCache::new(temp_dir.path().join("cache"));
+
+        let file = workspace_path.join("data").join("handling.cfg");
+        fs::write(&file, b"handling").unwrap();
+
+        // Present but not normalized yet
+        let status = WorkspaceWatcher::normalization_status("test_profile", &workspace_path, "data/handling.cfg", &cache).unwrap();
+        assert!(status.exists && !status.is_hardlinked && !status.has_reference);
+
+        WorkspaceWatcher::normalize_file(&file, "test_profile", &workspace_path, &cache, false).unwrap();
+        let status = WorkspaceWatcher::normalization_status("test_profile", &workspace_path, "data\\handling.cfg", &cache).unwrap();
+        assert!(status.exists && status.is_hardlinked && status.has_reference);
+        assert_eq!(status.blob_hash, Some(BlobCache::hash_file(&file).unwrap().to_hex().to_string()));
+
+        // Normalized file whose reference went missing
+        cache.remove_existing_ref("test_profile", &format!("data{}handling.cfg", std::path::MAIN_SEPARATOR)).unwrap();
+        let status = WorkspaceWatcher::normalization_status("test_profile", &workspace_path, "data/handling.cfg", &cache).unwrap();
+        assert!(status.exists && !status.has_reference && status.blob_hash.is_none());
+    }
+
+    #[test]
+    fn test_protected_files_are_not_queued() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(workspace_path.join("data")).unwrap();
+        fs::write(workspace_path.join("modloader.log"), "log").unwrap();
+        fs::write(workspace_path.join("data/handling.cfg"), "cfg").unwrap();
+
+        let protected_globs = compile_globs(&["*.log".to_string()]).unwrap();
+        let mut pending_changes = HashMap::new();
+        let event = notify::Event::new(EventKind::Create(notify::event::CreateKind::File))
+            .add_path(workspace_path.join("modloader.log"))
+            .add_path(workspace_path.join("data/handling.cfg"));
+
+        WorkspaceWatcher::process_notify_event(event, &workspace_path, false, &protected_globs, &WatchScope::default(), &mut pending_changes);
+
+        assert_eq!(pending_changes.len(), 1);
+        assert!(pending_changes.contains_key(&workspace_path.join("data/handling.cfg")));
+        assert!(!pending_changes.contains_key(&workspace_path.join("modloader.log")));
+    }
+
+    #[test]
+    fn test_events_outside_the_watch_scope_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(workspace_path.join("modloader/deep")).unwrap();
+        fs::create_dir_all(workspace_path.join("data")).unwrap();
+        let files = ["gta.dat", "modloader/mod.asi", "modloader/deep/nested.asi", "data/handling.cfg"];
+        for file in files {
+            fs::write(workspace_path.join(file), file).unwrap();
+        }
+        let queued = |watch_scope: &WatchScope| {
+            let mut pending_changes = HashMap::new();
+            let mut event = notify::Event::new(EventKind::Create(notify::event::CreateKind::File));
+            for file in files {
+                event = event.add_path(workspace_path.join(file));
+            }
+            WorkspaceWatcher::process_notify_event(event, &workspace_path, false, &[], watch_scope, &mut pending_changes);
+            let mut queued: Vec<PathBuf> = pending_changes.into_keys().collect();
+            queued.sort();
+            queued
+        };
+
+        assert_eq!(queued(&WatchScope::default()).len(), 4);
+
+        let recursive = WatchScope { roots: vec![PathBuf::from("modloader")], recursive: true };
+        assert_eq!(queued(&recursive), vec![workspace_path.join("modloader/deep/nested.asi"), workspace_path.join("modloader/mod.asi")]);
+
+        let flat = WatchScope { roots: vec![PathBuf::from("modloader")], recursive: false };
+        assert_eq!(queued(&flat), vec![workspace_path.join("modloader/mod.asi")]);
+
+        let top_level = WatchScope { roots: Vec::new(), recursive: false };
+        assert_eq!(queued(&top_level), vec![workspace_path.join("gta.dat")]);
+
+        // Only the selected roots are registered
+        let mut watcher = WorkspaceWatcher::new("test_profile".to_string(), workspace_path.clone(), temp_dir.path().join("cache")).unwrap();
+        watcher.set_watch_scope(WatchScope { roots: vec![PathBuf::from("modloader"), PathBuf::from("missing")], recursive: false });
+        watcher.start_watching().unwrap();
+        assert!(watcher.status().running);
+        watcher.stop_watching();
+    }
+
+    #[test]
+    fn test_profile_overrides_configure_only_that_profiles_watcher() {
+        use crate::profiles::ProfileManager;
+        use crate::settings::ProfileSettingsOverrides;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = Settings::new();
+        settings.data_root = temp_dir.path().to_path_buf();
+        settings.preferences.normalize_idle_seconds = 1;
+        let manager = ProfileManager::new(settings.data_root.join("profiles"));
+        manager.create_profile("archives".to_string()).unwrap();
+        let plain = manager.create_profile("plain".to_string()).unwrap();
+
+        let overrides = ProfileSettingsOverrides { normalize_idle_seconds: Some(30), ..Default::default() };
+        assert!(settings.validate_overrides(&overrides).is_valid());
+        manager.set_settings_overrides("archives", overrides).unwrap();
+        let archives = manager.get_profile("archives").unwrap().unwrap();
+
+        let watcher = WorkspaceWatcher::for_profile(&settings, &archives).unwrap();
+        assert_eq!(watcher.idle_threshold, Duration::from_secs(30));
+        let watcher = WorkspaceWatcher::for_profile(&settings, &plain).unwrap();
+        assert_eq!(watcher.idle_threshold, Duration::from_secs(1));
+
+        let escaping = ProfileSettingsOverrides { watch_roots: Some(vec!["../outside".to_string()]), ..Default::default() };
+        assert!(!settings.validate_overrides(&escaping).is_valid());
+    }
+
+    #[test]
+    fn test_rapidly_modified_files_wait_until_idle() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_path).unwrap();
+        let editing = workspace_path.join("handling.cfg");
+        let finished = workspace_path.join("gta.dat");
+        fs::write(&editing, "cfg").unwrap();
+        fs::write(&finished, "dat").unwrap();
+        let modify = |path: &Path| notify::Event::new(EventKind::Modify(notify::event::ModifyKind::Any)).add_path(path.to_path_buf());
+
+        let mut pending_changes = HashMap::new();
+        WorkspaceWatcher::process_notify_event(modify(&finished), &workspace_path, false, &[], &WatchScope::default(), &mut pending_changes);
+
+        // An editor keeps saving the same file; each save restarts its idle timer
+        let idle_threshold = Duration::from_millis(150);
+        for _ in 0..5 {
+            thread::sleep(Duration::from_millis(40));
+            WorkspaceWatcher::process_notify_event(modify(&editing), &workspace_path, false, &[], &WatchScope::default(), &mut pending_changes);
+        }
+        let ready = WorkspaceWatcher::take_idle_changes(&mut pending_changes, idle_threshold, Instant::now());
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].path, finished);
+        assert!(pending_changes.contains_key(&editing));
+
+        // Deletions don't wait
+        let delete = notify::Event::new(EventKind::Remove(notify::event::RemoveKind::File)).add_path(workspace_path.join("old.asi"));
+        WorkspaceWatcher::process_notify_event(delete, &workspace_path, false, &[], &WatchScope::default(), &mut pending_changes);
+        let ready = WorkspaceWatcher::take_idle_changes(&mut pending_changes, idle_threshold, Instant::now());
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].kind, FileChangeKind::Deleted);
+
+        // Once the edits stop, the file is normalized
+        let later = Instant::now() + idle_threshold;
+        let ready = WorkspaceWatcher::take_idle_changes(&mut pending_changes, idle_threshold, later);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].path, editing);
+        assert!(pending_changes.is_empty());
+    }
+
     #[test]
     fn test_file_change_event() {
         let event = FileChangeEvent {
@@ -628,6 +2177,7 @@ mod tests {
         let watcher = WorkspaceWatcher::new(
             "test_profile".to_string(),
             workspace_path.clone(),
+            cache_dir.clone(),
         );
         assert!(watcher.is_ok());
         
@@ -646,6 +2196,7 @@ mod tests {
             "test_profile",
             &workspace_path,
             cache,
+            false,
         );
         
         // Verify the file was processed successfully
@@ -669,6 +2220,7 @@ mod tests {
             "test_profile",
             &workspace_path,
             cache,
+            false,
         );
         assert!(normalize_result.is_ok());
         